@@ -0,0 +1,107 @@
+// Copyright (c) 2015, The Radare Project. All rights reserved.
+// See the COPYING file at the top-level directory of this distribution.
+// Licensed under the BSD 3-Clause License:
+// <http://opensource.org/licenses/BSD-3-Clause>
+// This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Computes the dominator and post-dominator trees of the basic blocks of the SSA.
+
+use middle::ssa::cfg_traits::CFG;
+use middle::ssa::ssastorage::SSAStorage;
+use petgraph::algo::dominators;
+use petgraph::graph::{Graph, NodeIndex};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default)]
+/// Dominator tree of the blocks of a function, see `dominator_tree`
+pub struct DominatorTree {
+    root: Option<NodeIndex>,
+    // Immediate dominator of every block reachable from the root, except the root itself
+    idom: HashMap<NodeIndex, NodeIndex>,
+}
+
+impl DominatorTree {
+    /// Block the tree is rooted at, the entry block of the function (the exit block for
+    /// post-dominators). `None` if the function has no SSA.
+    pub fn root(&self) -> Option<NodeIndex> {
+        self.root
+    }
+
+    /// Immediate dominator of `block`. `None` for the root and for blocks that are not
+    /// reachable from it.
+    pub fn idom(&self, block: NodeIndex) -> Option<NodeIndex> {
+        self.idom.get(&block).cloned()
+    }
+
+    /// Is `block` reachable from the root?
+    pub fn is_reachable(&self, block: NodeIndex) -> bool {
+        Some(block) == self.root || self.idom.contains_key(&block)
+    }
+
+    /// Does `a` dominate `b`? Every reachable block dominates itself. Unreachable blocks
+    /// neither dominate nor are dominated by any block.
+    pub fn dominates(&self, a: NodeIndex, b: NodeIndex) -> bool {
+        if !self.is_reachable(b) {
+            return false;
+        }
+        let mut n = b;
+        loop {
+            if n == a {
+                return true;
+            }
+            match self.idom.get(&n) {
+                Some(&d) => n = d,
+                None => return false,
+            }
+        }
+    }
+}
+
+/// Dominator tree of the CFG of `ssa`, rooted at the entry block
+pub fn dominator_tree(ssa: &SSAStorage) -> DominatorTree {
+    match ssa.entry_node() {
+        Some(entry) => build_tree(ssa, entry, false),
+        None => DominatorTree::default(),
+    }
+}
+
+/// Post-dominator tree of the CFG of `ssa`, that is the dominator tree of the reversed CFG
+/// rooted at the exit block. Blocks from which the exit cannot be reached are not part of
+/// the tree.
+pub fn post_dominator_tree(ssa: &SSAStorage) -> DominatorTree {
+    match ssa.exit_node() {
+        Some(exit) => build_tree(ssa, exit, true),
+        None => DominatorTree::default(),
+    }
+}
+
+// Dominator tree of the CFG of `ssa` rooted at `root`, with the edges reversed if `reversed`
+fn build_tree(ssa: &SSAStorage, root: NodeIndex, reversed: bool) -> DominatorTree {
+    // The SSA graph also holds the values, so the CFG is copied to a graph of its own.
+    let mut cfg = Graph::<NodeIndex, ()>::new();
+    let blocks = ssa.blocks();
+    let index = blocks.iter().map(|&b| (b, cfg.add_node(b))).collect::<HashMap<_, _>>();
+    for &b in &blocks {
+        for succ in ssa.succs_of(b) {
+            if let Some(&to) = index.get(&succ) {
+                if reversed {
+                    cfg.add_edge(to, index[&b], ());
+                } else {
+                    cfg.add_edge(index[&b], to, ());
+                }
+            }
+        }
+    }
+    let start = match index.get(&root) {
+        Some(&start) => start,
+        None => return DominatorTree::default(),
+    };
+    let doms = dominators::simple_fast(&cfg, start);
+    DominatorTree {
+        root: Some(root),
+        idom: cfg.node_indices()
+            .filter_map(|n| doms.immediate_dominator(n).map(|d| (cfg[n], cfg[d])))
+            .collect(),
+    }
+}
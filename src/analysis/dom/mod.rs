@@ -8,5 +8,6 @@
 /// Module for computing dominance and post-dominance information
 mod index;
 pub mod domtree;
+pub mod dominators;
 
 pub use self::domtree::DomTree;
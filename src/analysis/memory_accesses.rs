@@ -0,0 +1,63 @@
+// Copyright (c) 2015, The Radare Project. All rights reserved.
+// See the COPYING file at the top-level directory of this distribution.
+// Licensed under the BSD 3-Clause License:
+// <http://opensource.org/licenses/BSD-3-Clause>
+// This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Collects the memory loads and stores in the SSA IR.
+
+use middle::ir;
+use middle::ssa::ssa_traits::SSA;
+use middle::ssa::ssastorage::SSAStorage;
+use petgraph::graph::NodeIndex;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Kind of a `MemAccess`
+pub enum AccessKind {
+    Load,
+    Store,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Load or store in the SSA of a function, see `memory_accesses`
+pub struct MemAccess {
+    /// The `OpLoad` or `OpStore` node
+    pub node: NodeIndex,
+    pub kind: AccessKind,
+    /// Node for the accessed address
+    pub addr: NodeIndex,
+    /// Width in bits of the loaded or stored value, if known
+    pub width: Option<u16>,
+}
+
+/// Loads and stores in `ssa`, in order of address
+pub fn memory_accesses(ssa: &SSAStorage) -> Vec<MemAccess> {
+    let mut accesses = ssa.values()
+        .into_iter()
+        .filter_map(|n| {
+            let kind = match ssa.opcode(n) {
+                Some(ir::MOpcode::OpLoad) => AccessKind::Load,
+                Some(ir::MOpcode::OpStore) => AccessKind::Store,
+                _ => return None,
+            };
+            // Loads are (mem, addr) and stores (mem, addr, value), except for stores
+            // through `=`, which are (addr, value).
+            let ops = ssa.operands_of(n);
+            let (addr, value) = match (kind, ops.len()) {
+                (AccessKind::Load, 2) => (ops[1], n),
+                (AccessKind::Store, 3) => (ops[1], ops[2]),
+                (AccessKind::Store, 2) => (ops[0], ops[1]),
+                _ => return None,
+            };
+            Some(MemAccess {
+                node: n,
+                kind: kind,
+                addr: addr,
+                width: ssa.node_data(value).ok().and_then(|nd| nd.vt.width().get_width()),
+            })
+        })
+        .collect::<Vec<_>>();
+    accesses.sort_by_key(|access| (ssa.address(access.node), access.node));
+    accesses
+}
@@ -11,7 +11,10 @@
 // pub mod valueset;
 // pub mod propagate;
 pub mod dom;
+pub mod memory_accesses;
 pub mod sccp;
+pub mod stack;
+pub mod value_ranges;
 pub mod cse {
     pub mod cse;
     pub mod ssasort;
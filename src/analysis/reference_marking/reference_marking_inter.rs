@@ -121,6 +121,7 @@ impl<T: InterProcAnalysis> InterProceduralAnalyzer<T> {
                     map: csite.map.iter().map(|&(x, y)| (y, x)).collect(),
                     csite: 0,
                     csite_node: csite.csite_node,
+                    ..CallContextInfo::default()
                 };
                 (caller, T::pull(&mut current_analyzer, current_fn, &rcsite))
             }) {
@@ -441,6 +441,18 @@ impl<T> Analyzer<T>
         } // End of while-loop
     }
 
+    /// Nodes that are not constants themselves, but were found to always evaluate to a
+    /// constant. These are the nodes that `emit_ssa` replaces.
+    pub fn constants(&self) -> HashMap<T::ValueRef, u64> {
+        self.expr_val
+            .iter()
+            .filter_map(|(k, v)| match *v {
+                LatticeValue::Const(val) if self.g.constant(*k).is_none() => Some((*k, val)),
+                _ => None,
+            })
+            .collect()
+    }
+
     pub fn emit_ssa(&mut self) -> T {
         for (k, v) in &self.expr_val {
             if self.g.constant(*k).is_some() {
@@ -0,0 +1,111 @@
+// Copyright (c) 2015, The Radare Project. All rights reserved.
+// See the COPYING file at the top-level directory of this distribution.
+// Licensed under the BSD 3-Clause License:
+// <http://opensource.org/licenses/BSD-3-Clause>
+// This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Analyses of how a function uses the stack.
+
+use middle::ir;
+use middle::ssa::cfg_traits::CFG;
+use middle::ssa::ssa_traits::SSA;
+use middle::ssa::ssastorage::SSAStorage;
+use petgraph::graph::NodeIndex;
+use std::cmp;
+use std::collections::HashMap;
+
+// Names of the stack pointer register on the supported architectures
+const STACK_POINTERS: &'static [&'static str] = &["rsp", "esp", "sp"];
+
+// Offsets of the stack canary in the thread control block on x86_64 (`fs:[0x28]`) and x86
+// (`gs:[0x14]`)
+const CANARY_OFFSETS: &'static [u64] = &[0x28, 0x14];
+
+/// Estimate the size of the stack frame of the function of `ssa`, that is how far below its
+/// value at entry the stack pointer is moved by the function (pushes and `sub SP, imm` alike).
+/// Returns `None` if the SSA was not constructed or if the stack pointer is decremented by a
+/// non-constant amount (e.g. `alloca`), in which case the frame size is not static.
+pub fn frame_size(ssa: &SSAStorage) -> Option<u64> {
+    let sp = entry_stack_pointer(ssa)?;
+
+    // Propagate the offset from the entry stack pointer through constant additions and
+    // subtractions. Anything else (loads, phis, calls) stops the propagation.
+    let mut offsets = HashMap::new();
+    offsets.insert(sp, 0i64);
+    let mut worklist = vec![sp];
+    let mut depth = 0i64;
+    while let Some(node) = worklist.pop() {
+        let offset = offsets[&node];
+        for user in ssa.uses_of(node) {
+            if offsets.contains_key(&user) {
+                continue;
+            }
+            let operands = ssa.operands_of(user);
+            let imm = operands.iter().find(|&&o| o != node).and_then(|&o| ssa.constant(o));
+            let new_offset = match (ssa.opcode(user), imm) {
+                (Some(ir::MOpcode::OpAdd), Some(imm)) => offset.wrapping_add(imm as i64),
+                (Some(ir::MOpcode::OpSub), Some(imm)) if operands[0] == node => {
+                    offset.wrapping_sub(imm as i64)
+                }
+                (Some(ir::MOpcode::OpSub), None) if operands[0] == node => return None,
+                _ => continue,
+            };
+            depth = cmp::max(depth, -new_offset);
+            offsets.insert(user, new_offset);
+            worklist.push(user);
+        }
+    }
+    Some(depth as u64)
+}
+
+/// Value of the stack pointer at the entry of the function of `ssa`
+pub fn entry_stack_pointer(ssa: &SSAStorage) -> Option<NodeIndex> {
+    let entry_state = ssa.entry_node().and_then(|entry| ssa.registers_in(entry))?;
+    ssa.operands_of(entry_state)
+        .into_iter()
+        .find(|&n| ssa.comment(n).map_or(false, |c| STACK_POINTERS.contains(&c.as_str())))
+}
+
+/// Returns true if the function of `ssa` is instrumented with a stack canary: the canary is
+/// loaded from the thread control block (`fs:[0x28]` or `gs:[0x14]`) and stored on the stack
+/// in the prologue, and loaded again to be compared against the stored copy (usually by a
+/// `xor` or `sub`) before returning.
+pub fn has_canary(ssa: &SSAStorage) -> bool {
+    let is_canary_addr = |addr: NodeIndex| {
+        if let Some(c) = ssa.constant(addr) {
+            return CANARY_OFFSETS.contains(&c);
+        }
+        // The segment register may be a part of the address.
+        ssa.opcode(addr) == Some(ir::MOpcode::OpAdd) && {
+            let operands = ssa.operands_of(addr);
+            operands.iter()
+                .any(|&o| ssa.constant(o).map_or(false, |c| CANARY_OFFSETS.contains(&c))) &&
+            operands.iter()
+                .any(|&o| ssa.comment(o).map_or(false, |c| c == "fs" || c == "gs"))
+        }
+    };
+    let canary_loads = ssa.values()
+        .into_iter()
+        .filter(|&n| ssa.opcode(n) == Some(ir::MOpcode::OpLoad))
+        .filter(|&n| ssa.operands_of(n).get(1).map_or(false, |&addr| is_canary_addr(addr)))
+        .collect::<Vec<_>>();
+
+    let mut stored = false;
+    let mut checked = false;
+    for &load in &canary_loads {
+        for user in ssa.uses_of(load) {
+            match ssa.opcode(user) {
+                Some(ir::MOpcode::OpStore) => {
+                    stored |= ssa.operands_of(user).get(2) == Some(&load);
+                }
+                Some(ir::MOpcode::OpXor) |
+                Some(ir::MOpcode::OpSub) |
+                Some(ir::MOpcode::OpCmp) |
+                Some(ir::MOpcode::OpEq) => checked = true,
+                _ => {}
+            }
+        }
+    }
+    stored && checked
+}
@@ -0,0 +1,278 @@
+// Copyright (c) 2015, The Radare Project. All rights reserved.
+// See the COPYING file at the top-level directory of this distribution.
+// Licensed under the BSD 3-Clause License:
+// <http://opensource.org/licenses/BSD-3-Clause>
+// This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Value range analysis on the SSA IR.
+//!
+//! Finds the ranges of unsigned values that SSA values can take by abstract interpretation
+//! over the CFG, with widening of phis and narrowing of ranges by the outcome of comparisons
+//! with constants.
+
+use analysis::dom::dominators;
+use middle::ir;
+use middle::ssa::cfg_traits::CFG;
+use middle::ssa::ssa_traits::SSA;
+use middle::ssa::ssastorage::SSAStorage;
+use petgraph::graph::NodeIndex;
+use std::cmp;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Range of unsigned values an SSA value can take, see `value_ranges`
+pub struct ValueRange {
+    /// Smallest possible value
+    pub min: u64,
+    /// Largest possible value
+    pub max: u64,
+}
+
+impl ValueRange {
+    /// Range holding only `value`
+    pub fn constant(value: u64) -> ValueRange {
+        ValueRange {
+            min: value,
+            max: value,
+        }
+    }
+
+    /// Range holding all values of `width` bits
+    pub fn full(width: u16) -> ValueRange {
+        ValueRange {
+            min: 0,
+            max: width_mask(width),
+        }
+    }
+
+    /// Does the range hold `value`?
+    pub fn contains(&self, value: u64) -> bool {
+        self.min <= value && value <= self.max
+    }
+
+    /// Is `min` the only value in the range?
+    pub fn is_constant(&self) -> bool {
+        self.min == self.max
+    }
+
+    // Smallest range holding both ranges
+    fn union(&self, other: &ValueRange) -> ValueRange {
+        ValueRange {
+            min: cmp::min(self.min, other.min),
+            max: cmp::max(self.max, other.max),
+        }
+    }
+
+    // Range of `op` applied to `a` and, for binary operations, `b`, for a result of `width` bits
+    fn apply(op: &ir::MOpcode, a: ValueRange, b: ValueRange, width: u16) -> ValueRange {
+        let mask = width_mask(width);
+        let full = ValueRange::full(width);
+        let range = |min: Option<u64>, max: Option<u64>| match (min, max) {
+            (Some(min), Some(max)) if max <= mask => ValueRange { min: min, max: max },
+            _ => full,
+        };
+        // Smallest mask covering all bits of `v`
+        let bits = |v: u64| if v == 0 { 0 } else { u64::max_value() >> v.leading_zeros() };
+        match *op {
+            ir::MOpcode::OpAdd => range(a.min.checked_add(b.min), a.max.checked_add(b.max)),
+            ir::MOpcode::OpSub if a.min >= b.max => range(Some(a.min - b.max), Some(a.max - b.min)),
+            ir::MOpcode::OpMul => range(a.min.checked_mul(b.min), a.max.checked_mul(b.max)),
+            ir::MOpcode::OpDiv if b.min > 0 => range(Some(a.min / b.max), Some(a.max / b.min)),
+            ir::MOpcode::OpMod if b.min > 0 => range(Some(0), Some(cmp::min(a.max, b.max - 1))),
+            ir::MOpcode::OpAnd => range(Some(0), Some(cmp::min(a.max, b.max))),
+            ir::MOpcode::OpOr => {
+                range(Some(cmp::max(a.min, b.min)), Some(bits(cmp::max(a.max, b.max))))
+            }
+            ir::MOpcode::OpXor => range(Some(0), Some(bits(cmp::max(a.max, b.max)))),
+            ir::MOpcode::OpLsr if b.max < 64 => range(Some(a.min >> b.max), Some(a.max >> b.min)),
+            ir::MOpcode::OpLsl if b.max < 64 && a.max.leading_zeros() as u64 >= b.max => {
+                range(Some(a.min << b.min), Some(a.max << b.max))
+            }
+            ir::MOpcode::OpCmp | ir::MOpcode::OpEq | ir::MOpcode::OpLt | ir::MOpcode::OpGt => {
+                range(Some(0), Some(1))
+            }
+            ir::MOpcode::OpNarrow(_) |
+            ir::MOpcode::OpZeroExt(_) => range(Some(a.min), Some(a.max)),
+            _ => full,
+        }
+    }
+
+    // Narrow the range of `x` by the outcome `taken` of the comparison `x < c` or `x > c`
+    // (`gt`). Ranges that would become empty are left alone, as the path cannot be taken.
+    fn refine(&self, gt: bool, c: u64, taken: bool) -> ValueRange {
+        let (min, max) = match (gt, taken) {
+            (false, true) if c > 0 => (self.min, cmp::min(self.max, c - 1)),
+            (false, false) => (cmp::max(self.min, c), self.max),
+            (true, true) if c < u64::max_value() => (cmp::max(self.min, c + 1), self.max),
+            (true, false) => (self.min, cmp::min(self.max, c)),
+            _ => return *self,
+        };
+        if min <= max {
+            ValueRange { min: min, max: max }
+        } else {
+            *self
+        }
+    }
+}
+
+/// Conservative ranges of the unsigned values the values of `ssa` can take, for the values in
+/// blocks reachable from the entry. The ranges are found by interpreting the function over
+/// the CFG. Phis that keep growing are widened to the full range of their
+/// width, and then narrowed again. Values compared to constants with `<` or `>` are
+/// bounded accordingly in the blocks that are only entered on one outcome of the
+/// comparison. Values that are not understood get the full range of their width.
+pub fn value_ranges(ssa: &SSAStorage) -> HashMap<NodeIndex, ValueRange> {
+    // Number of times a phi may grow before it is widened
+    const WIDEN_AFTER: usize = 3;
+    // Number of passes spent narrowing down the widened ranges
+    const NARROW_PASSES: usize = 2;
+
+    let doms = dominators::dominator_tree(ssa);
+    let mut blocks =
+        ssa.blocks().into_iter().filter(|&b| doms.is_reachable(b)).collect::<Vec<_>>();
+    blocks.sort_by_key(|&b| ssa.starting_address(b));
+    let width = |n: NodeIndex| {
+        ssa.node_data(n).ok().and_then(|nd| nd.vt.width().get_width()).unwrap_or(64)
+    };
+
+    // Outcomes of comparisons, as (selector, taken), that are known in every block. They
+    // come from the blocks dominating it that are only entered from one side of a branch.
+    let conditions = blocks.iter()
+        .map(|&b| {
+            let mut known = Vec::new();
+            let mut cur = Some(b);
+            while let Some(block) = cur {
+                let preds = ssa.preds_of(block);
+                if preds.len() == 1 {
+                    let ci = ssa.conditional_blocks(preds[0]);
+                    if let (Some(ci), Some(sel)) = (ci, ssa.selector_in(preds[0])) {
+                        let sides = [ci.true_side, ci.false_side];
+                        if sides[0] != sides[1] && sides.contains(&block) {
+                            known.push((sel, block == ci.true_side));
+                        }
+                    }
+                }
+                cur = doms.idom(block);
+            }
+            (b, known)
+        })
+        .collect::<HashMap<_, _>>();
+
+    let mut ranges: HashMap<NodeIndex, ValueRange> = HashMap::new();
+    let mut grown: HashMap<NodeIndex, usize> = HashMap::new();
+    let mut narrow_passes = 0;
+    loop {
+        let narrowing = narrow_passes > 0;
+        let mut changed = false;
+        for &b in &blocks {
+            // Range of the operand `x` as used in `b`
+            let operand = |ranges: &HashMap<NodeIndex, ValueRange>, x: NodeIndex| {
+                let mut range = if ssa.is_phi(x) || ssa.is_expr(x) {
+                    *ranges.get(&x)?
+                } else {
+                    ValueRange::full(width(x))
+                };
+                for &(sel, taken) in &conditions[&b] {
+                    let gt = match ssa.opcode(sel) {
+                        Some(ir::MOpcode::OpLt) => false,
+                        Some(ir::MOpcode::OpGt) => true,
+                        _ => continue,
+                    };
+                    let ops = ssa.operands_of(sel);
+                    if ops.len() != 2 {
+                        continue;
+                    }
+                    // `c < x` is `x > c` and the other way around.
+                    if let (true, Some(c)) = (ops[0] == x, const_value(ssa, ops[1])) {
+                        range = range.refine(gt, c, taken);
+                    } else if let (true, Some(c)) = (ops[1] == x, const_value(ssa, ops[0])) {
+                        range = range.refine(!gt, c, taken);
+                    }
+                }
+                Some(range)
+            };
+
+            for n in ssa.phis_in(b).into_iter().chain(ssa.exprs_in(b)) {
+                let new = if ssa.is_phi(n) {
+                    ssa.operands_of(n)
+                        .into_iter()
+                        .filter_map(|x| operand(&ranges, x))
+                        .fold(None, |acc: Option<ValueRange>, r| {
+                            Some(acc.map_or(r, |acc| acc.union(&r)))
+                        })
+                } else if let Some(c) = const_value(ssa, n) {
+                    Some(ValueRange::constant(c))
+                } else {
+                    let op = ssa.opcode(n).unwrap_or(ir::MOpcode::OpInvalid);
+                    let ops = ssa.operands_of(n)
+                        .into_iter()
+                        .map(|x| operand(&ranges, x))
+                        .collect::<Option<Vec<_>>>();
+                    match ops {
+                        Some(ref ops) if ops.len() == 1 => {
+                            Some(ValueRange::apply(&op, ops[0], ops[0], width(n)))
+                        }
+                        Some(ref ops) if ops.len() == 2 => {
+                            Some(ValueRange::apply(&op, ops[0], ops[1], width(n)))
+                        }
+                        Some(_) => Some(ValueRange::full(width(n))),
+                        // Operands that were not reached yet
+                        None => None,
+                    }
+                };
+                let new = match new {
+                    Some(new) => new,
+                    None => continue,
+                };
+                let old = ranges.get(&n).cloned();
+                let new = match old {
+                    _ if narrowing => new,
+                    None => new,
+                    Some(old) if ssa.is_phi(n) && new.union(&old) != old => {
+                        let count = grown.entry(n).or_insert(0);
+                        *count += 1;
+                        if *count > WIDEN_AFTER {
+                            let full = ValueRange::full(width(n));
+                            ValueRange {
+                                min: if new.min < old.min { full.min } else { old.min },
+                                max: if new.max > old.max { full.max } else { old.max },
+                            }
+                        } else {
+                            new.union(&old)
+                        }
+                    }
+                    Some(old) => new.union(&old),
+                };
+                if old != Some(new) {
+                    ranges.insert(n, new);
+                    changed = true;
+                }
+            }
+        }
+        if narrowing {
+            if narrow_passes == NARROW_PASSES {
+                break;
+            }
+            narrow_passes += 1;
+        } else if !changed {
+            narrow_passes = 1;
+        }
+    }
+    ranges
+}
+
+// Value of the constant `n`, truncated to its width
+fn const_value(ssa: &SSAStorage, n: NodeIndex) -> Option<u64> {
+    let width = ssa.node_data(n).ok().and_then(|nd| nd.vt.width().get_width()).unwrap_or(0);
+    ssa.constant(n).map(|c| c & width_mask(width))
+}
+
+// Largest value of `width` bits. Unknown widths, given as 0, are taken as 64 bits.
+fn width_mask(width: u16) -> u64 {
+    if width == 0 || width >= 64 {
+        u64::max_value()
+    } else {
+        (1 << width) - 1
+    }
+}
@@ -7,4 +7,5 @@
 
 pub mod c_simple;
 pub mod c_ast_constructor;
+pub mod pseudocode;
 
@@ -0,0 +1,174 @@
+// Copyright (c) 2015, The Radare Project. All rights reserved.
+// See the COPYING file at the top-level directory of this distribution.
+// Licensed under the BSD 3-Clause License:
+// <http://opensource.org/licenses/BSD-3-Clause>
+// This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Emits C-like pseudocode for a function straight from its SSA, without structuring the
+//! control flow.
+
+use frontend::radeco_containers::{BindingType, RadecoFunction};
+use middle::ir;
+use middle::regfile::SubRegisterFile;
+use middle::ssa::cfg_traits::CFG;
+use middle::ssa::ssa_traits::{SSA, NodeData, NodeType};
+use petgraph::graph::NodeIndex;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Render `rfn` as C-like pseudocode. Basic blocks are emitted as labels in the order of their
+/// addresses, with control flow between them as `if`/`goto`. Values are named after their
+/// bindings where available, after the register they hold at function entry, or as temporaries
+/// otherwise.
+pub fn emit(rfn: &RadecoFunction, sub_reg_f: &SubRegisterFile) -> String {
+    let ssa = rfn.ssa();
+    let mut names: HashMap<NodeIndex, String> = HashMap::new();
+    for vb in rfn.bindings() {
+        if !vb.name().is_empty() {
+            names.insert(vb.idx, vb.name().to_owned());
+        }
+    }
+    let mut temps = 0;
+    let mut name_of = |n: NodeIndex| -> String {
+        if let Some(name) = names.get(&n) {
+            return name.clone();
+        }
+        let name = if let Some(c) = ssa.constant(n) {
+            format!("{:#x}", c)
+        } else if let Some(c) = ssa.comment(n) {
+            c
+        } else {
+            temps += 1;
+            format!("t{}", temps - 1)
+        };
+        names.insert(n, name.clone());
+        name
+    };
+
+    let mut blocks = ssa.blocks()
+        .into_iter()
+        .map(|b| (ssa.starting_address(b), b))
+        .collect::<Vec<_>>();
+    blocks.sort_by(|a, b| match (a.0, b.0) {
+        (Some(x), Some(y)) => x.cmp(&y).then(a.1.cmp(&b.1)),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.1.cmp(&b.1),
+    });
+    let labels = blocks.iter()
+        .enumerate()
+        .map(|(i, &(_, b))| (b, format!("bb_{}", i)))
+        .collect::<HashMap<_, _>>();
+
+    let mut args = rfn.bindings()
+        .into_iter()
+        .filter_map(|vb| match vb.btype {
+            BindingType::RegisterArgument(i) => Some((i, vb)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    args.sort_by_key(|&(i, _)| i);
+    let mut args = args.into_iter()
+        .map(|(i, vb)| if !vb.name().is_empty() {
+            vb.name().to_owned()
+        } else if let Some(reg) = vb.ridx.and_then(|r| sub_reg_f.whole_names.get(r as usize)) {
+            reg.clone()
+        } else {
+            format!("arg{}", i)
+        })
+        .collect::<Vec<_>>();
+    if rfn.bindings().into_iter().any(|vb| vb.btype.is_variadic()) {
+        args.push("...".to_owned());
+    }
+
+    let mut code = format!("{}({}) {{\n", rfn.name, args.join(", "));
+    for &(_, block) in &blocks {
+        code.push_str(&format!("{}:\n", labels[&block]));
+        for phi in ssa.phis_in(block) {
+            let ops = ssa.operands_of(phi).into_iter().map(&mut name_of).collect::<Vec<_>>();
+            let lhs = name_of(phi);
+            code.push_str(&format!("    {} = phi({});\n", lhs, ops.join(", ")));
+        }
+
+        for expr in ssa.exprs_in(block) {
+            let opcode = match ssa.node_data(expr) {
+                Ok(NodeData { nt: NodeType::Op(ir::MOpcode::OpConst(_)), .. }) |
+                Ok(NodeData { nt: NodeType::Op(ir::MOpcode::OpNop), .. }) => continue,
+                Ok(NodeData { nt: NodeType::Op(op), .. }) => op,
+                _ => continue,
+            };
+            let mut operands = ssa.sparse_operands_of(expr);
+            operands.sort_by_key(|&(i, _)| i);
+            let ops = operands.into_iter().map(|(_, n)| name_of(n)).collect::<Vec<_>>();
+            // Memory operations take the memory state as their first operand, which has no
+            // representation in the output.
+            let stmt = match (opcode, ops.len()) {
+                (ir::MOpcode::OpStore, 3) => {
+                    code.push_str(&format!("    *({}) = {};\n", ops[1], ops[2]));
+                    continue;
+                }
+                (ir::MOpcode::OpLoad, 2) => format!("*({})", ops[1]),
+                (ir::MOpcode::OpCall, _) if !ops.is_empty() => format!("call({})", ops[0]),
+                (ir::MOpcode::OpNot, 1) => format!("~{}", ops[0]),
+                (ir::MOpcode::OpNarrow(w), 1) |
+                (ir::MOpcode::OpZeroExt(w), 1) => format!("(uint{}_t){}", w, ops[0]),
+                (ir::MOpcode::OpSignExt(w), 1) => format!("(int{}_t){}", w, ops[0]),
+                (ir::MOpcode::OpCustom(f), _) => format!("{}({})", f, ops.join(", ")),
+                (op, n) => {
+                    match c_operator(&op) {
+                        Some(cop) if n == 2 => format!("{} {} {}", ops[0], cop, ops[1]),
+                        _ => format!("{}({})", op, ops.join(", ")),
+                    }
+                }
+            };
+            let lhs = name_of(expr);
+            code.push_str(&format!("    {} = {};\n", lhs, stmt));
+        }
+
+        if Some(block) == ssa.exit_node() {
+            // Wide return values are written as `hi:lo`.
+            let mut rets = rfn.bindings()
+                .into_iter()
+                .filter(|vb| vb.btype.is_return() && vb.idx != NodeIndex::end())
+                .map(|vb| name_of(vb.idx))
+                .collect::<Vec<_>>();
+            rets.reverse();
+            if rets.is_empty() {
+                code.push_str("    return;\n");
+            } else {
+                code.push_str(&format!("    return {};\n", rets.join(":")));
+            }
+        } else if let Some(ci) = ssa.conditional_blocks(block) {
+            let cond = ssa.selector_in(block).map_or("?".to_owned(), &mut name_of);
+            code.push_str(&format!("    if ({}) goto {}; else goto {};\n",
+                                   cond,
+                                   labels[&ci.true_side],
+                                   labels[&ci.false_side]));
+        } else if let Some(next) = ssa.unconditional_block(block) {
+            code.push_str(&format!("    goto {};\n", labels[&next]));
+        }
+    }
+    code.push_str("}\n");
+    code
+}
+
+// Infix C operator for binary opcodes that have one
+fn c_operator(op: &ir::MOpcode) -> Option<&'static str> {
+    match *op {
+        ir::MOpcode::OpAdd => Some("+"),
+        ir::MOpcode::OpSub | ir::MOpcode::OpCmp => Some("-"),
+        ir::MOpcode::OpMul => Some("*"),
+        ir::MOpcode::OpDiv => Some("/"),
+        ir::MOpcode::OpMod => Some("%"),
+        ir::MOpcode::OpAnd => Some("&"),
+        ir::MOpcode::OpOr => Some("|"),
+        ir::MOpcode::OpXor => Some("^"),
+        ir::MOpcode::OpLsl => Some("<<"),
+        ir::MOpcode::OpLsr => Some(">>"),
+        ir::MOpcode::OpEq => Some("=="),
+        ir::MOpcode::OpGt => Some(">"),
+        ir::MOpcode::OpLt => Some("<"),
+        _ => None,
+    }
+}
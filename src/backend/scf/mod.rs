@@ -10,6 +10,8 @@
 
 use std::collections::BTreeMap;
 
+pub mod structure;
+
 pub type AST_ = Box<AST>;
 
 pub type RUnit = u32;
@@ -0,0 +1,207 @@
+// Copyright (c) 2015, The Radare Project. All rights reserved.
+// See the COPYING file at the top-level directory of this distribution.
+// Licensed under the BSD 3-Clause License:
+// <http://opensource.org/licenses/BSD-3-Clause>
+// This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Recovers conditionals and loops from the control flow graph of the SSA.
+//!
+//! Loops are found as natural loops, i.e. from back edges to a block that dominates their
+//! source, and conditionals are closed at the immediate postdominator of the branching block.
+//! Whatever does not fit these patterns, such as irreducible regions or multiway branches,
+//! is left as `Goto`s to blocks that are structured on their own.
+
+use analysis::dom::dominators::{self, DominatorTree};
+use middle::ssa::cfg_traits::CFG;
+use middle::ssa::ssa_traits::SSA;
+use middle::ssa::ssastorage::SSAStorage;
+use petgraph::graph::NodeIndex;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Structured control flow of a function
+pub enum StructuredAst {
+    /// Statements executed one after the other
+    Seq(Vec<StructuredAst>),
+    /// Two-way branch on the selector `cond` of the preceding block
+    If {
+        cond: NodeIndex,
+        then_: Box<StructuredAst>,
+        else_: Option<Box<StructuredAst>>,
+    },
+    /// Endless loop, only left through `Break` or `Goto`
+    Loop(Box<StructuredAst>),
+    /// Leave the innermost loop
+    Break,
+    /// Instructions of a basic block
+    BasicBlock(NodeIndex),
+    /// Jump to a block that could not be structured in place
+    Goto(NodeIndex),
+}
+
+/// Structure the CFG of `ssa`. Blocks that are not reachable from the entry are left out.
+pub fn structure(ssa: &SSAStorage) -> StructuredAst {
+    let entry = match ssa.entry_node() {
+        Some(entry) => entry,
+        None => return StructuredAst::Seq(Vec::new()),
+    };
+    let doms = dominators::dominator_tree(ssa);
+    let postdoms = dominators::post_dominator_tree(ssa);
+    let mut reachable = ssa.blocks()
+        .into_iter()
+        .filter(|&b| doms.is_reachable(b))
+        .collect::<Vec<_>>();
+
+    let mut loops: HashMap<NodeIndex, HashSet<NodeIndex>> = HashMap::new();
+    for &latch in &reachable {
+        for header in ssa.succs_of(latch) {
+            if !doms.dominates(header, latch) {
+                continue;
+            }
+            let body = loops.entry(header).or_insert_with(HashSet::new);
+            body.insert(header);
+            let mut wl = vec![latch];
+            while let Some(n) = wl.pop() {
+                if body.insert(n) {
+                    wl.extend(ssa.preds_of(n).into_iter().filter(|&p| doms.is_reachable(p)));
+                }
+            }
+        }
+    }
+
+    let mut structurer = Structurer {
+        ssa: ssa,
+        postdoms: postdoms,
+        loops: loops,
+        done: HashSet::new(),
+    };
+    let mut seq = structurer.region(entry, None, None);
+    // Blocks that are only reached through a `Goto` are structured on their own.
+    reachable.sort_by_key(|&b| ssa.starting_address(b));
+    for b in reachable {
+        if !structurer.done.contains(&b) {
+            seq.extend(structurer.region(b, None, None));
+        }
+    }
+    StructuredAst::Seq(seq)
+}
+
+// Loop that is being structured
+struct LoopCtx<'l> {
+    header: NodeIndex,
+    body: &'l HashSet<NodeIndex>,
+    // Block where control flow continues after the loop
+    follow: Option<NodeIndex>,
+}
+
+struct Structurer<'a> {
+    ssa: &'a SSAStorage,
+    postdoms: DominatorTree,
+    // Natural loops, by header
+    loops: HashMap<NodeIndex, HashSet<NodeIndex>>,
+    done: HashSet<NodeIndex>,
+}
+
+impl<'a> Structurer<'a> {
+    // Structure the blocks starting at `start`, up to but excluding `stop`.
+    fn region(&mut self,
+              start: NodeIndex,
+              stop: Option<NodeIndex>,
+              ctx: Option<&LoopCtx>)
+              -> Vec<StructuredAst> {
+        let mut seq = Vec::new();
+        let mut cur = Some(start);
+        while let Some(b) = cur {
+            if Some(b) == stop {
+                break;
+            }
+            if let Some(ctx) = ctx {
+                // Back to the header is the next iteration of the loop.
+                if b == ctx.header && self.done.contains(&b) {
+                    break;
+                }
+                if Some(b) == ctx.follow {
+                    seq.push(StructuredAst::Break);
+                    break;
+                }
+                if !ctx.body.contains(&b) {
+                    seq.push(StructuredAst::Goto(b));
+                    break;
+                }
+            }
+            if self.done.contains(&b) {
+                seq.push(StructuredAst::Goto(b));
+                break;
+            }
+
+            let body = if ctx.map_or(true, |ctx| ctx.header != b) {
+                self.loops.get(&b).cloned()
+            } else {
+                None
+            };
+            if let Some(body) = body {
+                let follow = self.loop_follow(b, &body);
+                let inner = LoopCtx {
+                    header: b,
+                    body: &body,
+                    follow: follow,
+                };
+                let looped = self.region(b, None, Some(&inner));
+                seq.push(StructuredAst::Loop(Box::new(StructuredAst::Seq(looped))));
+                cur = follow;
+                continue;
+            }
+
+            self.done.insert(b);
+            seq.push(StructuredAst::BasicBlock(b));
+            if let Some(ci) = self.ssa.conditional_blocks(b) {
+                // Branches join at the immediate postdominator, unless it is outside the loop.
+                let join = self.postdoms
+                    .idom(b)
+                    .and_then(|join| if join == b ||
+                                        ctx.map_or(false, |ctx| !ctx.body.contains(&join)) {
+                        None
+                    } else {
+                        Some(join)
+                    });
+                let then_ = self.region(ci.true_side, join, ctx);
+                let else_ = self.region(ci.false_side, join, ctx);
+                seq.push(StructuredAst::If {
+                    cond: self.ssa.selector_in(b).unwrap_or(NodeIndex::end()),
+                    then_: Box::new(StructuredAst::Seq(then_)),
+                    else_: if else_.is_empty() {
+                        None
+                    } else {
+                        Some(Box::new(StructuredAst::Seq(else_)))
+                    },
+                });
+                cur = join;
+            } else {
+                let succs = self.ssa.succs_of(b);
+                if succs.len() == 1 {
+                    cur = Some(succs[0]);
+                } else {
+                    seq.extend(succs.into_iter().map(StructuredAst::Goto));
+                    cur = None;
+                }
+            }
+        }
+        seq
+    }
+
+    // Block after the loop: the exit of the header if there is one, otherwise the first exit
+    // of the loop.
+    fn loop_follow(&self, header: NodeIndex, body: &HashSet<NodeIndex>) -> Option<NodeIndex> {
+        let outside = |n: &NodeIndex| !body.contains(n);
+        if let Some(exit) = self.ssa.succs_of(header).into_iter().find(&outside) {
+            return Some(exit);
+        }
+        let mut exits = body.iter()
+            .flat_map(|&n| self.ssa.succs_of(n))
+            .filter(&outside)
+            .collect::<Vec<_>>();
+        exits.sort_by_key(|&n| self.ssa.starting_address(n));
+        exits.into_iter().next()
+    }
+}
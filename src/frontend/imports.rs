@@ -7,11 +7,15 @@ use std::cell::RefCell;
 use std::sync::Arc;
 
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ImportInfo {
     pub plt: u64,
     pub name: Cow<'static, str>,
     pub rfn: Arc<RefCell<RadecoFunction>>,
+    /// (module index, offset) of the definition of this import in the project, filled in by
+    /// `RadecoProject::resolve_imports`
+    #[serde(default)]
+    resolved: Option<(usize, u64)>,
 }
 
 impl ImportInfo {
@@ -22,6 +26,17 @@ impl ImportInfo {
             plt: plt,
             name: name,
             rfn: Arc::new(RefCell::new(rfn)),
+            resolved: None,
         }
     }
+
+    /// (module index, offset) of the function this import resolves to, if it has been
+    /// resolved
+    pub fn resolved(&self) -> Option<(usize, u64)> {
+        self.resolved
+    }
+
+    pub fn set_resolved(&mut self, resolved: Option<(usize, u64)>) {
+        self.resolved = resolved;
+    }
 }
@@ -1,16 +1,104 @@
 //! Implements some low-level analysis as a part of frontend
 
-use frontend::radeco_containers::{RadecoModule, CallGraph, CGInfo, CallContextInfo, RadecoFunction};
-use middle::ir::MOpcode;
+use frontend::radeco_containers::{RadecoModule, CallGraph, CGInfo, CallContextInfo, RadecoFunction,
+                                  CallingConvention, VarBinding};
+use frontend::radeco_source::Source;
+use middle::ir::{MAddress, MOpcode};
 use middle::regfile::SubRegisterFile;
-use middle::ssa::ssa_traits::{SSAWalk, SSA, NodeType};
+use middle::ssa::cfg_traits::{CFG, CFGMod};
+use middle::ssa::ssa_traits::{SSAWalk, SSA, SSAMod, NodeData, NodeType};
 use petgraph::Direction;
 use petgraph::graph::NodeIndex;
 use petgraph::visit::EdgeRef;
 use r2api::structs::FunctionInfo;
 
 use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+// Upper bound on the number of entries read from a single jump table
+const MAX_JUMP_TABLE_ENTRIES: u64 = 256;
+// Index of unconditional control flow edges in the CFG
+const UNCOND_EDGE: u8 = 2;
+
+/// Imported functions that are known to never return to their caller
+pub const NORETURN_IMPORTS: &'static [&'static str] = &["exit",
+                                                         "_exit",
+                                                         "_Exit",
+                                                         "abort",
+                                                         "__assert_fail",
+                                                         "__stack_chk_fail",
+                                                         "err",
+                                                         "errx",
+                                                         "longjmp",
+                                                         "pthread_exit",
+                                                         "quick_exit"];
+
+/// Arguments taken by an imported function
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ImportSignature {
+    /// Number of fixed arguments
+    pub nargs: usize,
+    /// Does the function take a variable number of arguments after the fixed ones
+    pub variadic: bool,
+    /// Does the function never return to its caller
+    pub noreturn: bool,
+}
+
+impl ImportSignature {
+    pub fn new(nargs: usize, variadic: bool) -> ImportSignature {
+        ImportSignature {
+            nargs: nargs,
+            variadic: variadic,
+            noreturn: false,
+        }
+    }
+
+    /// Mark the function as never returning to its caller
+    pub fn noreturn(mut self) -> ImportSignature {
+        self.noreturn = true;
+        self
+    }
+}
+
+/// Signatures of common libc functions, by name. These are applied to the stubs of imported
+/// functions while loading a module. The functions in `NORETURN_IMPORTS` are marked as
+/// `noreturn`.
+pub fn known_import_signatures() -> HashMap<String, ImportSignature> {
+    let fixed = [("abort", 0), ("atoi", 1), ("calloc", 2), ("close", 1), ("exit", 1),
+                 ("fclose", 1), ("fgets", 3), ("fopen", 2), ("fread", 4), ("free", 1),
+                 ("fwrite", 4), ("getchar", 0), ("malloc", 1), ("memcmp", 3), ("memcpy", 3),
+                 ("memmove", 3), ("memset", 3), ("putchar", 1), ("puts", 1), ("read", 3),
+                 ("realloc", 2), ("strcat", 2), ("strchr", 2), ("strcmp", 2), ("strcpy", 2),
+                 ("strdup", 1), ("strlen", 1), ("strncmp", 3), ("strncpy", 3), ("write", 3),
+                 ("_exit", 1), ("_Exit", 1), ("__assert_fail", 4), ("__stack_chk_fail", 0),
+                 ("longjmp", 2), ("pthread_exit", 1), ("quick_exit", 1)];
+    let variadic = [("execl", 2), ("fprintf", 2), ("fscanf", 2), ("ioctl", 2), ("open", 2),
+                    ("printf", 1), ("scanf", 1), ("snprintf", 3), ("sprintf", 2),
+                    ("sscanf", 2), ("err", 2), ("errx", 2)];
+    fixed.iter()
+        .map(|&(name, nargs)| (name, ImportSignature::new(nargs, false)))
+        .chain(variadic.iter().map(|&(name, nargs)| (name, ImportSignature::new(nargs, true))))
+        .map(|(name, sig)| if NORETURN_IMPORTS.contains(&name) {
+            (name.to_owned(), sig.noreturn())
+        } else {
+            (name.to_owned(), sig)
+        })
+        .collect()
+}
+
+/// Name of the import called by a call instruction, given its disassembly. For example
+/// `exit` for `call sym.imp.exit`.
+pub fn import_name(target: &str) -> &str {
+    let name = target.split_whitespace()
+        .last()
+        .unwrap_or("")
+        .trim_matches(|c| c == '[' || c == ']');
+    ["sym.imp.", "reloc.", "imp."]
+        .iter()
+        .find(|p| name.starts_with(*p))
+        .map_or(name, |p| &name[p.len()..])
+}
+
 /// Converts call graph information from `Source`, represented in FunctionInfo,
 /// into an actual graph with links.
 pub fn load_call_graph(finfos: &[FunctionInfo], rmod: &RadecoModule) -> CallGraph {
@@ -27,13 +115,30 @@ pub fn load_call_graph(finfos: &[FunctionInfo], rmod: &RadecoModule) -> CallGrap
         let fnode = node_map.get(&offset);
         if let Some(ref callrefs) = x.callrefs {
             for cs in callrefs {
-                match cs.call_type {
+                let is_jump = match cs.call_type {
+                    Some(ref c) if c == "J" => true,
                     Some(ref c) if c != "C" => continue,
-                    _ => {}
-                }
+                    _ => false,
+                };
                 let mut cctx = CallContextInfo::default();
-                cctx.csite = cs.source.expect("No source for call");
-                let target = node_map.get(&cs.target.unwrap());
+                cctx.csite = match cs.source {
+                    Some(source) => source,
+                    None => {
+                        radeco_warn!("No source for call in function at {:#x}", offset);
+                        continue;
+                    }
+                };
+                if is_jump {
+                    // Jumps are only calls if they are unconditional and go to the entry of
+                    // another function.
+                    let tailcall = cs.target.map_or(false, |t| t != offset) &&
+                                   is_jump_at(rmod, offset, cctx.csite) != Some(false);
+                    if !tailcall {
+                        continue;
+                    }
+                    cctx.is_tailcall = true;
+                }
+                let target = cs.target.and_then(|t| node_map.get(&t));
                 match (fnode, target) {
                     (Some(cn), Some(tn)) => {
                         cg.add_edge(*cn, *tn, cctx);
@@ -44,7 +149,8 @@ pub fn load_call_graph(finfos: &[FunctionInfo], rmod: &RadecoModule) -> CallGrap
         }
     }
 
-    // Initial callsite analysis to fix up CallContextInfo on the edges.
+    // Initial callsite analysis to fix up CallContextInfo on the edges. This needs the SSA,
+    // edges of functions without SSA keep the callsite reported by the `Source`.
     for (offset, node) in &node_map {
         if let Some(rfn) = rmod.functions.get(offset) {
             let mut csites = analyze_callsite_initial(rfn);
@@ -63,6 +169,16 @@ pub fn load_call_graph(finfos: &[FunctionInfo], rmod: &RadecoModule) -> CallGrap
     cg
 }
 
+// Is the instruction at `addr` in function at `offset` an unconditional jump? `None` if the
+// instruction is not known.
+fn is_jump_at(rmod: &RadecoModule, offset: u64, addr: u64) -> Option<bool> {
+    rmod.functions
+        .get(&offset)
+        .and_then(|rfn| rfn.instructions().iter().find(|op| op.offset == Some(addr)))
+        .and_then(|op| op.optype.as_ref())
+        .map(|ty| ty == "jmp")
+}
+
 /// Iterates through nodes in SSA for rfn and initializes the inital CallContextInfo
 fn analyze_callsite_initial(rfn: &RadecoFunction) -> HashMap<u64, CallContextInfo> {
     let mut cctxs = HashMap::new();
@@ -93,6 +209,17 @@ fn analyze_callsite_initial(rfn: &RadecoFunction) -> HashMap<u64, CallContextInf
     cctxs
 }
 
+// Order argument bindings by register. Bindings that are not tied to a register, such as
+// stack or variable arguments, come last.
+fn cmp_arg_bindings(x: &VarBinding, y: &VarBinding) -> Ordering {
+    match (x.ridx, y.ridx) {
+        (Some(xidx), Some(ref yidx)) => xidx.cmp(yidx),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
 pub fn init_call_ctx(rmod: &mut RadecoModule) {
     let cctxs: Vec<(u64, CallContextInfo)> = Vec::new();
     for wrapper in rmod.functions.iter() {
@@ -112,12 +239,7 @@ pub fn init_call_ctx(rmod: &mut RadecoModule) {
                     .filter(|x| x.btype.is_argument() || x.btype.is_return())
                     .cloned()
                     .collect::<Vec<_>>();
-                args.sort_by(|x, y| {
-                    match (x.ridx, y.ridx) {
-                        (Some(xidx), Some(ref yidx)) => xidx.cmp(yidx),
-                        (_, _) => unreachable!(),
-                    }
-                });
+                args.sort_by(cmp_arg_bindings);
                 Some((calleefn.cgid(), args))
             } else if let Some(calleefn) = rmod.imports.get(&callee_off).map(|ifn| ifn.rfn.borrow()) {
                 let mut args = calleefn.bindings()
@@ -125,12 +247,7 @@ pub fn init_call_ctx(rmod: &mut RadecoModule) {
                     .filter(|x| x.btype.is_argument() || x.btype.is_return())
                     .cloned()
                     .collect::<Vec<_>>();
-                args.sort_by(|x, y| {
-                    match (x.ridx, y.ridx) {
-                        (Some(xidx), Some(ref yidx)) => xidx.cmp(yidx),
-                        (_, _) => unreachable!(),
-                    }
-                });
+                args.sort_by(cmp_arg_bindings);
                 Some((calleefn.cgid(), args))
             } else {
                 None
@@ -151,3 +268,331 @@ pub fn init_call_ctx(rmod: &mut RadecoModule) {
         }
     }
 }
+
+/// Add callgraph edges for indirect calls through a register (`call rax`) whose target is a
+/// constant in an executable section, e.g. after constant propagation. Only targets that are
+/// functions or imports of `rmod` are linked. Callsites that already have an edge are left as
+/// they are.
+pub fn resolve_indirect_calls(rmod: &mut RadecoModule) {
+    let targets = rmod.functions
+        .iter()
+        .map(|(&off, rfn)| (off, rfn.cgid()))
+        .chain(rmod.imports.iter().map(|(&off, ifn)| (off, ifn.rfn.borrow().cgid())))
+        .filter(|&(off, cgid)| rmod.callgraph.node_weight(cgid) == Some(&off))
+        .collect::<HashMap<_, _>>();
+
+    let mut edges = Vec::new();
+    for rfn in rmod.functions.values() {
+        let known = rmod.callgraph
+            .callees(rfn.cgid())
+            .map(|(csite, _)| csite)
+            .collect::<HashSet<_>>();
+        for (csite, call) in rfn.callsites() {
+            if known.contains(&csite) {
+                continue;
+            }
+            let target = match indirect_call_target(rfn, call) {
+                Some(target) if rmod.is_executable(target) => target,
+                _ => continue,
+            };
+            if let Some(&callee) = targets.get(&target) {
+                let mut cctx = CallContextInfo::default();
+                cctx.map = rfn.ssa()
+                    .operands_of(call)
+                    .into_iter()
+                    .chain(Some(call))
+                    .map(|n| (n, NodeIndex::end()))
+                    .collect();
+                cctx.csite_node = call;
+                cctx.csite = csite;
+                cctx.resolved_indirect = true;
+                edges.push((rfn.cgid(), callee, cctx));
+            }
+        }
+    }
+
+    for (caller, callee, cctx) in edges {
+        rmod.callgraph.add_edge(caller, callee, cctx);
+    }
+}
+
+// Constant value of the register called through by `call`, if any. The register is taken
+// from the disassembly of the call, which is the first operand of `OpCall`.
+fn indirect_call_target(rfn: &RadecoFunction, call: NodeIndex) -> Option<u64> {
+    let ssa = rfn.ssa();
+    let operands = ssa.sparse_operands_of(call);
+    let disasm = operands.iter().find(|&&(i, _)| i == 0).and_then(|&(_, n)| ssa.comment(n))?;
+    let reg = disasm.split_whitespace().last()?;
+    let ridx = ssa.regnames.iter().position(|r| r == reg)?;
+    // Register arguments of the call are placed after the call target.
+    operands.iter()
+        .find(|&&(i, _)| i as usize == ridx + 1)
+        .and_then(|&(_, n)| ssa.constant(n))
+}
+
+/// Determine for every function with a return binding, whether any of its callers use the
+/// value it returns. Functions without callers, or with callsites that cannot be analyzed, are
+/// assumed to have their return value used. Results are available through
+/// `RadecoFunction::is_return_live`.
+pub fn analyze_return_liveness(rmod: &mut RadecoModule) {
+    let mut liveness = HashMap::new();
+    for (&offset, rfn) in &rmod.functions {
+        let ret_reg = rfn.bindings()
+            .into_iter()
+            .find(|vb| vb.btype.is_return())
+            .and_then(|vb| vb.ridx)
+            .and_then(|ridx| rfn.ssa().regnames.get(ridx as usize).cloned());
+        let ret_reg = match ret_reg {
+            Some(reg) => reg,
+            None => continue,
+        };
+        // Functions that are not a part of the callgraph have unknown callers.
+        if rmod.callgraph.node_weight(rfn.cgid()) != Some(&offset) {
+            liveness.insert(offset, true);
+            continue;
+        }
+
+        let mut has_callers = false;
+        let mut live = false;
+        for edge in rmod.callgraph.edges_directed(rfn.cgid(), Direction::Incoming) {
+            has_callers = true;
+            let cctx = edge.weight();
+            let caller = rmod.functions.get(&rmod.callgraph[edge.source()]);
+            // Tail calls return to the caller's caller.
+            live = cctx.is_tailcall ||
+                   caller.map_or(true, |c| is_return_used(c, cctx.csite_node, &ret_reg));
+            if live {
+                break;
+            }
+        }
+        liveness.insert(offset, live || !has_callers);
+    }
+
+    for (offset, live) in liveness {
+        if let Some(rfn) = rmod.functions.get_mut(&offset) {
+            rfn.set_return_live(live);
+        }
+    }
+}
+
+// Is the value returned in `reg` by the call at `call_node` in `caller` used?
+fn is_return_used(caller: &RadecoFunction, call_node: NodeIndex, reg: &str) -> bool {
+    let ssa = caller.ssa();
+    match ssa.node_data(call_node) {
+        Ok(NodeData { nt: NodeType::Op(MOpcode::OpCall), .. }) => {}
+        _ => return true,
+    }
+
+    // The value returned in a register is represented by a comment of the form `reg@address`
+    // that uses the call.
+    let prefix = format!("{}@", reg);
+    let rets = ssa.uses_of(call_node)
+        .into_iter()
+        .filter(|&n| ssa.comment(n).map_or(false, |c| c.starts_with(&prefix)))
+        .collect::<Vec<_>>();
+    rets.is_empty() || rets.into_iter().any(|n| !ssa.uses_of(n).is_empty())
+}
+
+/// Detect the calling convention used by `rfn` from its SSA, rather than assuming the one of
+/// the register profile. The arguments are the registers of the default convention, up to the
+/// last one that is read by the function before being written. The return register of the
+/// default convention is kept if the function writes to it, and is left empty otherwise.
+/// Arguments are also passed on the stack if the function loads any, see
+/// `RadecoFunction::stack_arguments`.
+pub fn detect_calling_convention(rfn: &RadecoFunction,
+                                 sub_reg_f: &SubRegisterFile)
+                                 -> CallingConvention {
+    let default = CallingConvention::default();
+    let ssa = rfn.ssa();
+    let entry_state = ssa.entry_node()
+        .and_then(|entry| ssa.registers_in(entry))
+        .map(|rs| ssa.operands_of(rs))
+        .unwrap_or_default();
+    let exit_state = ssa.exit_node()
+        .and_then(|exit| if ssa.preds_of(exit).is_empty() {
+            None
+        } else {
+            ssa.registers_in(exit)
+        })
+        .map(|rs| ssa.operands_of(rs))
+        .unwrap_or_default();
+    // Value of the register with the alias at function entry
+    let entry_value = |alias: &str| {
+        sub_reg_f.alias_info.get(alias).and_then(|reg| {
+            entry_state.iter()
+                .find(|&&n| ssa.comment(n).map_or(false, |c| &c == reg))
+                .cloned()
+        })
+    };
+
+    // Register states only carry the value through the function, and are not reads of it.
+    let nargs = default.args
+        .iter()
+        .rposition(|alias| {
+            entry_value(alias).map_or(false, |n| {
+                ssa.uses_of(n).into_iter().any(|u| ssa.node_data(u).is_ok())
+            })
+        })
+        .map_or(0, |i| i + 1);
+
+    let ret_written = sub_reg_f.register_id_by_alias(&default.ret)
+        .and_then(|ridx| exit_state.get(ridx as usize).cloned())
+        .map_or(false, |n| Some(n) != entry_value(&default.ret));
+
+    CallingConvention {
+        args: default.args[..nargs].to_vec(),
+        ret: if ret_written { default.ret } else { String::new() },
+        ret_hi: Vec::new(),
+        stack_args: !rfn.stack_arguments().is_empty(),
+    }
+}
+
+/// Recover the targets of indirect jumps through a jump table (`jmp [base + idx*scale]`), as
+/// generated for `switch` statements, and add them as successors of the jumping block in the
+/// CFG of `rfn`. The table is read through `source`, one entry of `scale` bytes at a time, for
+/// as long as the entries point to the start of a block in the function, up to
+/// `MAX_JUMP_TABLE_ENTRIES`. Only tables holding absolute addresses are understood.
+///
+/// Note that the SSA values of the targets are not updated for their new predecessors.
+/// Returns the number of jump tables that were recovered.
+pub fn recover_jump_tables(rfn: &mut RadecoFunction, source: &Source) -> usize {
+    let (start, end) = (rfn.offset, rfn.offset + rfn.size());
+    let mut recovered = Vec::new();
+    {
+        let ssa = rfn.ssa();
+        let blocks = ssa.blocks()
+            .into_iter()
+            .filter_map(|b| ssa.starting_address(b).map(|addr| (addr, b)))
+            .collect::<HashMap<_, _>>();
+        for &block in blocks.values() {
+            // Indirect jumps lead to a placeholder block outside of the function.
+            let placeholder = match ssa.unconditional_block(block) {
+                Some(next) => next,
+                None => continue,
+            };
+            match ssa.starting_address(placeholder) {
+                Some(addr) if addr.address < start || addr.address >= end => {}
+                _ => continue,
+            }
+            let (base, scale) = match ssa.selector_in(block).and_then(|sel| jump_table(rfn, sel)) {
+                Some(table) => table,
+                None => continue,
+            };
+            let mut targets = Vec::new();
+            for i in 0..MAX_JUMP_TABLE_ENTRIES {
+                let bytes = match source.read_bytes(base + i * scale, scale as usize) {
+                    Ok(bytes) => bytes,
+                    Err(_) => break,
+                };
+                let target = bytes.iter().rev().fold(0, |acc, &b| (acc << 8) | b as u64);
+                match blocks.get(&MAddress::new(target, 0)) {
+                    Some(&bb) if !targets.contains(&bb) => targets.push(bb),
+                    Some(_) => {}
+                    None => break,
+                }
+            }
+            if !targets.is_empty() {
+                recovered.push((block, placeholder, targets));
+            }
+        }
+    }
+
+    let ssa = rfn.ssa_mut();
+    for &(block, placeholder, ref targets) in &recovered {
+        if let Some(edge) = ssa.unconditional_edge(block) {
+            ssa.remove_control_edge(edge);
+        }
+        if ssa.preds_of(placeholder).is_empty() {
+            ssa.remove_block(placeholder);
+        }
+        for &target in targets {
+            ssa.insert_control_edge(block, target, UNCOND_EDGE);
+        }
+    }
+    recovered.len()
+}
+
+// Base address and scale of the table that `selector` loads a jump target from, if it is of
+// the form `[base + idx*scale]` with constant base and scale.
+fn jump_table(rfn: &RadecoFunction, selector: NodeIndex) -> Option<(u64, u64)> {
+    let ssa = rfn.ssa();
+    if ssa.opcode(selector) != Some(MOpcode::OpLoad) {
+        return None;
+    }
+    let addr = *ssa.operands_of(selector).get(1)?;
+    if ssa.opcode(addr) != Some(MOpcode::OpAdd) {
+        return None;
+    }
+    let operands = ssa.operands_of(addr);
+    let base = operands.iter().filter_map(|&n| ssa.constant(n)).next()?;
+    let index = *operands.iter().find(|&&n| ssa.opcode(n) == Some(MOpcode::OpMul))?;
+    let scale = ssa.operands_of(index).into_iter().filter_map(|n| ssa.constant(n)).next()?;
+    match scale {
+        1 | 2 | 4 | 8 => Some((base, scale)),
+        _ => None,
+    }
+}
+
+/// Remove the control flow out of the blocks of `rfn` that call an import that does not
+/// return, as marked in `known_import_signatures`, and delete the blocks that are no longer
+/// reachable from the entry. Instructions that follow the call in the same block are kept.
+/// Returns the number of deleted blocks.
+pub fn prune_after_noreturn(rfn: &mut RadecoFunction) -> usize {
+    prune_after_noreturn_with(rfn, &known_import_signatures())
+}
+
+/// Like `prune_after_noreturn`, but with the imports that do not return given by the
+/// `noreturn` flag in `sigs`. This allows to add functions of the analyzed program, or of
+/// libraries other than libc, that are known to not return.
+pub fn prune_after_noreturn_with(rfn: &mut RadecoFunction,
+                                 sigs: &HashMap<String, ImportSignature>)
+                                 -> usize {
+    let dead_ends = {
+        let ssa = rfn.ssa();
+        ssa.blocks()
+            .into_iter()
+            .filter(|&block| {
+                ssa.exprs_in(block).into_iter().any(|expr| {
+                    ssa.opcode(expr) == Some(MOpcode::OpCall) &&
+                    ssa.sparse_operands_of(expr)
+                        .into_iter()
+                        .find(|&(i, _)| i == 0)
+                        .and_then(|(_, target)| ssa.comment(target))
+                        .and_then(|target| sigs.get(import_name(&target)).cloned())
+                        .map_or(false, |sig| sig.noreturn)
+                })
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let ssa = rfn.ssa_mut();
+    for &block in &dead_ends {
+        for (edge, _) in ssa.outgoing_edges(block) {
+            ssa.remove_control_edge(edge);
+        }
+        if let Some(selector) = ssa.selector_in(block) {
+            ssa.remove_value(selector);
+        }
+    }
+
+    let entry = match ssa.entry_node() {
+        Some(entry) => entry,
+        None => return 0,
+    };
+    let mut reachable = HashSet::new();
+    let mut wl = vec![entry];
+    while let Some(block) = wl.pop() {
+        if reachable.insert(block) {
+            wl.extend(ssa.succs_of(block));
+        }
+    }
+    let exit = ssa.exit_node();
+    let unreachable = ssa.blocks()
+        .into_iter()
+        .filter(|b| !reachable.contains(b) && Some(*b) != exit)
+        .collect::<Vec<_>>();
+    for &block in &unreachable {
+        ssa.remove_block(block);
+    }
+    unreachable.len()
+}
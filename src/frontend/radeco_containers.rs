@@ -22,7 +22,8 @@
 //! # fn main() {
 //! let mut rp: RadecoProject = ProjectLoader::default()  // setup the default loader
 //!                                 .path("/bin/ls")      // path to bin to analyze
-//!                                 .load();              // fire-off the loading
+//!                                 .load()               // fire-off the loading
+//!                                 .unwrap();
 //! # }
 //! ```
 //!
@@ -32,45 +33,84 @@
 
 
 use frontend::bindings::{Binding, RBindings, RadecoBindings};
-use frontend::llanalyzer;
-use frontend::radeco_source::{WrappedR2Api, Source};
-use frontend::ssaconstructor::SSAConstruct;
+use frontend::llanalyzer::{self, ImportSignature};
+use frontend::radeco_source::{WrappedR2Api, Source, SourceErr};
+use frontend::ssaconstructor::{ConstructErr, SSAConstruct};
 use frontend::imports::ImportInfo;
 
+use analysis::dom::dominators::{self, DominatorTree};
+use analysis::memory_accesses::{self, MemAccess};
+use analysis::sccp;
+use analysis::stack;
+use analysis::value_ranges::{self, ValueRange};
+use backend::lang_c::pseudocode;
+use backend::scf::structure::{self, StructuredAst};
+use middle::dot;
 use middle::ir;
 use middle::regfile::SubRegisterFile;
 use middle::ssa::cfg_traits::CFG;
-use middle::ssa::ssa_traits::{SSA, NodeData, NodeType};
+use middle::ssa::ssa_traits::{SSA, SSAMod, NodeData, NodeType};
 
 use middle::ssa::ssastorage::SSAStorage;
+use middle::ssa::verifier::{self, SsaError};
 use petgraph::Direction;
+use petgraph::algo::{kosaraju_scc, tarjan_scc};
 
-use petgraph::graph::{NodeIndex, Graph};
-use petgraph::visit::EdgeRef;
+use petgraph::graph::{EdgeIndex, NodeIndex, Graph};
+use petgraph::visit::{Dfs, EdgeRef};
 use r2api::api_trait::R2Api;
-use r2api::structs::{LOpInfo, LRegInfo, LSymbolInfo, LRelocInfo, LImportInfo, LExportInfo,
-                     LSectionInfo, LEntryInfo, LSymbolType};
+use r2api::structs::{FunctionInfo, LOpInfo, LRegInfo, LSymbolInfo, LRelocInfo, LImportInfo,
+                     LExportInfo, LSectionInfo, LEntryInfo, LSymbolType, LVarInfo};
 
 use r2pipe::r2::R2;
+use rayon::{Configuration, ThreadPool};
+use cpp_demangle;
+use rustc_demangle;
 use rayon::prelude::*;
 use std::borrow::Cow;
-use std::cell::RefCell;
-use std::cmp::Ordering;
+use std::cell::{Ref, RefCell};
+use std::cmp::{self, Ordering};
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::collections::{btree_map, hash_map};
+use std::collections::Bound;
+use std::collections::hash_map::DefaultHasher;
+use serde_json;
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::env;
+use std::io::{self, Read, Write};
+use std::iter;
 use std::marker::PhantomData;
-use std::path::Path;
+use std::mem;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::slice;
-use std::sync::Arc;
+use std::sync::{mpsc, Arc, Mutex};
+use std::sync::atomic::{self, AtomicUsize};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 // use cpuprofiler::PROFILER;
 
+// Maximum number of bytes read when looking for a string
+const MAX_STRING_LEN: u64 = 4096;
+
+// Instruction prefixes on x86, which are disassembled as separate words
+const INSN_PREFIXES: &'static [&'static str] = &["lock", "rep", "repe", "repz", "repne", "repnz",
+                                                  "bnd", "notrack"];
+
 /// Defines sane defaults for the loading process.
 pub mod loader_defaults {
     use frontend::radeco_source::Source;
-    use r2api::structs::LSymbolType;
+    use r2api::structs::{LEntryInfo, LSymbolType};
     use std::borrow::Cow;
+    use std::collections::HashMap;
+    use std::fs::File;
+    use std::io::{BufRead, BufReader};
+    use std::path::Path;
     use std::rc::Rc;
     use super::{FLResult, PredicatedLoader};
     use super::{RadecoModule, RadecoFunction};
@@ -99,6 +139,178 @@ pub mod loader_defaults {
             })
     }
 
+    /// Use entrypoint information to identify functions. Entrypoints that were already
+    /// identified as functions by previous strategies are skipped. Functions are named with
+    /// `name_entrypoint`.
+    pub fn strat_use_entrypoint(source: Option<&Rc<Source>>,
+                                fl: &FLResult,
+                                rmod: &RadecoModule)
+                                -> FLResult {
+        entrypoint_functions(fl, rmod, &name_entrypoint)
+    }
+
+    /// Same as `strat_use_entrypoint`, but functions are named with `name`, which is called with
+    /// the entrypoint and its index among the entrypoints of the same type.
+    pub fn strat_use_entrypoint_named<F>(name: F) -> Box<PredicatedLoader>
+        where F: Fn(&LEntryInfo, usize) -> String + 'static
+    {
+        Box::new(move |_: Option<&Rc<Source>>, fl: &FLResult, rmod: &RadecoModule| {
+            entrypoint_functions(fl, rmod, &name)
+        })
+    }
+
+    /// Name of a function for the `n`th entrypoint of its type. The entrypoint of the program
+    /// is `entry.main`, others are named by their type, such as `entry.init0` or
+    /// `entry.fini1`. Entrypoints without a type are `entry0`, `entry1` and so on.
+    pub fn name_entrypoint(entry: &LEntryInfo, n: usize) -> String {
+        match entry.etype.as_ref().map(|t| t.as_str()) {
+            None => format!("entry{}", n),
+            Some("program") if n == 0 => "entry.main".to_owned(),
+            Some(etype) => format!("entry.{}{}", etype, n),
+        }
+    }
+
+    fn entrypoint_functions(fl: &FLResult,
+                            rmod: &RadecoModule,
+                            name: &Fn(&LEntryInfo, usize) -> String)
+                            -> FLResult {
+        let mut acc = FLResult::default();
+        // Number of entrypoints seen so far, by type
+        let mut seen = HashMap::new();
+        for entry in &rmod.entrypoint {
+            let n = seen.entry(entry.etype.as_ref()).or_insert(0);
+            *n += 1;
+            let vaddr = match entry.vaddr {
+                Some(vaddr) if !fl.functions.contains_key(&vaddr) => vaddr,
+                _ => continue,
+            };
+            let mut rfn = RadecoFunction::default();
+            rfn.name = Cow::from(name(entry, *n - 1));
+            rfn.offset = vaddr;
+
+            acc.functions.insert(rfn.offset, rfn);
+            acc.new += 1;
+        }
+        acc
+    }
+
+    /// Use a CSV file with lines of the form `addr,name,size` to identify functions. Addresses
+    /// and sizes can be decimal or hex prefixed with `0x`. Malformed lines are skipped.
+    pub fn strat_from_csv<P: AsRef<Path>>(path: P) -> Box<PredicatedLoader> {
+        let path = path.as_ref().to_path_buf();
+        Box::new(move |_: Option<&Rc<Source>>, _: &FLResult, _: &RadecoModule| {
+            let mut fl = FLResult::default();
+            let f = match File::open(&path) {
+                Ok(f) => f,
+                Err(e) => {
+                    radeco_warn!("Unable to open {}: {}", path.display(), e);
+                    return fl;
+                }
+            };
+            for (i, line) in BufReader::new(f).lines().enumerate() {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(e) => {
+                        radeco_warn!("Unable to read {}: {}", path.display(), e);
+                        break;
+                    }
+                };
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                let fields = line.split(',').map(|f| f.trim()).collect::<Vec<_>>();
+                let size = fields.get(2).and_then(|s| parse_u64(s));
+                let rfn = match (fields.len(), parse_u64(fields[0]), size) {
+                    (3, Some(offset), Some(size)) if !fields[1].is_empty() => {
+                        let mut rfn = RadecoFunction::default();
+                        rfn.name = Cow::from(fields[1].to_owned());
+                        rfn.offset = offset;
+                        rfn.size = size;
+                        rfn
+                    }
+                    _ => {
+                        radeco_warn!("Skipping malformed line {} of {}: {}",
+                                     i + 1,
+                                     path.display(),
+                                     line);
+                        continue;
+                    }
+                };
+                if fl.functions.insert(rfn.offset, rfn).is_none() {
+                    fl.new += 1;
+                }
+            }
+            fl
+        })
+    }
+
+    /// Use the targets of calls in the functions identified so far to identify more functions.
+    /// Only runs after some functions were identified by earlier strategies, and needs a
+    /// `Source` to disassemble them. Functions found this way have a size of 0.
+    pub fn strat_from_call_targets() -> Box<PredicatedLoader> {
+        Box::new(CallTargets)
+    }
+
+    struct CallTargets;
+
+    impl PredicatedLoader for CallTargets {
+        fn predicate(&self, x: &FLResult) -> bool {
+            !x.functions.is_empty()
+        }
+
+        fn strategy(&self,
+                    source: Option<&Rc<Source>>,
+                    last: &FLResult,
+                    rmod: &RadecoModule)
+                    -> FLResult {
+            let mut fl = FLResult::default();
+            let src = match source {
+                Some(src) => src,
+                None => return fl,
+            };
+            for rfn in last.functions.values() {
+                let ops = match src.disassemble_n_bytes(rfn.size, rfn.offset) {
+                    Ok(ops) => ops,
+                    Err(e) => {
+                        radeco_warn!(e);
+                        continue;
+                    }
+                };
+                let targets = ops.iter()
+                    .filter(|op| op.optype.as_ref().map_or(false, |t| t == "call"))
+                    .filter_map(|op| op.jump);
+                for target in targets {
+                    // Calls through the PLT are not functions of this module.
+                    if last.functions.contains_key(&target) ||
+                       rmod.imports.contains_key(&target) ||
+                       fl.functions.contains_key(&target) {
+                        continue;
+                    }
+                    let mut rfn = RadecoFunction::default();
+                    rfn.name = Cow::from(name_by_offset(target));
+                    rfn.offset = target;
+                    fl.functions.insert(target, rfn);
+                    fl.new += 1;
+                }
+            }
+            fl
+        }
+    }
+
+    /// Name of a function without a symbol, `fcn.<offset>` as in radare2
+    pub fn name_by_offset(offset: u64) -> String {
+        format!("fcn.{:08x}", offset)
+    }
+
+    fn parse_u64(s: &str) -> Option<u64> {
+        if s.starts_with("0x") || s.starts_with("0X") {
+            u64::from_str_radix(&s[2..], 16).ok()
+        } else {
+            s.parse().ok()
+        }
+    }
+
     /// Use analysis that `Source` provides to identify functions
     pub fn strat_use_source(source: Option<&Rc<Source>>,
                             fl: &FLResult,
@@ -124,6 +336,7 @@ pub mod loader_defaults {
     }
 }
 
+#[derive(Serialize, Deserialize)]
 /// Top level container used to hold all analysis
 pub struct RadecoProject {
     /// Map of loaded modules
@@ -140,6 +353,17 @@ pub trait CGInfo {
     fn callers<'a>(&'a self, idx: NodeIndex) -> Box<Iterator<Item = (u64, NodeIndex)> + 'a>;
     // Return (callsite, call target)
     fn callees<'a>(&'a self, idx: NodeIndex) -> Box<Iterator<Item = (u64, NodeIndex)> + 'a>;
+    // Return all nodes reachable from any of `roots` (including the roots themselves)
+    fn reachable_from(&self, roots: &[NodeIndex]) -> HashSet<NodeIndex> {
+        let mut reachable = HashSet::new();
+        let mut worklist = roots.to_vec();
+        while let Some(idx) = worklist.pop() {
+            if reachable.insert(idx) {
+                worklist.extend(self.callees(idx).map(|(_, target)| target));
+            }
+        }
+        reachable
+    }
 }
 
 impl CGInfo for CallGraph {
@@ -154,7 +378,7 @@ impl CGInfo for CallGraph {
     }
 }
 
-#[derive(Default)]
+#[derive(Default, Serialize, Deserialize)]
 /// Container to store information about a single loaded binary or library.
 pub struct RadecoModule {
     /// Human-readable name for the  module
@@ -164,8 +388,24 @@ pub struct RadecoModule {
     // Information from the loader
     symbols: Vec<LSymbolInfo>,
     sections: Arc<Vec<LSectionInfo>>,
+    // (start, end, index in `sections`) of sections, sorted by start address. Built lazily.
+    #[serde(skip)]
+    section_index: RefCell<Option<Vec<(u64, u64, usize)>>>,
+    // (start, index in `symbols`) of symbols with an address, sorted by start address. Built
+    // lazily.
+    #[serde(skip)]
+    symbol_index: RefCell<Option<Vec<(u64, usize)>>>,
+    // Map from data address to the offsets of the functions referencing it. Built lazily.
+    #[serde(skip)]
+    dataref_index: RefCell<Option<HashMap<u64, Vec<u64>>>>,
+    // Map from alternate entry address to the offset of its function. Built lazily.
+    #[serde(skip)]
+    entry_index: RefCell<Option<HashMap<u64, u64>>>,
     // Map from PLT entry address to `ImportInfo` for an import
     pub imports: HashMap<u64, ImportInfo>,
+    // Map from import name to its PLT entry address
+    #[serde(default)]
+    import_names: HashMap<String, u64>,
     exports: Vec<LExportInfo>,
     relocs: Vec<LRelocInfo>,
     libs: Vec<String>,
@@ -175,24 +415,162 @@ pub struct RadecoModule {
     pub callgraph: CallGraph,
     /// Map of functions loaded
     pub functions: BTreeMap<u64, RadecoFunction>,
-    /// Source used to load this module
+    /// Calling convention assumed for the bindings, if any
+    #[serde(default)]
+    cc: Option<CallingConvention>,
+    /// Functions that do not return, in addition to `llanalyzer::NORETURN_IMPORTS`
+    #[serde(default)]
+    noreturn: HashSet<String>,
+    /// Offsets of functions for which SSA construction failed, in sorted order
+    #[serde(default)]
+    failed_ssa: Vec<u64>,
+    /// PLT addresses of imports for which SSA construction failed, in sorted order
+    #[serde(default)]
+    failed_import_ssa: Vec<u64>,
+    /// Options of the `ModuleLoader` that loaded this module
+    #[serde(default)]
+    load_config: LoadConfig,
+    /// Source used to load this module. Not serialized, has to be re-attached after
+    /// deserialization.
+    #[serde(skip)]
     pub source: Option<Rc<Source>>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// Analyses that the `ModuleLoader` was configured to run while loading a module. See
+/// `RadecoModule::load_config`.
+pub struct LoadConfig {
+    /// See `ModuleLoader::metadata_only`
+    pub metadata_only: bool,
+    /// See `ModuleLoader::build_ssa`
+    pub build_ssa: bool,
+    /// See `ModuleLoader::build_callgraph`
+    pub build_callgraph: bool,
+    /// See `ModuleLoader::assume_cc`
+    pub assume_cc: bool,
+    /// See `ModuleLoader::detect_cc`
+    pub detect_cc: bool,
+    /// See `ModuleLoader::load_datarefs`
+    pub load_datarefs: bool,
+    /// See `ModuleLoader::load_locals`
+    pub load_locals: bool,
+    /// See `ModuleLoader::stub_imports`
+    pub stub_imports: bool,
+    /// See `ModuleLoader::keep_bytes`
+    #[serde(default)]
+    pub keep_bytes: bool,
+}
+
+// `RadecoModule` taken apart into parts that can be sent to another thread, see
+// `RadecoModule::into_sent`. The r2 session of the module is sent in place of its `Source`, and
+// the functions of its imports in place of the `ImportInfo`s that share them.
+struct SentModule {
+    r2: Option<R2>,
+    name: Cow<'static, str>,
+    path: Cow<'static, str>,
+    symbols: Vec<LSymbolInfo>,
+    sections: Arc<Vec<LSectionInfo>>,
+    imports: Vec<(u64, Cow<'static, str>, RadecoFunction)>,
+    import_names: HashMap<String, u64>,
+    exports: Vec<LExportInfo>,
+    relocs: Vec<LRelocInfo>,
+    libs: Vec<String>,
+    entrypoint: Vec<LEntryInfo>,
+    callgraph: CallGraph,
+    functions: BTreeMap<u64, RadecoFunction>,
+    cc: Option<CallingConvention>,
+    noreturn: HashSet<String>,
+    failed_ssa: Vec<u64>,
+    failed_import_ssa: Vec<u64>,
+    load_config: LoadConfig,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// Differences between the functions of two modules, as computed by `RadecoModule::diff`.
+/// Offsets of the first (old) module come first.
+pub struct ModuleDiff {
+    /// Functions only found in the new module, as (offset, name)
+    pub added: Vec<(u64, String)>,
+    /// Functions only found in the old module, as (offset, name)
+    pub removed: Vec<(u64, String)>,
+    /// Functions with the same name but different code, as (old offset, new offset)
+    pub changed: Vec<(u64, u64)>,
+    /// Functions with the same code, possibly renamed, as (old offset, new offset)
+    pub matched: Vec<(u64, u64)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Instruction of a function, decoded from its disassembly, see
+/// `RadecoFunction::iter_instructions`
+pub struct Instruction {
+    pub addr: u64,
+    pub size: u64,
+    /// Mnemonic, such as `mov`, including prefixes such as `rep`. Empty if the instruction has
+    /// no disassembly.
+    pub mnemonic: String,
+    /// Operands as they appear in the disassembly, e.g. `qword [rbp - 8]`
+    pub operands: Vec<String>,
+}
+
+impl Instruction {
+    fn decode(op: &LOpInfo) -> Option<Instruction> {
+        let (addr, size) = (op.offset?, op.size?);
+        let disasm = op.opcode.as_ref().map_or("", |s| s.trim());
+        // Prefixes are part of the mnemonic, as in `rep movsb`.
+        let mut words = disasm.split_whitespace();
+        let mut mnemonic = Vec::new();
+        while let Some(word) = words.next() {
+            mnemonic.push(word);
+            if !INSN_PREFIXES.contains(&word) {
+                break;
+            }
+        }
+        let rest = words.collect::<Vec<_>>().join(" ");
+        // Commas inside memory operands, as in `[rax, rbx]` on ARM, do not split operands.
+        let mut operands = Vec::new();
+        let (mut depth, mut start) = (0i32, 0);
+        for (i, c) in rest.char_indices() {
+            match c {
+                '[' | '{' | '(' => depth += 1,
+                ']' | '}' | ')' => depth -= 1,
+                ',' if depth == 0 => {
+                    operands.push(rest[start..i].trim().to_owned());
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+        if !rest[start..].trim().is_empty() {
+            operands.push(rest[start..].trim().to_owned());
+        }
+        Some(Instruction {
+            addr: addr,
+            size: size,
+            mnemonic: mnemonic.join(" "),
+            operands: operands,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FunctionType {
     /// Function defined in the current binary
     Function,
     /// Import from another module. Set to u16::max_value() to represent `Unknown`
     /// Fixed up when the corresponding library that defines this function is loaded
     Import(u16),
+    /// PLT stub or other function that only jumps to an import. No SSA is constructed for
+    /// these.
+    Trampoline,
 }
 
-#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub enum BindingType {
     // Arguments - ith argument
     RegisterArgument(usize),
     StackArgument(usize),
+    // Variable number of arguments, following the first i arguments
+    Variadic(usize),
     // Local variables
     RegisterLocal,
     // Stack offset (from "SP")
@@ -232,9 +610,76 @@ impl BindingType {
             _ => false,
         }
     }
+
+    pub fn is_variadic(&self) -> bool {
+        match *self {
+            BindingType::Variadic(_) => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+/// Describes how arguments and return values are passed to and from functions. Registers are
+/// referred to by their alias (role) in the register profile.
+pub struct CallingConvention {
+    /// Aliases of the registers used to pass arguments, in order
+    pub args: Vec<String>,
+    /// Alias of the register used for the return value
+    pub ret: String,
+    /// Aliases of the registers holding the higher parts of return values that are wider
+    /// than `ret`, in increasing order of significance, e.g. the one of `edx` for `edx:eax`
+    #[serde(default)]
+    pub ret_hi: Vec<String>,
+    /// Whether arguments are passed on the stack, after the ones in `args`
+    #[serde(default)]
+    pub stack_args: bool,
+}
+
+impl Default for CallingConvention {
+    fn default() -> CallingConvention {
+        CallingConvention::new(&["A0", "A1", "A2", "A3", "A4", "A5"], "SN")
+    }
+}
+
+impl CallingConvention {
+    pub fn new(args: &[&str], ret: &str) -> CallingConvention {
+        CallingConvention {
+            args: args.iter().map(|a| a.to_string()).collect(),
+            ret: ret.to_owned(),
+            ret_hi: Vec::new(),
+            stack_args: false,
+        }
+    }
+
+    /// Also return wide values in the registers `ret_hi` after `ret`, see `ret_hi`
+    pub fn wide_ret(mut self, ret_hi: &[&str]) -> CallingConvention {
+        self.ret_hi = ret_hi.iter().map(|r| r.to_string()).collect();
+        self
+    }
+
+    /// Aliases of all registers used for return values, starting with `ret`. Empty if there
+    /// is no return value.
+    pub fn rets(&self) -> Vec<&str> {
+        iter::once(&self.ret)
+            .filter(|r| !r.is_empty())
+            .chain(self.ret_hi.iter())
+            .map(|r| r.as_str())
+            .collect()
+    }
+
+    /// Convention passing all arguments on the stack, such as cdecl and stdcall on x86
+    pub fn stack(ret: &str) -> CallingConvention {
+        CallingConvention {
+            args: Vec::new(),
+            ret: ret.to_owned(),
+            ret_hi: Vec::new(),
+            stack_args: true,
+        }
+    }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct VarBinding {
     pub btype: BindingType,
     name: Cow<'static, str>,
@@ -265,11 +710,39 @@ impl VarBinding {
     pub fn btype_mut(&mut self) -> &mut BindingType {
         &mut self.btype
     }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn set_name<T: Into<Cow<'static, str>>>(&mut self, name: T) {
+        self.name = name.into();
+    }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct VarBindings(Vec<VarBinding>);
 
+impl VarBindings {
+    /// Binding of the register with index `ridx` in the regfile, if any.
+    pub fn by_ridx(&self, ridx: u64) -> Option<&VarBinding> {
+        self.0.iter().find(|vb| vb.ridx == Some(ridx))
+    }
+
+    /// Binding for the SSA node `idx`, if any.
+    pub fn by_node(&self, idx: NodeIndex) -> Option<&VarBinding> {
+        self.0.iter().find(|vb| vb.idx == idx)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
 impl<'a> IntoIterator for &'a VarBindings {
     type Item = &'a VarBinding;
     type IntoIter = VarBindingIter<'a>;
@@ -287,7 +760,7 @@ impl<'a> Iterator for VarBindingIter<'a> {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 /// Container to store information about identified function.
 /// Used as a basic unit in intra-functional analysis.
 pub struct RadecoFunction {
@@ -312,6 +785,33 @@ pub struct RadecoFunction {
     cgid: NodeIndex,
     /// Variable bindings
     bindings: VarBindings,
+    /// Were the instructions loaded for the function cut short by `max_fn_bytes`
+    #[serde(default)]
+    truncated: bool,
+    /// Bytes of the function, if loaded with `ModuleLoader::keep_bytes`
+    #[serde(default)]
+    raw_bytes: Vec<u8>,
+    /// Is the return value of this function used by any of its callers
+    #[serde(default)]
+    return_live: Option<bool>,
+    /// Is the function made up of ARM Thumb instructions
+    #[serde(default)]
+    is_thumb: bool,
+    /// Does the function take a variable number of arguments
+    #[serde(default)]
+    variadic: bool,
+    /// Is the function only a jump to an imported function
+    #[serde(default)]
+    is_thunk: bool,
+    /// Original name of the function, if `name` was demangled
+    #[serde(default)]
+    mangled_name: Option<Cow<'static, str>>,
+    /// User comments, by address. Independent of the SSA, so kept across reanalysis.
+    #[serde(default)]
+    comments: BTreeMap<u64, String>,
+    /// Addresses other than `offset` at which the function can be entered
+    #[serde(default)]
+    alt_entries: Vec<u64>,
 }
 
 #[derive(Default)]
@@ -322,15 +822,18 @@ pub struct ProjectLoader<'a> {
     load_library_path: Option<Cow<'static, str>>,
     filter_modules: Option<fn(&RadecoModule) -> bool>,
     source: Option<Rc<Source>>,
+    r2: Option<R2>,
     mloader: Option<ModuleLoader<'a>>,
+    parallel: bool,
+    arch: Option<Cow<'static, str>>,
+    bits: Option<u32>,
+    slice: Option<Cow<'static, str>>,
+    only_libs: Option<Vec<String>>,
 }
 
 impl<'a> ProjectLoader<'a> {
     // TODO:
     //  - Associate identified bins/libs with their ModuleLoaders
-    //  - Implement loading of libraries
-    //  - Parallelize module loading as they should have different sources
-    //  - Use filter option
     //  - Setup arch information in `RadecoProject`
     /// Enable loading of libraries
     pub fn load_libs(mut self) -> ProjectLoader<'a> {
@@ -357,6 +860,14 @@ impl<'a> ProjectLoader<'a> {
         self
     }
 
+    /// Use an already running r2 session as the source, instead of spawning a new one for
+    /// `path`. Any analysis or configuration done in the session is kept. `path` is optional
+    /// in this case.
+    pub fn attach_r2(mut self, r2: R2) -> ProjectLoader<'a> {
+        self.r2 = Some(r2);
+        self
+    }
+
     /// Set path to look for libraries. The `ProjectLoader` looks for
     /// matching filenames recursively within this directory.
     /// Only used if `load_libs` is true.
@@ -365,118 +876,739 @@ impl<'a> ProjectLoader<'a> {
         self
     }
 
-    /// Filter loading of `RadecoModules` based on `f`
+    /// Only load the libraries in `libs`, which are matched against the file names of the
+    /// libraries the main binary links against, e.g. `libcrypto.so.1.1`. Only used if
+    /// `load_libs` is true.
+    pub fn only_libs(mut self, libs: &[&str]) -> ProjectLoader<'a> {
+        self.only_libs = Some(libs.iter().map(|l| l.to_string()).collect());
+        self
+    }
+
+    /// Filter loading of `RadecoModules` based on `f`. Only applies to libraries, the main
+    /// binary is always loaded.
     pub fn filter_modules(mut self, f: fn(&RadecoModule) -> bool) -> ProjectLoader<'a> {
         self.filter_modules = Some(f);
         self
     }
 
+    /// Override the architecture detected for the main binary, e.g. for raw firmware images.
+    /// Applied to the `Source` through `Source::set_arch` before anything is loaded from it.
+    /// r2 sources set `asm.arch`, `BufferSource` switches between `x86` and `x86_64`. Loading
+    /// fails with `LoadError::ArchOverride` for sources that do not support overrides.
+    pub fn arch<T: AsRef<str>>(mut self, arch: T) -> ProjectLoader<'a> {
+        self.arch = Some(Cow::from(arch.as_ref().to_owned()));
+        self
+    }
+
+    /// Override the word size in bits of the main binary. See `arch`. r2 sources set
+    /// `asm.bits`.
+    pub fn bits(mut self, bits: u32) -> ProjectLoader<'a> {
+        self.bits = Some(bits);
+        self
+    }
+
+    /// Select the architecture slice to load from a Mach-O universal (fat) binary at `path`,
+    /// by its name as given by `list_slices`, e.g. `x86_64` or `arm64`. The slice is copied
+    /// to a new file in the temporary directory, which r2 is opened on and which is removed
+    /// again once r2 has opened it. Ignored if a `source` is set. Loading fails with
+    /// `LoadError::NoSlice` if the binary has no such slice.
+    pub fn slice<T: AsRef<str>>(mut self, arch: T) -> ProjectLoader<'a> {
+        self.slice = Some(Cow::from(arch.as_ref().to_owned()));
+        self
+    }
+
+    /// Names of the architecture slices of the Mach-O universal binary at `path`, in the
+    /// order they are stored in. Empty if the file is not a universal binary.
+    pub fn list_slices<P: AsRef<Path>>(path: P) -> io::Result<Vec<String>> {
+        let mut data = Vec::new();
+        File::open(path)?.read_to_end(&mut data)?;
+        Ok(fat_slices(&data)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(arch, _, _)| arch)
+            .collect())
+    }
+
+    /// Load the libraries in parallel, on the global rayon thread pool, while the main binary
+    /// is loaded. Mirrors `ModuleLoader::parallel`. The modules of the project are in the same
+    /// order either way, with the main binary first.
+    ///
+    /// `Source`s cannot be shared between threads, so every library is opened in an r2 session
+    /// of its own by the worker loading it. For the same reason, libraries are loaded with the
+    /// options of the configured `ModuleLoader`, but with the default function identification
+    /// strategies, see `FunctionLoader::include_defaults`.
+    pub fn parallel(mut self) -> ProjectLoader<'a> {
+        self.parallel = true;
+        self
+    }
+
+    // Spawn r2 for `path`, or for a copy of its `slice`
+    fn open_r2(&self) -> Result<R2, LoadError> {
+        if self.path.is_empty() {
+            return Err(LoadError::NoSource);
+        }
+        fs::metadata(self.path.as_ref())?;
+        let open_path = match self.slice {
+            Some(ref arch) => extract_slice(Path::new(self.path.as_ref()), arch)?,
+            None => PathBuf::from(self.path.as_ref()),
+        };
+        // Load r2 source.
+        let r2 = R2::new(Some(open_path.to_string_lossy()));
+        // r2 has the copy of the slice open now, it is not needed anymore.
+        if self.slice.is_some() {
+            let _ = fs::remove_file(&open_path);
+        }
+        r2.map_err(|e| LoadError::R2Spawn(e.to_string()))
+    }
+
+    // Libraries in `libs` selected by `only_libs`
+    fn wanted_libs<'l>(&self, libs: &'l [String]) -> Vec<&'l str> {
+        libs.iter()
+            .map(|lib| lib.as_str())
+            .filter(|lib| {
+                self.only_libs.as_ref().map_or(true, |only| {
+                    let name = Path::new(lib).file_name().and_then(|n| n.to_str()).unwrap_or(lib);
+                    only.iter().any(|o| o == name)
+                })
+            })
+            .collect()
+    }
+
+    // Paths on disk for the libraries in `libs` that could be found in `load_library_path`.
+    fn library_paths(&self, libs: &[&str]) -> Vec<PathBuf> {
+        let lib_dir = match self.load_library_path {
+            Some(ref lib_dir) => lib_dir,
+            None => {
+                radeco_warn!("load_libs is set, but no load_library_path was provided");
+                return Vec::new();
+            }
+        };
+
+        libs.iter()
+            .filter_map(|lib| {
+                let path = find_library(Path::new(lib_dir.as_ref()), lib);
+                if path.is_none() {
+                    radeco_warn!("Unable to find library {} in {}", lib, lib_dir);
+                }
+                path
+            })
+            .collect()
+    }
+
     /// Kick everything off based on the config/defaults
-    pub fn load(mut self) -> RadecoProject {
+    pub fn load(self) -> Result<RadecoProject, LoadError> {
+        self.load_sessions(None).map(|(rproj, _)| rproj)
+    }
+
+    // `load`, sending a `LoadEvent` to `events` for every module and function done. Also
+    // returns the r2 session of every module of the project, in the same order. `None` for
+    // modules loaded from a `Source` that was set up front.
+    fn load_sessions(mut self,
+                     events: Option<EventSender>)
+                     -> Result<(RadecoProject, Vec<Option<WrappedR2Api<R2>>>), LoadError> {
+        let mut main_session = None;
         if self.source.is_none() {
-            // Load r2 source.
-            let mut r2 = R2::new(Some(&self.path)).expect("Unable to open r2");
-            let mut r2w: WrappedR2Api<R2> = Rc::new(RefCell::new(r2));
-            self.source = Some(Rc::new(r2w));
+            let r2 = match self.r2.take() {
+                Some(r2) => r2,
+                None => self.open_r2()?,
+            };
+            let r2w: WrappedR2Api<R2> = Rc::new(RefCell::new(r2));
+            self.source = Some(Rc::new(Rc::clone(&r2w)));
+            main_session = Some(r2w);
         };
 
         let source = self.source.as_ref().unwrap();
 
         // TODO: Load more arch specific information from the source
+        // Overrides must be in place before the register profile is fetched.
+        if self.arch.is_some() || self.bits.is_some() {
+            source.set_arch(self.arch.as_ref().map(|a| a.as_ref()), self.bits)
+                .map_err(LoadError::ArchOverride)?;
+        }
 
         if self.mloader.is_none() {
             self.mloader = Some(ModuleLoader::default().source(Rc::clone(source)));
         }
 
-        let mut mod_map = Vec::new();
-
         {
             let mod_loader = self.mloader.as_mut().unwrap();
-            // TODO: Set name correctly
-            mod_map.push(mod_loader.load(Rc::clone(source)));
+            if mod_loader.path.is_none() && !self.path.is_empty() {
+                mod_loader.path = Some(self.path.clone());
+            }
+            if self.load_libs {
+                // Imports need stubs so that they can be linked against the libraries later.
+                mod_loader.stub_imports = true;
+            }
+            mod_loader.events = events.clone();
+        }
+
+        // With `parallel`, the libraries are loaded by the workers while the main binary is
+        // loaded on this thread. They are taken from the `Source`, as the main module is not
+        // there yet.
+        let workers = if self.load_libs && self.parallel {
+            let libs = source.libraries().unwrap_or_else(|e| {
+                radeco_warn!(e);
+                Vec::new()
+            });
+            let lib_paths = self.library_paths(&self.wanted_libs(&libs));
+            let mut opts = self.mloader.as_ref().unwrap().options();
+            opts.name = None;
+            opts.path = None;
+            Some(thread::spawn(move || {
+                lib_paths.par_iter()
+                    .map(|path| {
+                        let (rmod, session) = load_library(opts.loader(), path)?;
+                        Some(rmod.into_sent(Some(session)))
+                    })
+                    .collect::<Vec<_>>()
+            }))
+        } else {
+            None
+        };
+
+        let mut mod_map = Vec::new();
+        let mut sessions = Vec::new();
+        mod_map.push(self.mloader.as_mut().unwrap().load(Rc::clone(source))?);
+        sessions.push(main_session);
+        if let Some(ref events) = events {
+            send_event(events, LoadEvent::ModuleLoaded(mod_map[0].name.to_string()));
+        }
+
+        if self.load_libs {
+            let lib_mods: Vec<(RadecoModule, Option<WrappedR2Api<R2>>)> = match workers {
+                Some(workers) => {
+                    let sent = workers.join().unwrap_or_else(|_| {
+                        radeco_warn!("Unable to load libraries, a worker panicked");
+                        Vec::new()
+                    });
+                    sent.into_iter().filter_map(|m| m).map(RadecoModule::from_sent).collect()
+                }
+                None => {
+                    let lib_paths = self.library_paths(&self.wanted_libs(&mod_map[0].libs));
+                    // Every library gets its own `ModuleLoader`, configured like the one used
+                    // for the main binary, but with its own `Source`.
+                    let mut proto = self.mloader.as_ref().unwrap().clone();
+                    proto.source = None;
+                    proto.name = None;
+                    proto.path = None;
+                    if let Some(ref mut floader) = proto.floader {
+                        floader.source = None;
+                    }
+                    lib_paths.iter()
+                        .filter_map(|path| load_library(proto.clone(), path))
+                        .map(|(rmod, session)| (rmod, Some(session)))
+                        .collect()
+                }
+            };
+
+            for (lib_mod, session) in lib_mods {
+                if let Some(ref f) = self.filter_modules {
+                    if !f(&lib_mod) {
+                        continue;
+                    }
+                }
+                if let Some(ref events) = events {
+                    send_event(events, LoadEvent::ModuleLoaded(lib_mod.name.to_string()));
+                }
+                mod_map.push(lib_mod);
+                sessions.push(session);
+            }
         }
 
         // Clear out irrelevant fields in self and move it into project loader
         // XXX: Do when needed!
         // self.mod_loader = None;
         let regfile = SubRegisterFile::new(&source.register_profile()
-            .expect("Unable to load register profile"));
+            .map_err(LoadError::RegisterProfile)?);
 
-        RadecoProject {
+        let mut rproj = RadecoProject {
             modules: mod_map,
             // XXX
             reginfo: Arc::new(regfile),
+        };
+
+        if self.load_libs {
+            rproj.resolve_imports();
         }
+
+        rproj.validate_arch().map_err(LoadError::ArchMismatch)?;
+        Ok((rproj, sessions))
     }
 }
 
-// Iterators over RadecoProject to yeils RadecoModules
-/// `RadecoModule` with project information `zipped` into it
-pub struct ZippedModule<'m> {
-    pub project: &'m RadecoProject,
-    pub module: &'m RadecoModule,
+impl<'a> ProjectLoader<'a> {
+    /// Load the project on a separate thread, reporting the progress through the returned
+    /// receiver. With `ModuleLoader::build_ssa`, a `FunctionAnalyzed` event is sent for every
+    /// function as its SSA is constructed, followed by `ModuleLoaded` once its module is done.
+    /// The last event is either `Done` with the project or `Failed`.
+    ///
+    /// Loaders hold `Rc`s and cannot be sent to another thread as they are. The `ModuleLoader`
+    /// is sent with its options, but with the default function identification strategies, as
+    /// for the libraries with `parallel`. A `Source` set up front, on this loader or on its
+    /// `ModuleLoader`, cannot be sent at all, so only projects loaded from `path` or from a
+    /// session passed to `attach_r2` can be streamed. Otherwise the only event is `Failed`.
+    pub fn load_streaming(self) -> mpsc::Receiver<LoadEvent> {
+        let (tx, rx) = mpsc::channel();
+        let events = Arc::new(Mutex::new(tx));
+        match self.into_sent() {
+            Some(sent) => {
+                thread::spawn(move || {
+                    let last = match sent.loader().load_sessions(Some(Arc::clone(&events))) {
+                        Ok((rproj, sessions)) => {
+                            LoadEvent::Done(LoadedProject::new(rproj, sessions))
+                        }
+                        Err(e) => LoadEvent::Failed(e.to_string()),
+                    };
+                    send_event(&events, last);
+                });
+            }
+            None => {
+                let e = "Sources set up front cannot be sent to the loading thread".to_owned();
+                send_event(&events, LoadEvent::Failed(e));
+            }
+        }
+        rx
+    }
+
+    // Take the loader apart to send it to another thread, or `None` if it has a `Source`
+    fn into_sent(self) -> Option<SentLoader> {
+        let has_source = self.mloader.as_ref().map_or(false, |mloader| {
+            mloader.source.is_some() ||
+            mloader.floader.as_ref().map_or(false, |floader| floader.source.is_some())
+        });
+        if self.source.is_some() || has_source {
+            return None;
+        }
+        Some(SentLoader {
+            load_libs: self.load_libs,
+            path: self.path,
+            load_library_path: self.load_library_path,
+            filter_modules: self.filter_modules,
+            r2: self.r2,
+            mloader: self.mloader.as_ref().map(|mloader| mloader.options()),
+            parallel: self.parallel,
+            arch: self.arch,
+            bits: self.bits,
+            slice: self.slice,
+            only_libs: self.only_libs,
+        })
+    }
 }
 
-pub struct ModuleIter<'m> {
-    project: &'m RadecoProject,
-    iter: slice::Iter<'m, RadecoModule>,
+// `ProjectLoader` without a `Source`, taken apart into parts that can be sent to another
+// thread, see `ProjectLoader::into_sent`
+struct SentLoader {
+    load_libs: bool,
+    path: Cow<'static, str>,
+    load_library_path: Option<Cow<'static, str>>,
+    filter_modules: Option<fn(&RadecoModule) -> bool>,
+    r2: Option<R2>,
+    mloader: Option<LoaderOptions>,
+    parallel: bool,
+    arch: Option<Cow<'static, str>>,
+    bits: Option<u32>,
+    slice: Option<Cow<'static, str>>,
+    only_libs: Option<Vec<String>>,
 }
 
-// TODO: Add a way to access the project
-pub struct ZippedModuleMut<'m> {
-    pub module: &'m mut RadecoModule,
+impl SentLoader {
+    // Put the loader back together
+    fn loader(self) -> ProjectLoader<'static> {
+        ProjectLoader {
+            load_libs: self.load_libs,
+            path: self.path,
+            load_library_path: self.load_library_path,
+            filter_modules: self.filter_modules,
+            source: None,
+            r2: self.r2,
+            mloader: self.mloader.map(|opts| opts.loader()),
+            parallel: self.parallel,
+            arch: self.arch,
+            bits: self.bits,
+            slice: self.slice,
+            only_libs: self.only_libs,
+        }
+    }
 }
 
-pub struct ModuleIterMut<'m> {
-    iter: slice::IterMut<'m, RadecoModule>,
+/// Progress of loading a project, see `ProjectLoader::load_streaming`
+pub enum LoadEvent {
+    /// A module with the given name was loaded
+    ModuleLoaded(String),
+    /// SSA construction for the function at the given offset is done, whether it succeeded
+    /// or not
+    FunctionAnalyzed(u64),
+    /// Loading is done, see `LoadedProject::into_project`
+    Done(LoadedProject),
+    /// Loading failed, with the error message
+    Failed(String),
 }
 
-impl<'m> Iterator for ModuleIter<'m> {
-    type Item = ZippedModule<'m>;
-    fn next(&mut self) -> Option<ZippedModule<'m>> {
-        if let Some(rmod) = self.iter.next() {
-            Some(ZippedModule {
-                project: &self.project,
-                module: rmod,
-            })
-        } else {
-            None
+/// Project loaded by `ProjectLoader::load_streaming`. Projects hold `Rc`s and cannot be sent
+/// to another thread, so the project is sent taken apart, along with the r2 sessions its
+/// modules were loaded from.
+pub struct LoadedProject {
+    modules: Vec<SentModule>,
+    reginfo: Arc<SubRegisterFile>,
+}
+
+impl LoadedProject {
+    // Take `rproj` apart, along with the r2 `sessions` of its modules
+    fn new(rproj: RadecoProject, sessions: Vec<Option<WrappedR2Api<R2>>>) -> LoadedProject {
+        LoadedProject {
+            modules: rproj.modules
+                .into_iter()
+                .zip(sessions)
+                .map(|(rmod, session)| rmod.into_sent(session))
+                .collect(),
+            reginfo: rproj.reginfo,
         }
     }
-}
 
-impl<'m> Iterator for ModuleIterMut<'m> {
-    type Item = ZippedModuleMut<'m>;
-    fn next(&mut self) -> Option<ZippedModuleMut<'m>> {
-        if let Some(rmod) = self.iter.next() {
-            Some(ZippedModuleMut { module: rmod })
-        } else {
-            None
+    /// Put the project back together on the receiving thread. Every module gets the r2
+    /// session it was loaded from as its `Source` again, so that e.g.
+    /// `RadecoModule::read_bytes` keeps working.
+    pub fn into_project(self) -> RadecoProject {
+        RadecoProject {
+            modules: self.modules.into_iter().map(|m| RadecoModule::from_sent(m).0).collect(),
+            reginfo: self.reginfo,
         }
     }
 }
 
-pub struct ZippedFunction<'f> {
-    pub module: &'f RadecoModule,
-    pub function: (&'f u64, &'f RadecoFunction),
-}
+type EventSender = Arc<Mutex<mpsc::Sender<LoadEvent>>>;
 
-pub struct FunctionIter<'f> {
-    module: &'f RadecoModule,
-    iter: btree_map::Iter<'f, u64, RadecoFunction>,
+// Send `event` to `events`, ignoring a receiver that hung up.
+fn send_event(events: &EventSender, event: LoadEvent) {
+    if let Ok(tx) = events.lock() {
+        let _ = tx.send(event);
+    }
 }
 
-// TODO: Add a way to access module
-pub struct ZippedFunctionMut<'f> {
-    pub function: (&'f u64, &'f mut RadecoFunction),
+#[derive(Debug)]
+/// Errors that can occur while loading a `RadecoProject` or a `RadecoModule`
+pub enum LoadError {
+    /// Unable to spawn r2 to use as `Source`
+    R2Spawn(String),
+    /// Unable to get the register profile from the `Source`
+    RegisterProfile(SourceErr),
+    /// Neither a `Source` nor a path to load from was provided
+    NoSource,
+    /// A module was loaded with a register profile different from the project's
+    ArchMismatch(ArchMismatch),
+    /// Unable to apply the architecture override to the `Source`
+    ArchOverride(SourceErr),
+    /// The binary is not a universal binary with the selected architecture slice
+    NoSlice(String),
+    Io(io::Error),
 }
 
-pub struct FunctionIterMut<'f> {
-    iter: btree_map::IterMut<'f, u64, RadecoFunction>,
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LoadError::R2Spawn(ref e) => write!(f, "Unable to open r2: {}", e),
+            LoadError::RegisterProfile(ref e) => {
+                write!(f, "Unable to load register profile: {}", e)
+            }
+            LoadError::NoSource => write!(f, "No source or path to load from"),
+            LoadError::ArchMismatch(ref e) => write!(f, "{}", e),
+            LoadError::ArchOverride(ref e) => write!(f, "Unable to override architecture: {}", e),
+            LoadError::NoSlice(ref arch) => write!(f, "No {} slice in universal binary", arch),
+            LoadError::Io(ref e) => write!(f, "{}", e),
+        }
+    }
 }
 
-
-impl<'f> Iterator for FunctionIter<'f> {
+impl Error for LoadError {
+    fn description(&self) -> &str {
+        match *self {
+            LoadError::R2Spawn(_) => "unable to open r2",
+            LoadError::RegisterProfile(_) => "unable to load register profile",
+            LoadError::NoSource => "no source or path to load from",
+            LoadError::ArchMismatch(ref e) => e.description(),
+            LoadError::ArchOverride(_) => "unable to override architecture",
+            LoadError::NoSlice(_) => "no such slice in universal binary",
+            LoadError::Io(ref e) => e.description(),
+        }
+    }
+}
+
+#[derive(Debug)]
+/// Error returned by `RadecoProject::validate_arch` for a module whose register profile
+/// does not match the one of the project
+pub struct ArchMismatch {
+    /// Index of the offending module in the project
+    pub module: usize,
+    /// Name of the offending module
+    pub name: String,
+}
+
+impl fmt::Display for ArchMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,
+               "Register profile of module {} ({}) does not match the project",
+               self.module,
+               self.name)
+    }
+}
+
+impl Error for ArchMismatch {
+    fn description(&self) -> &str {
+        "register profile of module does not match the project"
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Where an address lies in a project, see `RadecoProject::resolve_address`
+pub struct SymbolRef<'a> {
+    /// Index of the module containing the address
+    pub module: usize,
+    /// Offset of the function containing the address, if any
+    pub function: Option<u64>,
+    /// Name of the function, or else of the symbol, containing the address
+    pub name: Option<&'a str>,
+    /// Offset of the address from the start of the function, symbol or else section. Negative
+    /// for alternate entries before the offset of their function.
+    pub offset: i64,
+    /// Name of the section containing the address
+    pub section: Option<&'a str>,
+    /// Indices of the other modules that also contain the address
+    pub overlaps: Vec<usize>,
+}
+
+impl From<io::Error> for LoadError {
+    fn from(e: io::Error) -> LoadError {
+        LoadError::Io(e)
+    }
+}
+
+// Open the file at `path` with r2 to use as `Source`.
+fn open_source(path: &Path) -> Option<WrappedR2Api<R2>> {
+    match R2::new(Some(path.to_string_lossy())) {
+        Ok(r2) => Some(Rc::new(RefCell::new(r2))),
+        Err(e) => {
+            radeco_warn!("Unable to open r2 for {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+// Open the library at `path` with r2 and load it using `loader`. Also returns the r2 session,
+// which is the `Source` of the module.
+fn load_library<'a>(loader: ModuleLoader<'a>,
+                    path: &Path)
+                    -> Option<(RadecoModule, WrappedR2Api<R2>)> {
+    let r2w = open_source(path)?;
+    let src: Rc<Source> = Rc::new(Rc::clone(&r2w));
+    let mut loader = loader.source(Rc::clone(&src))
+        .path(path.to_string_lossy())
+        .stub_imports();
+    match loader.load(src) {
+        Ok(rmod) => Some((rmod, r2w)),
+        Err(e) => {
+            radeco_warn!("Unable to load {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+// Magic numbers of Mach-O universal binaries with 32 and 64-bit slice offsets
+const FAT_MAGIC: u32 = 0xcafebabe;
+const FAT_MAGIC_64: u32 = 0xcafebabf;
+
+// (architecture name, offset, size) of the slices of a Mach-O universal binary, or `None` if
+// `data` is not one. The header and slice table are big endian.
+fn fat_slices(data: &[u8]) -> Option<Vec<(String, u64, u64)>> {
+    let be32 = |at: usize| {
+        data.get(at..at + 4)
+            .map(|b| b.iter().fold(0u64, |acc, &byte| (acc << 8) | byte as u64))
+    };
+    let be64 = |at: usize| Some((be32(at)? << 32) | be32(at + 4)?);
+    let (entry_size, wide) = match be32(0)? as u32 {
+        FAT_MAGIC => (20, false),
+        FAT_MAGIC_64 => (32, true),
+        _ => return None,
+    };
+    let nslices = be32(4)? as usize;
+    // Java class files share the magic number, with the class file version in place of the
+    // number of slices.
+    if nslices >= 20 {
+        return None;
+    }
+    (0..nslices)
+        .map(|i| {
+            let at = 8 + i * entry_size;
+            let cputype = be32(at)? as u32;
+            let (offset, size) = if wide {
+                (be64(at + 8)?, be64(at + 16)?)
+            } else {
+                (be32(at + 8)?, be32(at + 12)?)
+            };
+            let arch = match cputype {
+                7 => "i386".to_owned(),
+                0x0100_0007 => "x86_64".to_owned(),
+                12 => "arm".to_owned(),
+                0x0100_000c => "arm64".to_owned(),
+                18 => "ppc".to_owned(),
+                0x0100_0012 => "ppc64".to_owned(),
+                _ => format!("cpu_{:#x}", cputype),
+            };
+            Some((arch, offset, size))
+        })
+        .collect()
+}
+
+// Number of slices extracted so far, part of the names of the copies
+static SLICES_EXTRACTED: AtomicUsize = atomic::ATOMIC_USIZE_INIT;
+
+// Copy the `arch` slice of the universal binary at `path` to the temporary directory and
+// return the path of the copy. The copy gets a fresh name and is created exclusively, so
+// that no existing file is written to. The caller has to remove it.
+fn extract_slice(path: &Path, arch: &str) -> Result<PathBuf, LoadError> {
+    let mut data = Vec::new();
+    File::open(path)?.read_to_end(&mut data)?;
+    let (offset, size) = fat_slices(&data)
+        .and_then(|slices| slices.into_iter().find(|s| s.0 == arch))
+        .map(|(_, offset, size)| (offset as usize, size as usize))
+        .ok_or_else(|| LoadError::NoSlice(arch.to_owned()))?;
+    let bytes = data.get(offset..offset.saturating_add(size))
+        .ok_or_else(|| LoadError::NoSlice(arch.to_owned()))?;
+    let name = path.file_name().map_or(Cow::from("fat"), |n| n.to_string_lossy());
+    loop {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.subsec_nanos())
+            .unwrap_or(0);
+        let n = SLICES_EXTRACTED.fetch_add(1, atomic::Ordering::SeqCst);
+        let slice_path = env::temp_dir().join(format!("{}.{}.{:x}{:x}", name, arch, nanos, n));
+        let mut f = match OpenOptions::new().write(true).create_new(true).open(&slice_path) {
+            Ok(f) => f,
+            Err(ref e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e.into()),
+        };
+        if let Err(e) = f.write_all(bytes) {
+            let _ = fs::remove_file(&slice_path);
+            return Err(e.into());
+        }
+        return Ok(slice_path);
+    }
+}
+
+// Recursively search `dir` for a file named `name`.
+fn find_library(dir: &Path, name: &str) -> Option<PathBuf> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return None,
+    };
+
+    let mut subdirs = Vec::new();
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.is_dir() {
+            subdirs.push(path);
+        } else if entry.file_name().to_str() == Some(name) {
+            return Some(path);
+        }
+    }
+
+    subdirs.iter().filter_map(|d| find_library(d, name)).next()
+}
+
+// Iterators over RadecoProject to yeils RadecoModules
+/// `RadecoModule` with project information `zipped` into it
+pub struct ZippedModule<'m> {
+    pub project: &'m RadecoProject,
+    pub module: &'m RadecoModule,
+}
+
+pub struct ModuleIter<'m> {
+    project: &'m RadecoProject,
+    iter: slice::Iter<'m, RadecoModule>,
+}
+
+// TODO: Add a way to access the project
+pub struct ZippedModuleMut<'m> {
+    pub module: &'m mut RadecoModule,
+}
+
+pub struct ModuleIterMut<'m> {
+    iter: slice::IterMut<'m, RadecoModule>,
+}
+
+impl<'m> Iterator for ModuleIter<'m> {
+    type Item = ZippedModule<'m>;
+    fn next(&mut self) -> Option<ZippedModule<'m>> {
+        if let Some(rmod) = self.iter.next() {
+            Some(ZippedModule {
+                project: &self.project,
+                module: rmod,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl<'m> Iterator for ModuleIterMut<'m> {
+    type Item = ZippedModuleMut<'m>;
+    fn next(&mut self) -> Option<ZippedModuleMut<'m>> {
+        if let Some(rmod) = self.iter.next() {
+            Some(ZippedModuleMut { module: rmod })
+        } else {
+            None
+        }
+    }
+}
+
+/// Function of a callgraph node, as returned by `RadecoModule::function_at_node`. Derefs to the
+/// `RadecoFunction`.
+pub enum NodeFunction<'m> {
+    /// Function defined in the module
+    Local(&'m RadecoFunction),
+    /// Stub of an imported function
+    Import(Ref<'m, RadecoFunction>),
+}
+
+impl<'m> NodeFunction<'m> {
+    pub fn is_import(&self) -> bool {
+        match *self {
+            NodeFunction::Local(_) => false,
+            NodeFunction::Import(_) => true,
+        }
+    }
+}
+
+impl<'m> Deref for NodeFunction<'m> {
+    type Target = RadecoFunction;
+    fn deref(&self) -> &RadecoFunction {
+        match *self {
+            NodeFunction::Local(rfn) => rfn,
+            NodeFunction::Import(ref rfn) => &*rfn,
+        }
+    }
+}
+
+pub struct ZippedFunction<'f> {
+    pub module: &'f RadecoModule,
+    pub function: (&'f u64, &'f RadecoFunction),
+}
+
+pub struct FunctionIter<'f> {
+    module: &'f RadecoModule,
+    iter: btree_map::Iter<'f, u64, RadecoFunction>,
+}
+
+// TODO: Add a way to access module
+pub struct ZippedFunctionMut<'f> {
+    pub function: (&'f u64, &'f mut RadecoFunction),
+}
+
+pub struct FunctionIterMut<'f> {
+    iter: btree_map::IterMut<'f, u64, RadecoFunction>,
+}
+
+
+impl<'f> Iterator for FunctionIter<'f> {
     type Item = ZippedFunction<'f>;
     fn next(&mut self) -> Option<ZippedFunction<'f>> {
         if let Some(rfn) = self.iter.next() {
@@ -501,19 +1633,99 @@ impl<'f> Iterator for FunctionIterMut<'f> {
     }
 }
 
-#[derive(Default)]
+// Iterators over all functions in all modules of a RadecoProject
+/// Yields (module index, offset, function)
+pub struct AllFunctions<'p> {
+    modules: iter::Enumerate<slice::Iter<'p, RadecoModule>>,
+    current: Option<(usize, btree_map::Iter<'p, u64, RadecoFunction>)>,
+}
+
+/// Yields (module index, offset, function)
+pub struct AllFunctionsMut<'p> {
+    modules: iter::Enumerate<slice::IterMut<'p, RadecoModule>>,
+    current: Option<(usize, btree_map::IterMut<'p, u64, RadecoFunction>)>,
+}
+
+impl<'p> Iterator for AllFunctions<'p> {
+    type Item = (usize, u64, &'p RadecoFunction);
+    fn next(&mut self) -> Option<(usize, u64, &'p RadecoFunction)> {
+        loop {
+            if let Some((midx, ref mut functions)) = self.current {
+                if let Some((&off, rfn)) = functions.next() {
+                    return Some((midx, off, rfn));
+                }
+            }
+            match self.modules.next() {
+                Some((midx, rmod)) => self.current = Some((midx, rmod.functions.iter())),
+                None => return None,
+            }
+        }
+    }
+}
+
+impl<'p> Iterator for AllFunctionsMut<'p> {
+    type Item = (usize, u64, &'p mut RadecoFunction);
+    fn next(&mut self) -> Option<(usize, u64, &'p mut RadecoFunction)> {
+        loop {
+            if let Some((midx, ref mut functions)) = self.current {
+                if let Some((&off, rfn)) = functions.next() {
+                    return Some((midx, off, rfn));
+                }
+            }
+            match self.modules.next() {
+                Some((midx, rmod)) => self.current = Some((midx, rmod.functions.iter_mut())),
+                None => return None,
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+/// Time spent in each phase of `ModuleLoader::load_timed`
+pub struct LoadTimings {
+    /// Loading module level information, such as symbols, sections and imports
+    pub metadata: Duration,
+    /// Identifying functions with the `FunctionLoader`
+    pub functions: Duration,
+    /// Disassembling the functions
+    pub disassembly: Duration,
+    /// Constructing the SSA of functions and imports
+    pub ssa: Duration,
+    /// Building the callgraph, loading data references and locals and setting up bindings
+    pub callgraph: Duration,
+}
+
+#[derive(Default, Clone)]
 /// Module-level loader used to construct a `RadecoModule`
 pub struct ModuleLoader<'a> {
     source: Option<Rc<Source>>,
+    name: Option<Cow<'static, str>>,
+    path: Option<Cow<'static, str>>,
     floader: Option<FunctionLoader<'a>>,
     filter: Option<fn(&RadecoFunction) -> bool>,
+    filter_range: Option<(u64, u64)>,
+    min_fn_size: Option<usize>,
+    max_fn_bytes: Option<usize>,
+    max_ssa_nodes: Option<usize>,
+    on_progress: Option<fn(usize, usize)>,
+    post_ssa: Option<fn(&mut RadecoFunction, &SubRegisterFile)>,
+    name_scheme: Option<fn(u64) -> String>,
+    events: Option<EventSender>,
     build_callgraph: bool,
     build_ssa: bool,
     load_datarefs: bool,
     load_locals: bool,
     parallel: bool,
+    num_threads: Option<usize>,
     assume_cc: bool,
+    detect_cc: bool,
+    cc: CallingConvention,
     stub_imports: bool,
+    import_signatures: HashMap<String, ImportSignature>,
+    noreturn_functions: Vec<String>,
+    metadata_only: bool,
+    demangle: bool,
+    keep_bytes: bool,
 }
 
 impl<'a> ModuleLoader<'a> {
@@ -530,6 +1742,18 @@ impl<'a> ModuleLoader<'a> {
         self
     }
 
+    /// Set the name of the module to load. Defaults to the basename of `path`.
+    pub fn name<T: AsRef<str>>(mut self, name: T) -> ModuleLoader<'a> {
+        self.name = Some(Cow::from(name.as_ref().to_owned()));
+        self
+    }
+
+    /// Set the path on disk of the module to load
+    pub fn path<T: AsRef<str>>(mut self, path: T) -> ModuleLoader<'a> {
+        self.path = Some(Cow::from(path.as_ref().to_owned()));
+        self
+    }
+
     /// Builds callgraph. Needs support from `Source`
     pub fn build_callgraph(mut self) -> ModuleLoader<'a> {
         self.build_callgraph = true;
@@ -556,13 +1780,19 @@ impl<'a> ModuleLoader<'a> {
         self
     }
 
-    /// Executes parallelizable functions in parallel. Uses `num_thread` number
-    /// of threads. Defaults to 8 if `None`.
+    /// Executes parallelizable functions in parallel, using the global rayon thread pool.
     pub fn parallel(mut self) -> ModuleLoader<'a> {
         self.parallel = true;
         self
     }
 
+    /// Same as `parallel`, but uses a separate thread pool of `num_threads` threads.
+    pub fn parallel_with(mut self, num_threads: usize) -> ModuleLoader<'a> {
+        self.parallel = true;
+        self.num_threads = Some(num_threads);
+        self
+    }
+
     /// Assume calling convention information in regfile to be true. This is used for setting up
     /// bindings for arguments and return values for functions.
     pub fn assume_cc(mut self) -> ModuleLoader<'a> {
@@ -570,6 +1800,22 @@ impl<'a> ModuleLoader<'a> {
         self
     }
 
+    /// Detect the calling convention of every function from its SSA, instead of assuming it.
+    /// The detected convention is used for setting up the bindings of the function. Needs
+    /// `build_ssa` and `build_callgraph`. Imports, that have no body to detect it from, use the
+    /// convention set by `calling_convention`.
+    pub fn detect_cc(mut self) -> ModuleLoader<'a> {
+        self.detect_cc = true;
+        self
+    }
+
+    /// Calling convention to assume when `assume_cc` is set. Defaults to passing six
+    /// arguments in registers `A0`-`A5` and returning in `SN`.
+    pub fn calling_convention(mut self, cc: CallingConvention) -> ModuleLoader<'a> {
+        self.cc = cc;
+        self
+    }
+
     /// Create blank, stub entries for imported functions.
     /// Required for load-libs, auto set when load_libs is true for the project loader.
     pub fn stub_imports(mut self) -> ModuleLoader<'a> {
@@ -577,64 +1823,119 @@ impl<'a> ModuleLoader<'a> {
         self
     }
 
-    fn init_fn_bindings(rfn: &mut RadecoFunction, sub_reg_f: &SubRegisterFile) {
+    /// Signatures of imported functions to use, in addition to
+    /// `llanalyzer::known_import_signatures`. These take precedence over the known signatures
+    /// of functions with the same name.
+    pub fn import_signatures(mut self,
+                             sigs: HashMap<String, ImportSignature>)
+                             -> ModuleLoader<'a> {
+        self.import_signatures.extend(sigs);
+        self
+    }
+
+    /// Names of functions that never return to their caller, in addition to
+    /// `llanalyzer::NORETURN_IMPORTS` and the signatures marked as `noreturn` in
+    /// `import_signatures`. See `RadecoModule::is_noreturn_import`.
+    pub fn noreturn_functions(mut self, names: &[&str]) -> ModuleLoader<'a> {
+        self.noreturn_functions.extend(names.iter().map(|&name| name.to_owned()));
+        self
+    }
+
+    /// Only load module level information, such as sections, symbols and imports. Functions
+    /// are not identified, so `functions` is left empty and no disassembly or SSA construction
+    /// is done.
+    pub fn metadata_only(mut self) -> ModuleLoader<'a> {
+        self.metadata_only = true;
+        self
+    }
+
+    /// Demangle the names of the identified functions. Both Rust and C++ (Itanium ABI) names
+    /// are understood, other names are left unchanged. The original name is kept and is
+    /// available through `RadecoFunction::mangled_name`.
+    pub fn demangle(mut self) -> ModuleLoader<'a> {
+        self.demangle = true;
+        self
+    }
+
+    fn init_fn_bindings(rfn: &mut RadecoFunction,
+                        sub_reg_f: &SubRegisterFile,
+                        cc: &CallingConvention) {
         // Setup binding information for functions based on reg_p. Note that this essential
         // marks the "potential" arguments without worrying about if they're ever used. Future
         // analysis can refine this information to make argument recognition more precise.
 
         // Get register state at entry block (for arguments) and at exit block (for returns).
+        // Functions that never return have no reachable exit block, and hence no return value.
         let (entry_state, exit_state) = {
             let ssa = rfn.ssa();
-            let entry = ssa.entry_node().expect("No entry node found for function!");
-            let exit = ssa.exit_node().expect("No exit node found for function!");
-
-            let entry_state = ssa.registers_in(entry).expect("No registers found in entry");
-            let exit_state = ssa.registers_in(exit).expect("No registers found in entry");
-            (ssa.operands_of(entry_state), ssa.operands_of(exit_state))
+            // Without an entry there is nothing to bind to, e.g. if SSA construction failed.
+            let entry_state = match ssa.entry_node().and_then(|entry| ssa.registers_in(entry)) {
+                Some(entry_state) => entry_state,
+                None => return,
+            };
+            let exit_state = ssa.exit_node()
+                .and_then(|exit| if ssa.preds_of(exit).is_empty() {
+                    None
+                } else {
+                    ssa.registers_in(exit)
+                });
+            (ssa.operands_of(entry_state), exit_state.map(|rs| ssa.operands_of(rs)))
         };
 
+        let rets = cc.rets();
         let mut tbindings: Vec<VarBinding> = sub_reg_f.alias_info
             .iter()
             .enumerate()
             .filter_map(|(i, reg)| {
                 let alias = reg.0;
-                if let &Some(idx) = &["A0", "A1", "A2", "A3", "A4", "A5", "SN"]
-                    .iter()
-                    .position(|f| f == alias) {
-                    let mut vb = VarBinding::default();
-                    if idx < 6 {
-                        vb.btype = BindingType::RegisterArgument(idx);
-                        vb.idx = *entry_state.iter()
-                            .find(|&&ridx| {
-                                if let Ok(NodeType::Comment(ref s)) =
-                                    rfn.ssa().node_data(ridx).map(|n| n.nt) {
-                                    if s == reg.1 { true } else { false }
-                                } else {
-                                    false
-                                }
-                            })
-                            .unwrap_or(&NodeIndex::end());
-                    } else {
-                        vb.btype = BindingType::Return;
-                        vb.idx = *exit_state.iter()
-                            .find(|&&ridx| {
-                                if let Ok(NodeType::Comment(ref s)) =
-                                    rfn.ssa().node_data(ridx).map(|n| n.nt) {
-                                    if s == reg.1 { true } else { false }
-                                } else {
-                                    false
-                                }
-                            })
-                            .unwrap_or(&NodeIndex::end());
+                let (btype, state) = if let Some(idx) = cc.args.iter().position(|f| f == alias) {
+                    (BindingType::RegisterArgument(idx), &entry_state)
+                } else if rets.contains(&alias.as_str()) {
+                    match exit_state {
+                        Some(ref exit_state) => (BindingType::Return, exit_state),
+                        None => return None,
                     }
-                    vb.ridx = sub_reg_f.register_id_by_alias(alias);
-                    Some(vb)
                 } else {
-                    None
-                }
+                    return None;
+                };
+
+                let mut vb = VarBinding::default();
+                vb.btype = btype;
+                vb.idx = *state.iter()
+                    .find(|&&ridx| {
+                        if let Ok(NodeType::Comment(ref s)) =
+                            rfn.ssa().node_data(ridx).map(|n| n.nt) {
+                            if s == reg.1 { true } else { false }
+                        } else {
+                            false
+                        }
+                    })
+                    .unwrap_or(&NodeIndex::end());
+                vb.ridx = sub_reg_f.register_id_by_alias(alias);
+                Some(vb)
             })
             .collect();
 
+        // Arguments on the stack are recognized from the loads of the function.
+        if cc.stack_args {
+            let stack_args = rfn.stack_arguments();
+            tbindings.extend(stack_args.into_iter().map(|(i, load)| {
+                VarBinding::new(BindingType::StackArgument(i), None, load, None)
+            }));
+        }
+
+        // The variable arguments are not tied to any register, they follow the fixed ones.
+        if rfn.variadic {
+            tbindings.push(VarBinding::new(BindingType::Variadic(cc.args.len()),
+                                           Some("...".to_owned()),
+                                           NodeIndex::end(),
+                                           None));
+        }
+
+        let ret_ids = rets.iter()
+            .map(|alias| sub_reg_f.register_id_by_alias(alias))
+            .collect::<Vec<_>>();
+        let ret_position = |vb: &VarBinding| ret_ids.iter().position(|&id| id == vb.ridx);
         tbindings.sort_by(|x, y| {
             match (x.btype, y.btype) {
                 (BindingType::RegisterArgument(i), BindingType::RegisterArgument(ref j)) => {
@@ -642,15 +1943,165 @@ impl<'a> ModuleLoader<'a> {
                 }
                 (BindingType::RegisterArgument(_), _) => Ordering::Less,
                 (_, BindingType::RegisterArgument(_)) => Ordering::Greater, 
+                (BindingType::StackArgument(i), BindingType::StackArgument(ref j)) => i.cmp(j),
+                (BindingType::StackArgument(_), _) => Ordering::Less,
+                (_, BindingType::StackArgument(_)) => Ordering::Greater,
+                // Parts of wide return values, from the least significant one
+                (BindingType::Return, BindingType::Return) => {
+                    ret_position(x).cmp(&ret_position(y))
+                }
+                (BindingType::Variadic(_), _) => Ordering::Less,
+                (_, BindingType::Variadic(_)) => Ordering::Greater,
                 (_, _) => Ordering::Equal,
             }
         });
 
+        // Keep the bindings for locals that might have been loaded from the `Source`.
+        tbindings.extend(rfn.bindings.0.drain(..).filter(|vb| vb.btype.is_local()));
         rfn.bindings = VarBindings(tbindings);
     }
 
+    /// Mark functions that consist of a single unconditional jump to an import as thunks.
+    fn mark_thunks(rmod: &mut RadecoModule) {
+        let imports = &rmod.imports;
+        for rfn in rmod.functions.values_mut() {
+            rfn.is_thunk = match rfn.instructions.as_slice() {
+                &[ref op] => {
+                    op.optype.as_ref().map_or(false, |t| t == "jmp") &&
+                    op.jump.map_or(false, |target| imports.contains_key(&target))
+                }
+                _ => false,
+            };
+        }
+    }
+
+    /// Mark functions that start in ARM Thumb mode and strip the mode bit from their offsets.
+    /// A function is in Thumb mode if its own address, or a symbol or entrypoint address
+    /// pointing to it, has the lowest bit set.
+    fn apply_thumb_mode(rmod: &mut RadecoModule) {
+        let thumb_addrs = rmod.symbols
+            .iter()
+            .filter_map(|s| s.vaddr)
+            .chain(rmod.entrypoint.iter().filter_map(|e| e.vaddr))
+            .map(split_thumb_bit)
+            .filter(|&(_, thumb)| thumb)
+            .map(|(addr, _)| addr)
+            .collect::<HashSet<_>>();
+        let functions = mem::replace(&mut rmod.functions, BTreeMap::new());
+        for (off, mut rfn) in functions {
+            let (addr, thumb) = split_thumb_bit(off);
+            rfn.offset = addr;
+            rfn.is_thumb = thumb || thumb_addrs.contains(&addr);
+            rmod.functions.insert(addr, rfn);
+        }
+    }
+
+    fn init_fn_locals(rfn: &mut RadecoFunction, locals: &[LVarInfo], sub_reg_f: &SubRegisterFile) {
+        // Setup bindings for the local variables reported by the `Source`. Stack variables are
+        // placed relative to the stack pointer at function entry: locals by their distance below
+        // it, arguments by their slot above the return address (as in `stack_arguments`).
+        // Offsets from the frame pointer assume the usual `push BP; mov BP, SP` prologue, offsets
+        // from the stack pointer are taken after the frame has been set up, so they can only be
+        // placed if the frame size is known. Register locals are matched to the node of the
+        // register at function entry, which is only possible if the SSA was constructed.
+        // Otherwise, the binding is left with an invalid node index.
+        let entry_regs = {
+            let ssa = rfn.ssa();
+            ssa.entry_node()
+                .and_then(|entry| ssa.registers_in(entry))
+                .map(|rs| ssa.operands_of(rs))
+                .unwrap_or_default()
+        };
+        let frame_size = rfn.stack_frame_size();
+        let sp = sub_reg_f.alias_info.get("SP");
+        let bp = sub_reg_f.alias_info.get("BP");
+
+        for local in locals {
+            let base = local.reference.as_ref().and_then(|r| r.base.clone());
+            let offset = local.reference.as_ref().and_then(|r| r.offset);
+            let mut vb = VarBinding::new(BindingType::Unknown,
+                                         local.name.clone(),
+                                         NodeIndex::end(),
+                                         None);
+            match (base, offset) {
+                (Some(ref reg), Some(offset)) => {
+                    let slot = sub_reg_f.get_subregister(reg).map_or(0, |sr| sr.width as i64 / 8);
+                    let entry_offset = if Some(reg) == bp {
+                        offset.checked_sub(slot)
+                    } else if Some(reg) == sp {
+                        frame_size.and_then(|size| offset.checked_sub(size as i64))
+                    } else {
+                        None
+                    };
+                    let btype = match entry_offset {
+                        // The return address takes the first slot.
+                        Some(off) if slot > 0 && off >= slot && off % slot == 0 => {
+                            Some(BindingType::StackArgument((off / slot - 1) as usize))
+                        }
+                        Some(off) if off < 0 => {
+                            off.checked_neg().map(|depth| BindingType::StackLocal(depth as usize))
+                        }
+                        _ => None,
+                    };
+                    match btype {
+                        Some(btype) => vb.btype = btype,
+                        None => {
+                            radeco_warn!("Cannot place local {:?} at {}{:+} on the stack",
+                                         local.name,
+                                         reg,
+                                         offset);
+                            continue;
+                        }
+                    }
+                }
+                (Some(ref reg), None) => {
+                    vb.btype = BindingType::RegisterLocal;
+                    vb.ridx = sub_reg_f.register_id_by_name(reg);
+                    vb.idx = *entry_regs.iter()
+                        .find(|&&ridx| rfn.ssa().comment(ridx).as_ref() == Some(reg))
+                        .unwrap_or(&NodeIndex::end());
+                }
+                (None, _) => {
+                    radeco_warn!("No reference found for local {:?}", local.name);
+                    continue;
+                }
+            }
+            rfn.bindings.0.push(vb);
+        }
+    }
+
+    // Mark functions that have a self-edge or are a part of a larger strongly connected
+    // component in the callgraph as recursive. Functions that are not in the callgraph are left
+    // as unknown.
+    fn mark_recursive(rmod: &mut RadecoModule) {
+        let mut recursive = HashSet::new();
+        for scc in kosaraju_scc(&rmod.callgraph) {
+            if scc.len() > 1 {
+                recursive.extend(scc);
+            } else if rmod.callgraph.find_edge(scc[0], scc[0]).is_some() {
+                recursive.insert(scc[0]);
+            }
+        }
+
+        for nidx in rmod.callgraph.node_indices() {
+            let addr = rmod.callgraph[nidx];
+            if let Some(rfn) = rmod.functions.get_mut(&addr) {
+                rfn.is_recursive = Some(recursive.contains(&nidx));
+            }
+        }
+    }
+
     /// Kick everything off and load module information based on config and defaults
-    pub fn load(&mut self, src: Rc<Source>) -> RadecoModule {
+    pub fn load(&mut self, src: Rc<Source>) -> Result<RadecoModule, LoadError> {
+        self.load_timed(src).map(|(rmod, _)| rmod)
+    }
+
+    /// Same as `load`, but also measures the time spent in each phase of loading.
+    pub fn load_timed(&mut self,
+                      src: Rc<Source>)
+                      -> Result<(RadecoModule, LoadTimings), LoadError> {
+        let mut timings = LoadTimings::default();
+        let mut start = Instant::now();
         let source = if self.source.is_some() {
             self.source.as_ref().unwrap()
         } else {
@@ -665,6 +2116,35 @@ impl<'a> ModuleLoader<'a> {
         floader.source = Some(Rc::clone(source));
 
         let mut rmod = RadecoModule::default();
+        rmod.path = self.path.clone().unwrap_or_default();
+        rmod.load_config = LoadConfig {
+            metadata_only: self.metadata_only,
+            build_ssa: self.build_ssa,
+            build_callgraph: self.build_callgraph,
+            assume_cc: self.assume_cc,
+            detect_cc: self.detect_cc,
+            load_datarefs: self.load_datarefs,
+            load_locals: self.load_locals,
+            stub_imports: self.stub_imports,
+            keep_bytes: self.keep_bytes,
+        };
+        rmod.noreturn = self.noreturn_functions
+            .iter()
+            .cloned()
+            .chain(self.import_signatures
+                .iter()
+                .filter(|&(_, sig)| sig.noreturn)
+                .map(|(name, _)| name.clone()))
+            .collect();
+        rmod.name = match self.name {
+            Some(ref name) => name.clone(),
+            None => {
+                Path::new(rmod.path.as_ref())
+                    .file_name()
+                    .map(|f| Cow::from(f.to_string_lossy().into_owned()))
+                    .unwrap_or_default()
+            }
+        };
 
         // Fill in module level information from the `Source`
         match source.symbols() {
@@ -682,11 +2162,19 @@ impl<'a> ModuleLoader<'a> {
             Ok(import_info) => {
                 rmod.imports = import_info.iter().filter_map(|ii| {
                     if let Some(plt) = ii.plt {
-                        Some((plt, ImportInfo::new_stub(plt, Cow::from(ii.name.as_ref().unwrap().clone()))))
+                        let name = Cow::from(ii.name.as_ref().unwrap().clone());
+                        let ifn = ImportInfo::new_stub(plt, name);
+                        // The stub is named like the PLT entry in radare2.
+                        ifn.rfn.borrow_mut().name = Cow::from(format!("sym.imp.{}", ifn.name));
+                        Some((plt, ifn))
                     } else {
                         None
                     }
                 }).collect();
+                rmod.import_names = rmod.imports
+                    .iter()
+                    .map(|(&plt, ii)| (ii.name.to_string(), plt))
+                    .collect();
             },
             Err(e) => radeco_warn!(e),
         }
@@ -711,45 +2199,163 @@ impl<'a> ModuleLoader<'a> {
             Err(e) => radeco_warn!(e),
         }
 
+        timings.metadata = start.elapsed();
+        if self.metadata_only {
+            rmod.source = Some(Rc::clone(source));
+            return Ok((rmod, timings));
+        }
+
+        start = Instant::now();
         let mut flresult = floader.load(&rmod);
-        flresult.functions = if self.filter.is_some() {
-            let filter_fn = self.filter.as_ref().unwrap();
-            flresult.functions.into_iter().filter(|&(ref x, ref v)| filter_fn(v)).collect()
-        } else {
-            flresult.functions
-        };
+        let filter_fn = self.filter;
+        let filter_range = self.filter_range;
+        let min_fn_size = self.min_fn_size;
+        flresult.functions = flresult.functions
+            .into_iter()
+            .filter(|&(off, ref rfn)| {
+                filter_range.map_or(true, |(lo, hi)| off >= lo && off < hi) &&
+                min_fn_size.map_or(true, |min| rfn.size >= min as u64) &&
+                filter_fn.map_or(true, |f| f(rfn))
+            })
+            .collect();
 
         rmod.functions = flresult.functions;
+        if self.demangle {
+            for rfn in rmod.functions.values_mut() {
+                if let Some(demangled) = demangle_name(&rfn.name) {
+                    let mangled = mem::replace(&mut rfn.name, Cow::from(demangled));
+                    rfn.mangled_name = Some(mangled);
+                }
+            }
+        }
+
+        let reg_p = source.register_profile().map_err(LoadError::RegisterProfile)?;
+        let sub_reg_f = SubRegisterFile::new(&reg_p);
+        // The "tf" (Thumb state) flag is only present in ARM register profiles.
+        if sub_reg_f.named_registers.contains_key("tf") {
+            ModuleLoader::apply_thumb_mode(&mut rmod);
+        }
+        let name_scheme: fn(u64) -> String =
+            self.name_scheme.unwrap_or(loader_defaults::name_by_offset);
+        for rfn in rmod.functions.values_mut().filter(|rfn| rfn.name.is_empty()) {
+            rfn.name = Cow::from(name_scheme(rfn.offset));
+        }
+
+        timings.functions = start.elapsed();
 
         // Load instructions into functions
+        start = Instant::now();
         for (_, rfn) in rmod.functions.iter_mut() {
-            rfn.instructions = source.disassemble_n_bytes(rfn.size, rfn.offset)
+            let n = match self.max_fn_bytes {
+                Some(max) if rfn.size > max as u64 => {
+                    radeco_warn!("Truncating function at {:#x} of size {:#x} to {:#x} bytes",
+                                 rfn.offset,
+                                 rfn.size,
+                                 max);
+                    rfn.truncated = true;
+                    max as u64
+                }
+                _ => rfn.size,
+            };
+            rfn.instructions = source.disassemble_n_bytes_mode(n, rfn.offset, rfn.is_thumb)
                 .unwrap_or(Vec::new());
+            if self.keep_bytes {
+                rfn.raw_bytes = source.read_bytes(rfn.offset, n as usize).unwrap_or_else(|e| {
+                    radeco_warn!("Unable to read bytes of function at {:#x}: {}", rfn.offset, e);
+                    Vec::new()
+                });
+            }
         }
+        ModuleLoader::mark_thunks(&mut rmod);
+        timings.disassembly = start.elapsed();
 
         // Optionally construct the SSA.
-        let reg_p = source.register_profile().expect("Unable to load register profile");
-        let sub_reg_f = SubRegisterFile::new(&reg_p);
+        start = Instant::now();
         if self.build_ssa {
-            if self.parallel {
-                let ascc = self.assume_cc;
-                rmod.functions.par_iter_mut().for_each(|(_, rfn)| {
-                    SSAConstruct::<SSAStorage>::construct(rfn, &reg_p, ascc);
-                });
-            } else {
-                for (off, rfn) in rmod.functions.iter_mut() {
-                    SSAConstruct::<SSAStorage>::construct(rfn, &reg_p, self.assume_cc);
+            let ascc = self.assume_cc;
+            let max_nodes = self.max_ssa_nodes;
+            let on_progress = self.on_progress;
+            let post_ssa = self.post_ssa;
+            let events = self.events.as_ref();
+            let sub_reg_f = &sub_reg_f;
+            let total = rmod.functions.values().filter(|rfn| !rfn.is_thunk).count();
+            let done = AtomicUsize::new(0);
+            let construct = |off: u64, rfn: &mut RadecoFunction| {
+                // Trampolines only jump elsewhere, there is nothing to analyze in them.
+                if rfn.is_thunk {
+                    return None;
                 }
-            }
-        }
-
-        if self.stub_imports {
-            for (off, ifn) in rmod.imports.iter_mut() {
-                SSAConstruct::<SSAStorage>::construct(&mut ifn.rfn.borrow_mut(), &reg_p, self.assume_cc);
-            }
+                let res =
+                    SSAConstruct::<SSAStorage>::construct_bounded(rfn, &reg_p, ascc, max_nodes);
+                if let (true, Some(f)) = (res.is_ok(), post_ssa) {
+                    f(rfn, sub_reg_f);
+                }
+                if let Some(f) = on_progress {
+                    f(done.fetch_add(1, atomic::Ordering::SeqCst) + 1, total);
+                }
+                if let Some(events) = events {
+                    send_event(events, LoadEvent::FunctionAnalyzed(off));
+                }
+                res.err().map(|e| (off, e))
+            };
+            let mut failed: Vec<(u64, ConstructErr)> = if self.parallel {
+                let functions = &mut rmod.functions;
+                let construct_all = || -> Vec<(u64, ConstructErr)> {
+                    functions.par_iter_mut()
+                        .filter_map(|(&off, rfn)| construct(off, rfn))
+                        .collect()
+                };
+                let pool = self.num_threads
+                    .map(|n| ThreadPool::new(Configuration::new().num_threads(n)));
+                match pool {
+                    Some(Ok(pool)) => pool.install(construct_all),
+                    Some(Err(e)) => {
+                        radeco_warn!("Unable to create thread pool: {}", e);
+                        construct_all()
+                    }
+                    None => construct_all(),
+                }
+            } else {
+                rmod.functions
+                    .iter_mut()
+                    .filter_map(|(&off, rfn)| construct(off, rfn))
+                    .collect()
+            };
+            failed.sort_by_key(|&(off, _)| off);
+            for &(off, e) in &failed {
+                radeco_warn!("SSA construction failed for function at {:#x}: {}", off, e);
+            }
+            rmod.failed_ssa = failed.into_iter().map(|(off, _)| off).collect();
+
+            // Nothing follows a call to a function that does not return.
+            let sigs = rmod.noreturn_signatures();
+            for (off, rfn) in rmod.functions.iter_mut() {
+                if !rfn.is_thunk && rmod.failed_ssa.binary_search(off).is_err() {
+                    llanalyzer::prune_after_noreturn_with(rfn, &sigs);
+                }
+            }
+        }
+
+        let mut signatures = llanalyzer::known_import_signatures();
+        signatures.extend(self.import_signatures.clone());
+        if self.stub_imports {
+            for (off, ifn) in rmod.imports.iter_mut() {
+                if let Err(e) = SSAConstruct::<SSAStorage>::construct(&mut ifn.rfn.borrow_mut(),
+                                                                      &reg_p,
+                                                                      self.assume_cc) {
+                    radeco_warn!("SSA construction failed for import at {:#x}: {}", off, e);
+                    rmod.failed_import_ssa.push(*off);
+                }
+                if let Some(sig) = signatures.get(ifn.name.as_ref()) {
+                    ifn.rfn.borrow_mut().variadic = sig.variadic;
+                }
+            }
+            rmod.failed_import_ssa.sort();
         }
+        timings.ssa = start.elapsed();
 
         // Load optional information. These need support from `Source` for analysis
+        start = Instant::now();
         if self.build_callgraph || self.load_datarefs || self.load_locals {
             let aux_info = match source.functions() {
                 Ok(info) => info,
@@ -773,37 +2379,83 @@ impl<'a> ModuleLoader<'a> {
                         }
                     }
                 }
+                ModuleLoader::mark_recursive(&mut rmod);
             }
 
             if self.load_datarefs {
                 for info in &aux_info {
-                    if let Some(mut rfn) = rmod.functions.get_mut(&info.offset.unwrap()) {
+                    let offset = match info.offset {
+                        Some(offset) => offset,
+                        None => continue,
+                    };
+                    if let Some(mut rfn) = rmod.functions.get_mut(&offset) {
                         rfn.datarefs = info.datarefs.clone().unwrap_or_default();
                     }
                 }
             }
 
             if self.load_locals {
-                unimplemented!()
+                for info in &aux_info {
+                    let locals = match info.locals {
+                        Some(ref locals) if !locals.is_empty() => locals,
+                        _ => continue,
+                    };
+                    let offset = match info.offset {
+                        Some(offset) => offset,
+                        None => continue,
+                    };
+                    if let Some(rfn) = rmod.functions.get_mut(&offset) {
+                        ModuleLoader::init_fn_locals(rfn, locals, &sub_reg_f);
+                    }
+                }
             }
         }
 
-        if self.build_callgraph && self.assume_cc {
+        if self.build_callgraph && (self.assume_cc || self.detect_cc) {
             for (off, rfn) in rmod.functions.iter_mut() {
-                ModuleLoader::init_fn_bindings(rfn, &sub_reg_f);
+                // There is nothing to bind to in functions without a proper SSA.
+                if rfn.is_thunk || rmod.failed_ssa.binary_search(off).is_ok() {
+                    continue;
+                }
+                if self.detect_cc {
+                    let cc = llanalyzer::detect_calling_convention(rfn, &sub_reg_f);
+                    ModuleLoader::init_fn_bindings(rfn, &sub_reg_f, &cc);
+                } else {
+                    ModuleLoader::init_fn_bindings(rfn, &sub_reg_f, &self.cc);
+                }
             }
-            // Do the same for imports.
+            // Do the same for imports, passing only as many arguments as their signature has.
             for (plt, ifn) in rmod.imports.iter_mut() {
-                ModuleLoader::init_fn_bindings(&mut ifn.rfn.borrow_mut(), &sub_reg_f);
+                if rmod.failed_import_ssa.binary_search(plt).is_ok() {
+                    continue;
+                }
+                let cc = match signatures.get(ifn.name.as_ref()) {
+                    Some(sig) => {
+                        let nargs = cmp::min(sig.nargs, self.cc.args.len());
+                        CallingConvention {
+                            args: self.cc.args[..nargs].to_vec(),
+                            ret: self.cc.ret.clone(),
+                            ret_hi: self.cc.ret_hi.clone(),
+                            stack_args: self.cc.stack_args,
+                        }
+                    }
+                    None => self.cc.clone(),
+                };
+                ModuleLoader::init_fn_bindings(&mut ifn.rfn.borrow_mut(), &sub_reg_f, &cc);
             }
 
             llanalyzer::init_call_ctx(&mut rmod);
+            // Detected conventions differ between functions.
+            if !self.detect_cc {
+                rmod.cc = Some(self.cc.clone());
+            }
         }
+        timings.callgraph = start.elapsed();
 
         // Set source
         rmod.source = Some(Rc::clone(&source));
 
-        rmod
+        Ok((rmod, timings))
     }
 
     /// Setup a function loader for the module
@@ -817,14 +2469,234 @@ impl<'a> ModuleLoader<'a> {
         self.filter = Some(f);
         self
     }
+
+    /// Only load functions that start within `[lo, hi)`. Applied along with `filter`, before
+    /// any instructions are loaded for the functions.
+    pub fn filter_range(mut self, lo: u64, hi: u64) -> ModuleLoader<'a> {
+        self.filter_range = Some((lo, hi));
+        self
+    }
+
+    /// Only load functions of at least `min` bytes, dropping small stubs. Applied along with
+    /// `filter`, before any instructions are loaded for the functions.
+    pub fn min_fn_size(mut self, min: usize) -> ModuleLoader<'a> {
+        self.min_fn_size = Some(min);
+        self
+    }
+
+    /// Disassemble at most `max` bytes of any function. Larger functions are truncated and
+    /// marked as such. Unlimited by default.
+    pub fn max_fn_bytes(mut self, max: usize) -> ModuleLoader<'a> {
+        self.max_fn_bytes = Some(max);
+        self
+    }
+
+    /// Keep the bytes of every function, see `RadecoFunction::raw_bytes`. Needs support for
+    /// `read_bytes` from `Source`.
+    pub fn keep_bytes(mut self) -> ModuleLoader<'a> {
+        self.keep_bytes = true;
+        self
+    }
+
+    /// Give up on constructing the SSA of a function once it has more than `max` nodes. The
+    /// function is then left without SSA and is reported in `RadecoModule::failed_ssa`.
+    /// Unlimited by default.
+    pub fn max_ssa_nodes(mut self, max: usize) -> ModuleLoader<'a> {
+        self.max_ssa_nodes = Some(max);
+        self
+    }
+
+    /// Report the progress of SSA construction. `f` is called with the number of functions
+    /// done so far and the total number of functions, every time the SSA of a function is
+    /// constructed. With `parallel`, calls are made from the worker threads.
+    pub fn on_progress(mut self, f: fn(usize, usize)) -> ModuleLoader<'a> {
+        self.on_progress = Some(f);
+        self
+    }
+
+    /// Run `f` on every function right after its SSA was constructed, e.g. for a custom
+    /// analysis pass. Not called for functions whose SSA construction failed. With
+    /// `parallel`, calls are made from the worker threads.
+    pub fn post_ssa(mut self, f: fn(&mut RadecoFunction, &SubRegisterFile)) -> ModuleLoader<'a> {
+        self.post_ssa = Some(f);
+        self
+    }
+
+    /// Name functions that were identified without a name with `f`, called with their
+    /// offset. Defaults to `loader_defaults::name_by_offset`.
+    pub fn name_scheme(mut self, f: fn(u64) -> String) -> ModuleLoader<'a> {
+        self.name_scheme = Some(f);
+        self
+    }
+
+    // Options of the loader that can be sent to other threads, see `LoaderOptions`
+    fn options(&self) -> LoaderOptions {
+        LoaderOptions {
+            name: self.name.clone(),
+            path: self.path.clone(),
+            filter: self.filter,
+            filter_range: self.filter_range,
+            min_fn_size: self.min_fn_size,
+            max_fn_bytes: self.max_fn_bytes,
+            max_ssa_nodes: self.max_ssa_nodes,
+            on_progress: self.on_progress,
+            post_ssa: self.post_ssa,
+            name_scheme: self.name_scheme,
+            events: self.events.clone(),
+            build_callgraph: self.build_callgraph,
+            build_ssa: self.build_ssa,
+            load_datarefs: self.load_datarefs,
+            load_locals: self.load_locals,
+            parallel: self.parallel,
+            num_threads: self.num_threads,
+            assume_cc: self.assume_cc,
+            detect_cc: self.detect_cc,
+            cc: self.cc.clone(),
+            stub_imports: self.stub_imports,
+            import_signatures: self.import_signatures.clone(),
+            noreturn_functions: self.noreturn_functions.clone(),
+            metadata_only: self.metadata_only,
+            demangle: self.demangle,
+            keep_bytes: self.keep_bytes,
+            merge_policy: self.floader.as_ref().map(|floader| floader.merge_policy),
+        }
+    }
 }
 
-#[derive(Default)]
+// Options of a `ModuleLoader` that can be sent to other threads, see `ModuleLoader::options`.
+// The `Source` and the function identification strategies hold `Rc`s and borrows, and are left
+// out. Only the `MergePolicy` of the `FunctionLoader` is kept.
+struct LoaderOptions {
+    name: Option<Cow<'static, str>>,
+    path: Option<Cow<'static, str>>,
+    filter: Option<fn(&RadecoFunction) -> bool>,
+    filter_range: Option<(u64, u64)>,
+    min_fn_size: Option<usize>,
+    max_fn_bytes: Option<usize>,
+    max_ssa_nodes: Option<usize>,
+    on_progress: Option<fn(usize, usize)>,
+    post_ssa: Option<fn(&mut RadecoFunction, &SubRegisterFile)>,
+    name_scheme: Option<fn(u64) -> String>,
+    events: Option<EventSender>,
+    build_callgraph: bool,
+    build_ssa: bool,
+    load_datarefs: bool,
+    load_locals: bool,
+    parallel: bool,
+    num_threads: Option<usize>,
+    assume_cc: bool,
+    detect_cc: bool,
+    cc: CallingConvention,
+    stub_imports: bool,
+    import_signatures: HashMap<String, ImportSignature>,
+    noreturn_functions: Vec<String>,
+    metadata_only: bool,
+    demangle: bool,
+    keep_bytes: bool,
+    merge_policy: Option<MergePolicy>,
+}
+
+impl LoaderOptions {
+    // `ModuleLoader` set up with these options, using the default function identification
+    // strategies
+    fn loader(&self) -> ModuleLoader<'static> {
+        ModuleLoader {
+            name: self.name.clone(),
+            path: self.path.clone(),
+            filter: self.filter,
+            filter_range: self.filter_range,
+            min_fn_size: self.min_fn_size,
+            max_fn_bytes: self.max_fn_bytes,
+            max_ssa_nodes: self.max_ssa_nodes,
+            on_progress: self.on_progress,
+            post_ssa: self.post_ssa,
+            name_scheme: self.name_scheme,
+            events: self.events.clone(),
+            build_callgraph: self.build_callgraph,
+            build_ssa: self.build_ssa,
+            load_datarefs: self.load_datarefs,
+            load_locals: self.load_locals,
+            parallel: self.parallel,
+            num_threads: self.num_threads,
+            assume_cc: self.assume_cc,
+            detect_cc: self.detect_cc,
+            cc: self.cc.clone(),
+            stub_imports: self.stub_imports,
+            import_signatures: self.import_signatures.clone(),
+            noreturn_functions: self.noreturn_functions.clone(),
+            metadata_only: self.metadata_only,
+            demangle: self.demangle,
+            keep_bytes: self.keep_bytes,
+            floader: self.merge_policy
+                .map(|policy| FunctionLoader::default().include_defaults().merge_policy(policy)),
+            ..ModuleLoader::default()
+        }
+    }
+}
+
+#[derive(Default, Clone)]
 /// Breaks down `RadecoModule` into functions
 /// Performs low-level function identification.
+///
+/// Strategies run in order, from the front of the pipeline to its back. Every strategy sees
+/// the functions identified by the strategies before it, both in its predicate and when it is
+/// run, and the `MergePolicy` decides between functions identified at the same offset.
 pub struct FunctionLoader<'a> {
     source: Option<Rc<Source>>,
-    strategies: Vec<&'a PredicatedLoader>,
+    strategies: Vec<Strategy<'a>>,
+    merge_policy: MergePolicy,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Decides which function is kept when multiple strategies identify a function at the
+/// same offset.
+pub enum MergePolicy {
+    /// Keep the function identified by the earlier strategy
+    FirstWins,
+    /// Keep the function identified by the later strategy
+    LastWins,
+    /// Keep the function that has a name, breaking ties by picking the larger function.
+    /// If both are the same, the earlier one is kept.
+    PreferNamed,
+}
+
+impl Default for MergePolicy {
+    fn default() -> MergePolicy {
+        MergePolicy::LastWins
+    }
+}
+
+impl MergePolicy {
+    // Should `new` replace `old`, which is at the same offset?
+    fn replaces(&self, old: &RadecoFunction, new: &RadecoFunction) -> bool {
+        match *self {
+            MergePolicy::FirstWins => false,
+            MergePolicy::LastWins => true,
+            MergePolicy::PreferNamed => {
+                match (old.name.is_empty(), new.name.is_empty()) {
+                    (true, false) => true,
+                    (false, true) => false,
+                    _ => new.size > old.size,
+                }
+            }
+        }
+    }
+}
+
+// Function identification strategy, either borrowed from the user or owned by the loader.
+#[derive(Clone)]
+enum Strategy<'a> {
+    Borrowed(&'a PredicatedLoader),
+    Owned(Arc<PredicatedLoader>),
+}
+
+impl<'a> Strategy<'a> {
+    fn get(&self) -> &PredicatedLoader {
+        match *self {
+            Strategy::Borrowed(strat) => strat,
+            Strategy::Owned(ref strat) => &**strat,
+        }
+    }
 }
 
 pub trait PredicatedLoader {
@@ -863,34 +2735,97 @@ pub struct FLResult {
     new: u32,
 }
 
+impl FLResult {
+    /// Number of unique functions identified
+    pub fn count(&self) -> u32 {
+        self.new
+    }
+}
+
 impl<'a> FunctionLoader<'a> {
-    /// Add a function identification strategy to the pipeline
+    /// Add a function identification strategy to the back of the pipeline
     pub fn strategy<'b: 'a>(mut self, strat: &'b PredicatedLoader) -> FunctionLoader<'a> {
-        self.strategies.push(strat);
+        self.strategies.push(Strategy::Borrowed(strat));
+        self
+    }
+
+    /// Add a function identification strategy to the front of the pipeline, so that it runs
+    /// before all strategies added so far.
+    pub fn strategy_front<'b: 'a>(mut self, strat: &'b PredicatedLoader) -> FunctionLoader<'a> {
+        self.strategies.insert(0, Strategy::Borrowed(strat));
+        self
+    }
+
+    /// Remove all strategies from the pipeline, including the default ones.
+    pub fn clear_strategies(mut self) -> FunctionLoader<'a> {
+        self.strategies.clear();
+        self
+    }
+
+    /// Add an owned function identification strategy to the pipeline. Useful for closures
+    /// that capture their environment.
+    pub fn strategy_boxed(mut self, strat: Box<PredicatedLoader>) -> FunctionLoader<'a> {
+        self.strategies.push(Strategy::Owned(Arc::from(strat)));
+        self
+    }
+
+    /// Set the policy used to merge functions identified at the same offset by different
+    /// strategies. Defaults to `MergePolicy::LastWins`.
+    pub fn merge_policy(mut self, policy: MergePolicy) -> FunctionLoader<'a> {
+        self.merge_policy = policy;
         self
     }
 
     /// Kick everything off and breakdown a radeco module into functions
     pub fn load(&mut self, rmod: &RadecoModule) -> FLResult {
-        self.strategies.iter().fold(FLResult::default(), |mut acc, f| {
+        let policy = self.merge_policy;
+        self.strategies.iter().map(|s| s.get()).fold(FLResult::default(), |mut acc, f| {
             if f.predicate(&acc) {
                 let fl = f.strategy(self.source.as_ref(), &acc, rmod);
-                acc.new += fl.new;
-                acc.functions.extend(fl.functions.into_iter());
+                for (off, rfn) in fl.functions {
+                    match acc.functions.entry(off) {
+                        btree_map::Entry::Vacant(e) => {
+                            e.insert(rfn);
+                            acc.new += 1;
+                        }
+                        btree_map::Entry::Occupied(mut e) => {
+                            if policy.replaces(e.get(), &rfn) {
+                                e.insert(rfn);
+                            }
+                        }
+                    }
+                }
             }
             acc
         })
     }
 
-    /// Include default strategies to identify functions in the loaded binary
+    /// Include default strategies to identify functions in the loaded binary. These are added
+    /// to the back of the pipeline, use `strategy_front` for strategies that need to run
+    /// before them.
     pub fn include_defaults(mut self) -> FunctionLoader<'a> {
-        // TODO: Append these to the front
-        self.strategies.push(&loader_defaults::strat_use_symbols);
-        self.strategies.push(&loader_defaults::strat_use_source);
+        self.strategies.push(Strategy::Borrowed(&loader_defaults::strat_use_symbols));
+        self.strategies.push(Strategy::Borrowed(&loader_defaults::strat_use_entrypoint));
+        self.strategies.push(Strategy::Borrowed(&loader_defaults::strat_use_source));
         self
     }
 }
 
+/// Pass over the modules of a `RadecoProject`, their functions and the bindings of these, see
+/// `RadecoProject::accept`. Every method does nothing by default.
+pub trait ProjectVisitor {
+    fn visit_module(&mut self, _midx: usize, _rmod: &RadecoModule) {}
+    fn visit_function(&mut self, _midx: usize, _rfn: &RadecoFunction) {}
+    fn visit_binding(&mut self, _midx: usize, _rfn: &RadecoFunction, _vb: &VarBinding) {}
+}
+
+/// Like `ProjectVisitor`, with mutable access, see `RadecoProject::accept_mut`
+pub trait ProjectVisitorMut {
+    fn visit_module(&mut self, _midx: usize, _rmod: &mut RadecoModule) {}
+    fn visit_function(&mut self, _midx: usize, _rfn: &mut RadecoFunction) {}
+    fn visit_binding(&mut self, _midx: usize, _vb: &mut VarBinding) {}
+}
+
 impl RadecoProject {
     pub fn new() -> RadecoProject {
         RadecoProject {
@@ -899,10 +2834,136 @@ impl RadecoProject {
         }
     }
 
+    /// Save the project to `path`, so that it can be reloaded later using `load_from`
+    /// without having to redo the analysis.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let f = File::create(path)?;
+        serde_json::to_writer(f, self).map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+    }
+
+    /// Load a project previously saved using `save`. Sources are not saved along with the
+    /// project and have to be re-attached to the modules.
+    pub fn load_from<P: AsRef<Path>>(path: P) -> io::Result<RadecoProject> {
+        let f = File::open(path)?;
+        serde_json::from_reader(f).map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+    }
+
+    /// Link the imports of every module to the matching export of another module in the
+    /// project. If more than one module exports a symbol, the one loaded first wins.
+    pub fn resolve_imports(&mut self) {
+        // Map from export name to the (module index, offset) of all its definitions
+        let mut exports: HashMap<String, Vec<(usize, u64)>> = HashMap::new();
+        for (midx, rmod) in self.modules.iter().enumerate() {
+            for export in &rmod.exports {
+                if let (&Some(ref name), Some(vaddr)) = (&export.name, export.vaddr) {
+                    exports.entry(name.clone()).or_insert_with(Vec::new).push((midx, vaddr));
+                }
+            }
+        }
+
+        for (midx, rmod) in self.modules.iter_mut().enumerate() {
+            for ifn in rmod.imports.values_mut() {
+                let defs = exports.get(ifn.name.as_ref())
+                    .map(|defs| defs.iter().filter(|d| d.0 != midx).cloned().collect::<Vec<_>>())
+                    .unwrap_or_default();
+                if defs.len() > 1 {
+                    radeco_warn!("{} is exported by multiple modules, using module {}",
+                                 ifn.name,
+                                 defs[0].0);
+                }
+                ifn.set_resolved(defs.first().cloned());
+            }
+        }
+    }
+
+    /// Imports of all modules, as (module index, PLT address, name, resolved definition), in
+    /// the order of `RadecoModule::iter_imports`. The definition is given as (module index,
+    /// offset) and is `None` for imports that `resolve_imports` did not resolve.
+    pub fn iter_resolved_imports<'a>
+        (&'a self)
+         -> Box<Iterator<Item = (usize, u64, &'a str, Option<(usize, u64)>)> + 'a> {
+        box self.modules.iter().enumerate().flat_map(|(midx, rmod)| {
+            rmod.iter_imports().map(move |(plt, name, resolved)| (midx, plt, name, resolved))
+        })
+    }
+
+    /// Where `addr` lies in the project: the module with a function, symbol or section
+    /// containing it, and the function or symbol it is in. Lookups use the sorted indices of
+    /// every module. If more than one module maps `addr`, the first one is returned and the
+    /// others are listed in `SymbolRef::overlaps`.
+    pub fn resolve_address(&self, addr: u64) -> Option<SymbolRef> {
+        let mut found: Option<SymbolRef> = None;
+        for (midx, rmod) in self.modules.iter().enumerate() {
+            let function = rmod.function_containing(addr);
+            let symbol = rmod.symbol_at(addr);
+            let section = rmod.section_at(addr);
+            if function.is_none() && symbol.is_none() && section.is_none() {
+                continue;
+            }
+            if let Some(ref mut sref) = found {
+                sref.overlaps.push(midx);
+                continue;
+            }
+
+            let (name, start) = match (function, symbol) {
+                (Some((&off, rfn)), _) => (Some(rfn.name.as_ref()), off),
+                (None, Some(sym)) => (sym.name.as_ref().map(|n| n.as_str()), sym.vaddr.unwrap()),
+                (None, None) => (None, section.and_then(|s| s.vaddr).unwrap_or(addr)),
+            };
+            found = Some(SymbolRef {
+                module: midx,
+                function: function.map(|(&off, _)| off),
+                name: name,
+                offset: addr.wrapping_sub(start) as i64,
+                section: section.and_then(|s| s.name.as_ref()).map(|n| n.as_str()),
+                overlaps: Vec::new(),
+            });
+        }
+        found
+    }
+
     pub fn regfile(&self) -> &Arc<SubRegisterFile> {
         &self.reginfo
     }
 
+    /// Check that every module with an attached source reports the same registers and
+    /// aliases as the register file of the project. Modules whose register profile cannot
+    /// be fetched are skipped with a warning.
+    pub fn validate_arch(&self) -> Result<(), ArchMismatch> {
+        for (i, rmod) in self.modules.iter().enumerate() {
+            let reg_p = match rmod.source.as_ref().map(|s| s.register_profile()) {
+                Some(Ok(reg_p)) => reg_p,
+                Some(Err(e)) => {
+                    radeco_warn!("Unable to check register profile of {}: {}", rmod.name, e);
+                    continue;
+                }
+                None => continue,
+            };
+            let regfile = SubRegisterFile::new(&reg_p);
+            if regfile.whole_names != self.reginfo.whole_names ||
+               regfile.alias_info != self.reginfo.alias_info {
+                return Err(ArchMismatch {
+                    module: i,
+                    name: rmod.name.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Attach sources to the modules of a project loaded using `load_from`. `f` is called for
+    /// every module and returns the source to use for it, if any. Modules for which `f`
+    /// returns `None` keep their current source.
+    pub fn attach_sources<F>(&mut self, f: F)
+        where F: Fn(&RadecoModule) -> Option<Rc<Source>>
+    {
+        for rmod in &mut self.modules {
+            if let Some(source) = f(rmod) {
+                rmod.set_source(source);
+            }
+        }
+    }
+
     pub fn nth_module(&self, idx: usize) -> Option<&RadecoModule> {
         if self.modules.len() > idx {
             Some(&self.modules[idx])
@@ -929,6 +2990,67 @@ impl RadecoProject {
     pub fn iter_mut<'a>(&'a mut self) -> ModuleIterMut<'a> {
         ModuleIterMut { iter: self.modules.iter_mut() }
     }
+
+    /// Walk the project with `visitor`. Every module is visited before its functions, in
+    /// order of offset, and every function before its bindings.
+    pub fn accept<V: ProjectVisitor>(&self, visitor: &mut V) {
+        for (midx, rmod) in self.modules.iter().enumerate() {
+            visitor.visit_module(midx, rmod);
+            for rfn in rmod.functions.values() {
+                visitor.visit_function(midx, rfn);
+                for vb in &rfn.bindings.0 {
+                    visitor.visit_binding(midx, rfn, vb);
+                }
+            }
+        }
+    }
+
+    /// Walk the project with `visitor`, in the same order as `accept`
+    pub fn accept_mut<V: ProjectVisitorMut>(&mut self, visitor: &mut V) {
+        for (midx, rmod) in self.modules.iter_mut().enumerate() {
+            visitor.visit_module(midx, rmod);
+            for rfn in rmod.functions.values_mut() {
+                visitor.visit_function(midx, rfn);
+                for vb in &mut rfn.bindings.0 {
+                    visitor.visit_binding(midx, vb);
+                }
+            }
+        }
+    }
+
+    /// Iterate over the functions of all modules in the project
+    pub fn all_functions<'a>(&'a self) -> AllFunctions<'a> {
+        AllFunctions {
+            modules: self.modules.iter().enumerate(),
+            current: None,
+        }
+    }
+
+    pub fn all_functions_mut<'a>(&'a mut self) -> AllFunctionsMut<'a> {
+        AllFunctionsMut {
+            modules: self.modules.iter_mut().enumerate(),
+            current: None,
+        }
+    }
+
+    /// Groups of functions, given as (module index, offset), that have the same
+    /// `signature_hash_with` and hence likely the same code. Only groups of at least two
+    /// functions are returned. Functions without instructions are ignored.
+    pub fn find_duplicates(&self) -> Vec<Vec<(usize, u64)>> {
+        let mut groups: HashMap<u64, Vec<(usize, u64)>> = HashMap::new();
+        for (midx, off, rfn) in self.all_functions() {
+            if !rfn.instructions.is_empty() {
+                let hash = rfn.signature_hash_with(&self.modules[midx], &self.reginfo);
+                groups.entry(hash).or_insert_with(Vec::new).push((midx, off));
+            }
+        }
+        let mut dups = groups.into_iter()
+            .map(|(_, group)| group)
+            .filter(|group| group.len() > 1)
+            .collect::<Vec<_>>();
+        dups.sort();
+        dups
+    }
 }
 
 impl RadecoModule {
@@ -938,6 +3060,16 @@ impl RadecoModule {
         rmod
     }
 
+    /// Human-readable name of the module
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Path on disk from which the module was loaded
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
     pub fn function(&self, offset: u64) -> Option<&RadecoFunction> {
         self.functions.get(&offset)
     }
@@ -946,6 +3078,104 @@ impl RadecoModule {
         self.functions.get_mut(&offset)
     }
 
+    /// Function, local or imported, represented by the node `idx` of the callgraph
+    pub fn function_at_node(&self, idx: NodeIndex) -> Option<NodeFunction> {
+        let addr = *self.callgraph.node_weight(idx)?;
+        if let Some(rfn) = self.functions.get(&addr) {
+            Some(NodeFunction::Local(rfn))
+        } else {
+            self.imports.get(&addr).map(|ifn| NodeFunction::Import(ifn.rfn.borrow()))
+        }
+    }
+
+    /// Names of the imports that the function at `offset` can reach through the callgraph,
+    /// either by calling them directly or through calls to other functions of the module.
+    /// Empty if there is no function at `offset` in the callgraph.
+    pub fn external_calls_from(&self, offset: u64) -> HashSet<String> {
+        let mut names = HashSet::new();
+        let start = match self.functions.get(&offset) {
+            Some(rfn) if self.callgraph.node_weight(rfn.cgid()) == Some(&offset) => rfn.cgid(),
+            _ => return names,
+        };
+        let mut dfs = Dfs::new(&self.callgraph, start);
+        while let Some(nidx) = dfs.next(&self.callgraph) {
+            if let Some(ifn) = self.imports.get(&self.callgraph[nidx]) {
+                names.insert(ifn.name.to_string());
+            }
+        }
+        names
+    }
+
+    /// Does the function `name` never return to its caller? These are the functions in
+    /// `llanalyzer::NORETURN_IMPORTS` and the ones added with
+    /// `ModuleLoader::noreturn_functions`. Names as in the disassembly of calls, such as
+    /// `sym.imp.exit`, are accepted as well.
+    pub fn is_noreturn_import(&self, name: &str) -> bool {
+        let name = llanalyzer::import_name(name);
+        llanalyzer::NORETURN_IMPORTS.contains(&name) || self.noreturn.contains(name)
+    }
+
+    /// `RadecoFunction::is_noreturn` for `rfn`, a function of this module, with the calls
+    /// to all functions for which `is_noreturn_import` holds taken as not returning.
+    pub fn is_function_noreturn(&self, rfn: &RadecoFunction) -> bool {
+        rfn.calls_noreturn(|name| self.is_noreturn_import(name))
+    }
+
+    /// `llanalyzer::known_import_signatures`, with all functions for which
+    /// `is_noreturn_import` holds marked as `noreturn`. For use with
+    /// `llanalyzer::prune_after_noreturn_with`.
+    pub fn noreturn_signatures(&self) -> HashMap<String, ImportSignature> {
+        let mut sigs = llanalyzer::known_import_signatures();
+        for name in &self.noreturn {
+            let sig = sigs.entry(name.clone()).or_insert_with(|| ImportSignature::new(0, false));
+            sig.noreturn = true;
+        }
+        sigs
+    }
+
+    /// Add a call from the function at `from` to the one at `to`, made at the address `csite`,
+    /// to the callgraph. Nodes are added for addresses that are not yet in the callgraph. If
+    /// the same call is already present, its edge is returned and nothing is added.
+    pub fn add_call_edge(&mut self, from: u64, to: u64, csite: u64) -> EdgeIndex {
+        let caller = self.callgraph_node(from);
+        let callee = self.callgraph_node(to);
+        let existing = self.callgraph
+            .edges_directed(caller, Direction::Outgoing)
+            .find(|e| e.target() == callee && e.weight().csite == csite)
+            .map(|e| e.id());
+        if let Some(edge) = existing {
+            return edge;
+        }
+        let mut cctx = CallContextInfo::default();
+        cctx.csite = csite;
+        cctx.csite_node = self.functions
+            .get(&from)
+            .and_then(|rfn| rfn.callsites().into_iter().find(|&(addr, _)| addr == csite))
+            .map_or(NodeIndex::end(), |(_, call)| call);
+        self.callgraph.add_edge(caller, callee, cctx)
+    }
+
+    // Node of the function at `addr` in the callgraph, which is added if it does not exist
+    fn callgraph_node(&mut self, addr: u64) -> NodeIndex {
+        let known = self.functions
+            .get(&addr)
+            .map(|rfn| rfn.cgid())
+            .or_else(|| self.imports.get(&addr).map(|ifn| ifn.rfn.borrow().cgid()))
+            .into_iter()
+            .chain(self.callgraph.node_indices())
+            .find(|&idx| self.callgraph.node_weight(idx) == Some(&addr));
+        if let Some(idx) = known {
+            return idx;
+        }
+        let idx = self.callgraph.add_node(addr);
+        if let Some(rfn) = self.functions.get_mut(&addr) {
+            rfn.cgid = idx;
+        } else if let Some(ifn) = self.imports.get(&addr) {
+            ifn.rfn.borrow_mut().cgid = idx;
+        }
+        idx
+    }
+
     pub fn iter<'a>(&'a self) -> FunctionIter<'a> {
         FunctionIter {
             module: &self,
@@ -961,51 +3191,4346 @@ impl RadecoModule {
         &self.sections
     }
 
+    /// Imports of this module, as (PLT address, name, resolved definition), sorted by
+    /// address. The definition is only known after `RadecoProject::resolve_imports`.
+    pub fn iter_imports<'a>(&'a self)
+                            -> Box<Iterator<Item = (u64, &'a str, Option<(usize, u64)>)> + 'a> {
+        let mut imports = self.imports
+            .iter()
+            .map(|(&plt, ifn)| (plt, ifn.name.as_ref(), ifn.resolved()))
+            .collect::<Vec<_>>();
+        imports.sort_by_key(|&(plt, _, _)| plt);
+        box imports.into_iter()
+    }
+
+    /// Lookup an import by name, irrespective of where its PLT entry is.
+    pub fn import_by_name(&self, name: &str) -> Option<&ImportInfo> {
+        self.import_plt(name).and_then(move |plt| self.imports.get(&plt))
+    }
+
+    pub fn import_by_name_mut(&mut self, name: &str) -> Option<&mut ImportInfo> {
+        match self.import_plt(name) {
+            Some(plt) => self.imports.get_mut(&plt),
+            None => None,
+        }
+    }
+
+    // `imports` is public and may have been modified after load, so fallback to a linear
+    // scan if the index is stale.
+    fn import_plt(&self, name: &str) -> Option<u64> {
+        match self.import_names.get(name) {
+            Some(plt) if self.imports.get(plt).map_or(false, |ii| ii.name == name) => Some(*plt),
+            _ => self.imports.values().find(|ii| ii.name == name).map(|ii| ii.plt),
+        }
+    }
+
     pub fn callgraph(&self) -> &CallGraph {
         &self.callgraph
     }
-}
 
-impl RadecoFunction {
-    pub fn new() -> RadecoFunction {
-        RadecoFunction::default()
+    /// Is `addr` in a section that is mapped executable?
+    pub fn is_executable(&self, addr: u64) -> bool {
+        self.section_at(addr)
+            .and_then(|s| s.flags.as_ref())
+            .map_or(false, |f| f.contains('x'))
     }
 
-    pub fn instructions(&self) -> &[LOpInfo] {
-        self.instructions.as_slice()
+    /// Is `addr` in a section that is mapped writable?
+    pub fn is_writable(&self, addr: u64) -> bool {
+        self.section_at(addr)
+            .and_then(|s| s.flags.as_ref())
+            .map_or(false, |f| f.contains('w'))
     }
 
-    pub fn ssa(&self) -> &SSAStorage {
-        &self.ssa
+    // Section that `addr` is in. For overlapping sections, the one starting closest to `addr`
+    // is picked, or the last one if they start at the same address.
+    fn section_at(&self, addr: u64) -> Option<&LSectionInfo> {
+        if self.section_index.borrow().is_none() {
+            let mut index = self.sections
+                .iter()
+                .enumerate()
+                .filter_map(|(i, s)| match (s.vaddr, s.vsize.or(s.size)) {
+                    (Some(vaddr), Some(size)) if size > 0 => Some((vaddr, vaddr + size, i)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            // Stable, keeps sections starting at the same address in order.
+            index.sort_by_key(|&(vaddr, _, _)| vaddr);
+            *self.section_index.borrow_mut() = Some(index);
+        }
+
+        let index = self.section_index.borrow();
+        let index = index.as_ref().unwrap();
+        // Number of sections starting at or before `addr`
+        let n = match index.binary_search_by(|&(vaddr, _, _)| if vaddr <= addr {
+            Ordering::Less
+        } else {
+            Ordering::Greater
+        }) {
+            Ok(n) | Err(n) => n,
+        };
+        index[..n]
+            .iter()
+            .rev()
+            .find(|&&(_, end, _)| addr < end)
+            .map(|&(_, _, i)| &self.sections[i])
     }
 
-    pub fn ssa_mut(&mut self) -> &mut SSAStorage {
-        &mut self.ssa
+    /// Symbol whose `[vaddr, vaddr + size)` range contains `addr`. If more than one does, the
+    /// one starting closest to `addr` is returned. Symbols without a size only contain their
+    /// own address. For an alternate entry of a function, the symbol of the function is
+    /// preferred.
+    pub fn symbol_at(&self, addr: u64) -> Option<&LSymbolInfo> {
+        // Alternate entries are attributed to the symbol of their function.
+        if let Some(offset) = self.alt_entry_of(addr) {
+            if let Some(sym) = self.symbol_at(offset) {
+                return Some(sym);
+            }
+        }
+        if self.symbol_index.borrow().is_none() {
+            let mut index = self.symbols
+                .iter()
+                .enumerate()
+                .filter_map(|(i, s)| s.vaddr.map(|vaddr| (vaddr, i)))
+                .collect::<Vec<_>>();
+            // Stable, the last of the symbols starting at the same address is found first.
+            index.sort_by_key(|&(vaddr, _)| vaddr);
+            *self.symbol_index.borrow_mut() = Some(index);
+        }
+
+        let index = self.symbol_index.borrow();
+        let index = index.as_ref().unwrap();
+        // Number of symbols starting at or before `addr`
+        let n = match index.binary_search_by(|&(vaddr, _)| if vaddr <= addr {
+            Ordering::Less
+        } else {
+            Ordering::Greater
+        }) {
+            Ok(n) | Err(n) => n,
+        };
+        index[..n]
+            .iter()
+            .rev()
+            .find(|&&(vaddr, i)| {
+                addr == vaddr || addr - vaddr < self.symbols[i].size.unwrap_or(0)
+            })
+            .map(|&(_, i)| &self.symbols[i])
     }
 
-    /// Returns the id in the call graph for this function.
-    pub fn cgid(&self) -> NodeIndex {
-        self.cgid
+    /// Function whose `[offset, offset + size)` range contains `addr`, along with its offset.
+    /// Functions of unknown (zero) size only contain their own offset. Alternate entries, see
+    /// `add_alt_entry`, belong to their function even when they are outside of this range.
+    pub fn function_containing(&self, addr: u64) -> Option<(&u64, &RadecoFunction)> {
+        if let Some(offset) = self.alt_entry_of(addr) {
+            return self.functions.range((Bound::Included(offset), Bound::Included(offset))).next();
+        }
+        self.functions
+            .range((Bound::Unbounded, Bound::Included(addr)))
+            .next_back()
+            .and_then(|(off, rfn)| if addr == *off || addr - off < rfn.size {
+                Some((off, rfn))
+            } else {
+                None
+            })
     }
 
-    pub fn bindings(&self) -> &VarBindings {
-        &self.bindings
+    /// Record `addr` as an additional entry point of the function at `offset`, for functions
+    /// that can be entered at more than one address. Fails if there is no function at
+    /// `offset`, or if `addr` already is the entry of another function.
+    pub fn add_alt_entry(&mut self, offset: u64, addr: u64) -> Result<(), String> {
+        if addr != offset && self.functions.contains_key(&addr) {
+            return Err(format!("{:#x} is the entry of another function", addr));
+        }
+        match self.alt_entry_of(addr) {
+            Some(other) if other != offset => {
+                return Err(format!("{:#x} is an entry of the function at {:#x}", addr, other));
+            }
+            _ => {}
+        }
+        let rfn = self.functions
+            .get_mut(&offset)
+            .ok_or_else(|| format!("No function at {:#x}", offset))?;
+        if addr != offset {
+            if let Err(i) = rfn.alt_entries.binary_search(&addr) {
+                rfn.alt_entries.insert(i, addr);
+            }
+        }
+        *self.entry_index.borrow_mut() = None;
+        Ok(())
     }
-}
 
-#[derive(Clone, Debug, Default)]
-pub struct CallContextInfo {
-    /// NodeIndex mapping from a node in the caller's context to a node in callee's context
-    pub map: Vec<(NodeIndex, NodeIndex)>,
-    /// NodeIndex corresponding to callsite (`OpCall`) in the caller context
-    pub csite_node: NodeIndex,
-    /// Address of callsite
-    pub csite: u64,
-}
+    /// Offset of the function that has `addr` as an alternate entry, if any. The index used
+    /// for the lookup is built on first use, and is only updated by `add_function`,
+    /// `remove_function` and `add_alt_entry`.
+    pub fn alt_entry_of(&self, addr: u64) -> Option<u64> {
+        if self.entry_index.borrow().is_none() {
+            let index = self.functions
+                .iter()
+                .flat_map(|(&off, rfn)| rfn.alt_entries.iter().map(move |&entry| (entry, off)))
+                .collect::<HashMap<_, _>>();
+            *self.entry_index.borrow_mut() = Some(index);
+        }
+        self.entry_index.borrow().as_ref().and_then(|index| index.get(&addr).cloned())
+    }
 
-#[cfg(test)]
+    /// Offsets of the functions whose `datarefs` include `addr`, in ascending order. The
+    /// index used for the lookup is built on first use, and is only updated by
+    /// `add_function` and `remove_function`.
+    pub fn xrefs_to_data(&self, addr: u64) -> Vec<u64> {
+        if self.dataref_index.borrow().is_none() {
+            let mut index: HashMap<u64, Vec<u64>> = HashMap::new();
+            // Functions are visited in ascending order, so every entry stays sorted.
+            for (&off, rfn) in &self.functions {
+                for &dref in &rfn.datarefs {
+                    let xrefs = index.entry(dref).or_insert_with(Vec::new);
+                    if xrefs.last() != Some(&off) {
+                        xrefs.push(off);
+                    }
+                }
+            }
+            *self.dataref_index.borrow_mut() = Some(index);
+        }
+        self.dataref_index
+            .borrow()
+            .as_ref()
+            .and_then(|index| index.get(&addr).cloned())
+            .unwrap_or_default()
+    }
+
+    /// Compare the functions of this module with those of `other`, a different version of the
+    /// same binary. Functions are first paired up by name, then the remaining ones by their
+    /// `signature_hash`, with pointers into the sections of their module masked out, to find
+    /// functions that were only renamed. Functions without instructions are only paired up by
+    /// name.
+    pub fn diff(&self, other: &RadecoModule) -> ModuleDiff {
+        self.diff_hashed(other, None)
+    }
+
+    /// `diff` that hashes functions with `signature_hash_with`, only renaming the registers
+    /// of `regfile`
+    pub fn diff_with(&self, other: &RadecoModule, regfile: &SubRegisterFile) -> ModuleDiff {
+        self.diff_hashed(other, Some(regfile))
+    }
+
+    fn diff_hashed(&self, other: &RadecoModule, regfile: Option<&SubRegisterFile>) -> ModuleDiff {
+        let mut diff = ModuleDiff::default();
+        let by_name = other.functions
+            .iter()
+            .map(|(&off, rfn)| (rfn.name.as_ref(), off))
+            .collect::<HashMap<_, _>>();
+        let mut paired = HashSet::new();
+        let mut unpaired = Vec::new();
+        for (&off, rfn) in &self.functions {
+            match by_name.get(rfn.name.as_ref()) {
+                Some(&new_off) if !paired.contains(&new_off) => {
+                    paired.insert(new_off);
+                    let new_hash = other.functions[&new_off].hash_code(Some(other), regfile);
+                    if rfn.hash_code(Some(self), regfile) == new_hash {
+                        diff.matched.push((off, new_off));
+                    } else {
+                        diff.changed.push((off, new_off));
+                    }
+                }
+                _ => unpaired.push((off, rfn)),
+            }
+        }
+
+        let mut by_hash: HashMap<u64, Vec<u64>> = HashMap::new();
+        for (&off, rfn) in other.functions.iter().rev() {
+            if !paired.contains(&off) && !rfn.instructions.is_empty() {
+                let hash = rfn.hash_code(Some(other), regfile);
+                by_hash.entry(hash).or_insert_with(Vec::new).push(off);
+            }
+        }
+        for (off, rfn) in unpaired {
+            let new_off = if rfn.instructions.is_empty() {
+                None
+            } else {
+                by_hash.get_mut(&rfn.hash_code(Some(self), regfile)).and_then(|offs| offs.pop())
+            };
+            match new_off {
+                Some(new_off) => {
+                    paired.insert(new_off);
+                    diff.matched.push((off, new_off));
+                }
+                None => diff.removed.push((off, rfn.name.to_string())),
+            }
+        }
+
+        diff.added = other.functions
+            .iter()
+            .filter(|&(off, _)| !paired.contains(off))
+            .map(|(&off, rfn)| (off, rfn.name.to_string()))
+            .collect();
+        diff.matched.sort();
+        diff
+    }
+
+    /// Read `len` bytes at `addr` using the attached `Source`. Fails if `addr` is not mapped in
+    /// any section of the module.
+    pub fn read_bytes(&self, addr: u64, len: usize) -> Result<Vec<u8>, SourceErr> {
+        if self.section_at(addr).is_none() {
+            return Err(SourceErr::SrcErr("Address not mapped in any section"));
+        }
+        match self.source {
+            Some(ref source) => source.read_bytes(addr, len),
+            None => Err(SourceErr::SrcErr("No source attached to the module")),
+        }
+    }
+
+    /// Read the NUL-terminated UTF-8 string at `addr`. Returns `None` if `addr` is not in a
+    /// readable, non-executable section or no valid string is found there.
+    pub fn string_at(&self, addr: u64) -> Option<String> {
+        let section = self.section_at(addr)?;
+        if !section.flags.as_ref().map_or(false, |f| f.contains('r') && !f.contains('x')) {
+            return None;
+        }
+
+        // Do not read past the end of the section, as mapped in memory
+        let end = section.vaddr? + section.vsize.or(section.size)?;
+        let len = cmp::min(end - addr, MAX_STRING_LEN);
+        let bytes = self.read_bytes(addr, len as usize).ok()?;
+        let end = bytes.iter().position(|&b| b == 0)?;
+        String::from_utf8(bytes[..end].to_vec()).ok()
+    }
+
+    /// Add a function that was identified outside of the loading process. The function is
+    /// added to the callgraph, without any edges. Fails if there already is a function at the
+    /// same offset. Use `build_function_ssa` to construct its SSA.
+    pub fn add_function(&mut self, mut rfn: RadecoFunction) -> Result<(), String> {
+        if self.functions.contains_key(&rfn.offset) {
+            return Err(format!("Function at {:#x} already exists", rfn.offset));
+        }
+        rfn.cgid = self.callgraph.add_node(rfn.offset);
+        self.functions.insert(rfn.offset, rfn);
+        *self.dataref_index.borrow_mut() = None;
+        *self.entry_index.borrow_mut() = None;
+        Ok(())
+    }
+
+    /// Remove the function at `offset` along with its node and edges in the callgraph. Since
+    /// removing a node from the callgraph moves another one into its place, `cgid` of the
+    /// remaining functions and imports is updated to match.
+    pub fn remove_function(&mut self, offset: u64) -> Option<RadecoFunction> {
+        let rfn = self.functions.remove(&offset)?;
+        *self.dataref_index.borrow_mut() = None;
+        *self.entry_index.borrow_mut() = None;
+        let cgid = if self.callgraph.node_weight(rfn.cgid) == Some(&offset) {
+            Some(rfn.cgid)
+        } else {
+            self.callgraph.node_indices().find(|&n| self.callgraph[n] == offset)
+        };
+        if let Some(cgid) = cgid {
+            self.callgraph.remove_node(cgid);
+            for nidx in self.callgraph.node_indices() {
+                let addr = self.callgraph[nidx];
+                if let Some(rfn) = self.functions.get_mut(&addr) {
+                    rfn.cgid = nidx;
+                } else if let Some(ifn) = self.imports.get_mut(&addr) {
+                    ifn.rfn.borrow_mut().cgid = nidx;
+                }
+            }
+        }
+        if let Ok(i) = self.failed_ssa.binary_search(&offset) {
+            self.failed_ssa.remove(i);
+        }
+        Some(rfn)
+    }
+
+    /// Merge the functions at `a` and `b` into one, for functions that were split up by the
+    /// analysis. The function that comes second has to start within or right at the end of the
+    /// first one. Its instructions, datarefs, bindings and comments are added to the first,
+    /// its calls are moved over to it in the callgraph and it is removed from the module.
+    ///
+    /// If a source is attached, the SSA and bindings of the merged function are rebuilt as with
+    /// `reanalyze_function`. Otherwise both are cleared, as they would refer to the SSA of
+    /// the separate functions. Returns the offset of the merged function.
+    pub fn merge_functions(&mut self, a: u64, b: u64) -> Result<u64, String> {
+        let (first, second) = if a <= b { (a, b) } else { (b, a) };
+        if first == second {
+            return Err(format!("Cannot merge the function at {:#x} with itself", first));
+        }
+        let end = match (self.functions.get(&first), self.functions.get(&second)) {
+            (Some(rfn), Some(_)) => rfn.offset + rfn.size,
+            (None, _) => return Err(format!("No known function at {:#x}", first)),
+            (_, None) => return Err(format!("No known function at {:#x}", second)),
+        };
+        if second > end {
+            return Err(format!("Functions at {:#x} and {:#x} are not adjacent", first, second));
+        }
+
+        // Calls from and to the second function, by address, as the removal renumbers nodes.
+        let moved = {
+            let cg = &self.callgraph;
+            let cgid = self.functions[&second].cgid;
+            cg.edges_directed(cgid, Direction::Outgoing)
+                .map(|er| (second, cg[er.target()], er.weight().clone()))
+                .chain(cg.edges_directed(cgid, Direction::Incoming)
+                    .filter(|er| er.source() != cgid)
+                    .map(|er| (cg[er.source()], second, er.weight().clone())))
+                .filter(|&(from, to, _)| from != first && to != first)
+                .collect::<Vec<_>>()
+        };
+        let other = self.remove_function(second).unwrap();
+        for (from, to, cctx) in moved {
+            let (from, to) = {
+                let cg = &self.callgraph;
+                let node = |addr| {
+                    let addr = if addr == second { first } else { addr };
+                    cg.node_indices().find(|&n| cg[n] == addr)
+                };
+                (node(from), node(to))
+            };
+            if let (Some(from), Some(to)) = (from, to) {
+                self.callgraph.add_edge(from, to, cctx);
+            }
+        }
+
+        {
+            let rfn = self.functions.get_mut(&first).unwrap();
+            let other_end = other.offset + other.size;
+            if other_end > end {
+                rfn.size = other_end - rfn.offset;
+            }
+            rfn.instructions.extend(other.instructions
+                .into_iter()
+                .filter(|op| op.offset.map_or(true, |o| o >= end)));
+            let skip = (end - other.offset) as usize;
+            if !rfn.raw_bytes.is_empty() && skip < other.raw_bytes.len() {
+                rfn.raw_bytes.extend_from_slice(&other.raw_bytes[skip..]);
+            }
+            rfn.datarefs.extend(other.datarefs);
+            rfn.datarefs.sort();
+            rfn.datarefs.dedup();
+            rfn.bindings.0.extend(other.bindings.0);
+            for (addr, comment) in other.comments {
+                rfn.comments.entry(addr).or_insert(comment);
+            }
+            rfn.alt_entries.extend(other.alt_entries);
+            rfn.alt_entries.sort();
+            rfn.alt_entries.dedup();
+            rfn.truncated |= other.truncated;
+            rfn.is_thunk = false;
+        }
+        *self.dataref_index.borrow_mut() = None;
+        *self.entry_index.borrow_mut() = None;
+
+        self.rebuild_or_clear(first);
+        Ok(first)
+    }
+
+    /// Split the function at `offset` in two at `at`, for functions that were merged by the
+    /// analysis. A new function is created for the part from `at` to the end, which has to
+    /// start on an instruction boundary. Instructions, comments and calls in the callgraph are
+    /// moved to the new function based on their address, and datarefs based on which of the
+    /// two parts mention them in their instructions.
+    ///
+    /// The SSA and bindings of both functions are rebuilt or cleared as with
+    /// `merge_functions`. Returns the offset of the new function.
+    pub fn split_function(&mut self, offset: u64, at: u64) -> Result<u64, String> {
+        if self.functions.contains_key(&at) {
+            return Err(format!("Function at {:#x} already exists", at));
+        }
+        let tail = {
+            let rfn = self.functions
+                .get_mut(&offset)
+                .ok_or_else(|| format!("No known function at {:#x}", offset))?;
+            if at <= rfn.offset || at - rfn.offset >= rfn.size {
+                return Err(format!("{:#x} is not inside the function at {:#x}", at, offset));
+            }
+            let i = rfn.instructions
+                .iter()
+                .position(|op| op.offset == Some(at))
+                .ok_or_else(|| format!("{:#x} is not at an instruction boundary", at))?;
+
+            let mut tail = RadecoFunction::default();
+            tail.name = Cow::from(loader_defaults::name_by_offset(at));
+            tail.offset = at;
+            tail.size = rfn.offset + rfn.size - at;
+            rfn.size = at - rfn.offset;
+            tail.instructions = rfn.instructions.split_off(i);
+            if rfn.raw_bytes.len() > rfn.size as usize {
+                tail.raw_bytes = rfn.raw_bytes.split_off(rfn.size as usize);
+            }
+            // Datarefs mentioned by neither part stay with the first one.
+            for dref in mem::replace(&mut rfn.datarefs, Vec::new()) {
+                let in_tail = tail.instructions.iter().any(|op| mentions_addr(op, dref));
+                if in_tail {
+                    tail.datarefs.push(dref);
+                }
+                if !in_tail || rfn.instructions.iter().any(|op| mentions_addr(op, dref)) {
+                    rfn.datarefs.push(dref);
+                }
+            }
+            tail.comments = rfn.comments.split_off(&at);
+            let j = rfn.alt_entries.iter().position(|&a| a >= at).unwrap_or(rfn.alt_entries.len());
+            tail.alt_entries = rfn.alt_entries.split_off(j);
+            tail.alt_entries.retain(|&a| a != at);
+            tail.truncated = rfn.truncated;
+            rfn.truncated = false;
+            tail.is_thumb = rfn.is_thumb;
+            rfn.is_thunk = false;
+            tail
+        };
+
+        // Calls made from the tail, in descending order as removing an edge moves the last one
+        // into its place.
+        let cgid = self.functions[&offset].cgid;
+        let mut moved = self.callgraph
+            .edges_directed(cgid, Direction::Outgoing)
+            .filter(|er| er.weight().csite >= at)
+            .map(|er| (er.id(), er.target()))
+            .collect::<Vec<_>>();
+        moved.sort_by(|a, b| b.0.cmp(&a.0));
+        let mut calls = Vec::new();
+        for (eidx, target) in moved {
+            let target = self.callgraph[target];
+            calls.push((target, self.callgraph.remove_edge(eidx).unwrap()));
+        }
+        self.add_function(tail)?;
+        let tail_cgid = self.functions[&at].cgid;
+        for (target, cctx) in calls {
+            let target = self.callgraph.node_indices().find(|&n| self.callgraph[n] == target);
+            if let Some(target) = target {
+                self.callgraph.add_edge(tail_cgid, target, cctx);
+            }
+        }
+
+        self.rebuild_or_clear(offset);
+        self.rebuild_or_clear(at);
+        Ok(at)
+    }
+
+    // Rebuild the SSA and bindings of the function at `offset` after its instructions were
+    // changed, or clear them if that is not possible.
+    fn rebuild_or_clear(&mut self, offset: u64) {
+        if let Err(e) = self.reanalyze_function(offset) {
+            if self.source.is_some() {
+                radeco_warn!("Unable to rebuild the SSA of {:#x}: {}", offset, e);
+            }
+            let rfn = self.functions.get_mut(&offset).unwrap();
+            rfn.ssa = SSAStorage::new();
+            rfn.bindings = VarBindings::default();
+        }
+    }
+
+    /// Serialize the callgraph to JSON, as an object with a list of `nodes`, each with its
+    /// `addr`, `name` and `is_import`, and a list of `edges`, each with the `from` and `to`
+    /// addresses and the `callsite`.
+    pub fn callgraph_json(&self) -> String {
+        let nodes = self.callgraph
+            .node_indices()
+            .map(|nidx| {
+                let addr = self.callgraph[nidx];
+                let import = self.imports.get(&addr);
+                CallGraphJsonNode {
+                    addr: addr,
+                    name: import.map(|ifn| ifn.name.as_ref())
+                        .or_else(|| self.functions.get(&addr).map(|rfn| rfn.name.as_ref())),
+                    is_import: import.is_some(),
+                }
+            })
+            .collect();
+        let edges = self.callgraph
+            .edge_references()
+            .map(|er| {
+                CallGraphJsonEdge {
+                    from: self.callgraph[er.source()],
+                    to: self.callgraph[er.target()],
+                    callsite: er.weight().csite,
+                }
+            })
+            .collect();
+        serde_json::to_string(&CallGraphJson {
+                nodes: nodes,
+                edges: edges,
+            })
+            .expect("Unable to serialize callgraph")
+    }
+
+    /// Disassemble the function at `offset` using the attached `Source` and construct its SSA,
+    /// replacing any previous one. Bindings are cleared, as they refer to the nodes of the
+    /// previous SSA.
+    pub fn build_function_ssa(&mut self, offset: u64) -> Result<(), String> {
+        let source = match self.source {
+            Some(ref source) => Rc::clone(source),
+            None => return Err("No source attached to the module".to_owned()),
+        };
+        let assume_cc = self.cc.is_some();
+        let sigs = self.noreturn_signatures();
+        let failed_ssa = &mut self.failed_ssa;
+        let rfn = match self.functions.get_mut(&offset) {
+            Some(rfn) => rfn,
+            None => return Err(format!("No known function at {:#x}", offset)),
+        };
+
+        let reg_p = source.register_profile().map_err(|e| e.to_string())?;
+        rfn.instructions = source.disassemble_n_bytes_mode(rfn.size, rfn.offset, rfn.is_thumb)
+            .map_err(|e| e.to_string())?;
+        rfn.truncated = false;
+        rfn.bindings = VarBindings::default();
+        rfn.ssa = SSAStorage::new();
+        let constructed = SSAConstruct::<SSAStorage>::construct(rfn, &reg_p, assume_cc);
+        if constructed.is_ok() {
+            llanalyzer::prune_after_noreturn_with(rfn, &sigs);
+        }
+        match failed_ssa.binary_search(&offset) {
+            Ok(i) if constructed.is_ok() => {
+                failed_ssa.remove(i);
+            }
+            Err(i) if constructed.is_err() => failed_ssa.insert(i, offset),
+            _ => {}
+        }
+        constructed.map_err(|e| format!("SSA construction failed: {}", e))
+    }
+
+    /// Reload the instructions for the function at `offset` from the `Source` and rebuild its
+    /// SSA and bindings. All other functions and the callgraph are left untouched.
+    pub fn reanalyze_function(&mut self, offset: u64) -> Result<(), String> {
+        // Bindings refer to nodes in the old SSA, so they have to be recreated.
+        let had_locals = match self.functions.get(&offset) {
+            Some(rfn) => rfn.bindings.0.iter().any(|vb| vb.btype.is_local()),
+            None => return Err(format!("No known function at {:#x}", offset)),
+        };
+        self.build_function_ssa(offset)?;
+
+        // `build_function_ssa` fails if there is no source.
+        let source = Rc::clone(self.source.as_ref().unwrap());
+        let reg_p = source.register_profile().map_err(|e| e.to_string())?;
+        let sub_reg_f = SubRegisterFile::new(&reg_p);
+        let cc = self.cc.clone();
+        let rfn = self.functions.get_mut(&offset).unwrap();
+        if had_locals {
+            match source.function_at(offset) {
+                Ok(FunctionInfo { locals: Some(ref locals), .. }) => {
+                    ModuleLoader::init_fn_locals(rfn, locals, &sub_reg_f);
+                }
+                Ok(_) => {}
+                Err(e) => radeco_warn!(e),
+            }
+        }
+        if let Some(ref cc) = cc {
+            ModuleLoader::init_fn_bindings(rfn, &sub_reg_f, cc);
+        }
+        Ok(())
+    }
+
+    /// Set the source used for operations that need to go back to the binary, such as
+    /// `reanalyze_function` or `read_bytes`.
+    pub fn set_source(&mut self, source: Rc<Source>) {
+        self.source = Some(source);
+    }
+
+    // Take the module apart to send it to another thread. `session` is the r2 session the
+    // module was loaded from, which is sent along if nothing but the module refers to it
+    // anymore. The lazily built indices are left behind, they are rebuilt when needed.
+    fn into_sent(mut self, session: Option<WrappedR2Api<R2>>) -> SentModule {
+        self.source = None;
+        let r2 = session.and_then(|r2w| match Rc::try_unwrap(r2w) {
+            Ok(r2) => Some(r2.into_inner()),
+            Err(_) => {
+                radeco_warn!("r2 session of {} is still in use, it is not sent", self.name);
+                None
+            }
+        });
+        let imports = self.imports
+            .into_iter()
+            .map(|(plt, ifn)| {
+                let rfn = match Arc::try_unwrap(ifn.rfn) {
+                    Ok(rfn) => rfn.into_inner(),
+                    Err(rfn) => {
+                        let copy = rfn.borrow().clone();
+                        copy
+                    }
+                };
+                (plt, ifn.name, rfn)
+            })
+            .collect();
+        SentModule {
+            r2: r2,
+            name: self.name,
+            path: self.path,
+            symbols: self.symbols,
+            sections: self.sections,
+            imports: imports,
+            import_names: self.import_names,
+            exports: self.exports,
+            relocs: self.relocs,
+            libs: self.libs,
+            entrypoint: self.entrypoint,
+            callgraph: self.callgraph,
+            functions: self.functions,
+            cc: self.cc,
+            noreturn: self.noreturn,
+            failed_ssa: self.failed_ssa,
+            failed_import_ssa: self.failed_import_ssa,
+            load_config: self.load_config,
+        }
+    }
+
+    // Put a module taken apart by `into_sent` back together, with its r2 session as `Source`.
+    // Also returns the session.
+    fn from_sent(sent: SentModule) -> (RadecoModule, Option<WrappedR2Api<R2>>) {
+        let session = sent.r2.map(|r2| Rc::new(RefCell::new(r2)));
+        let imports = sent.imports
+            .into_iter()
+            .map(|(plt, name, rfn)| {
+                let ifn = ImportInfo::new_stub(plt, name);
+                *ifn.rfn.borrow_mut() = rfn;
+                (plt, ifn)
+            })
+            .collect();
+        let rmod = RadecoModule {
+            name: sent.name,
+            path: sent.path,
+            symbols: sent.symbols,
+            sections: sent.sections,
+            imports: imports,
+            import_names: sent.import_names,
+            exports: sent.exports,
+            relocs: sent.relocs,
+            libs: sent.libs,
+            entrypoint: sent.entrypoint,
+            callgraph: sent.callgraph,
+            functions: sent.functions,
+            cc: sent.cc,
+            noreturn: sent.noreturn,
+            failed_ssa: sent.failed_ssa,
+            failed_import_ssa: sent.failed_import_ssa,
+            load_config: sent.load_config,
+            source: session.as_ref().map(|r2w| Rc::new(Rc::clone(r2w)) as Rc<Source>),
+            ..RadecoModule::default()
+        };
+        (rmod, session)
+    }
+
+    /// Options of the `ModuleLoader` that loaded this module. All `false` for modules that were
+    /// not produced by a `ModuleLoader`.
+    pub fn load_config(&self) -> &LoadConfig {
+        &self.load_config
+    }
+
+    /// Was the SSA of the functions constructed while loading. Functions for which it failed
+    /// are listed in `failed_ssa`.
+    pub fn has_ssa(&self) -> bool {
+        let config = &self.load_config;
+        config.build_ssa && !config.metadata_only
+    }
+
+    /// Was the callgraph built while loading
+    pub fn has_callgraph(&self) -> bool {
+        let config = &self.load_config;
+        config.build_callgraph && !config.metadata_only
+    }
+
+    /// Were the argument and return bindings of the functions set up while loading. This
+    /// needs the SSA, the callgraph and an assumed or detected calling convention.
+    pub fn has_bindings(&self) -> bool {
+        let config = &self.load_config;
+        self.has_ssa() && self.has_callgraph() && (config.assume_cc || config.detect_cc)
+    }
+
+    /// Offsets of functions for which SSA construction failed while loading or reanalyzing.
+    /// The SSA of these functions is incomplete and they have no argument or return bindings.
+    pub fn failed_ssa(&self) -> &[u64] {
+        &self.failed_ssa
+    }
+
+    /// PLT addresses of imports for which SSA construction of their stub failed while loading.
+    /// These stubs have no bindings.
+    pub fn failed_import_ssa(&self) -> &[u64] {
+        &self.failed_import_ssa
+    }
+
+    /// Offsets of functions that are not reachable in the callgraph from any entrypoint
+    pub fn dead_functions(&self) -> Vec<u64> {
+        let entries = self.entrypoint.iter().filter_map(|e| e.vaddr).collect::<HashSet<_>>();
+        let roots = self.callgraph
+            .node_indices()
+            .filter(|&n| entries.contains(&self.callgraph[n]))
+            .collect::<Vec<_>>();
+        let reachable = self.callgraph
+            .reachable_from(&roots)
+            .into_iter()
+            .map(|n| self.callgraph[n])
+            .collect::<HashSet<_>>();
+        self.functions.keys().cloned().filter(|off| !reachable.contains(off)).collect()
+    }
+
+    /// Offsets of the functions grouped into strongly connected components of the callgraph,
+    /// ordered such that callees come before their callers. Each group is a set of mutually
+    /// recursive functions, or a single function. Functions that are not a part of the
+    /// callgraph come first, as they have no known callees. Imports are left out.
+    pub fn analysis_order(&self) -> Vec<Vec<u64>> {
+        let mut order = self.functions
+            .iter()
+            .filter(|&(&off, rfn)| self.callgraph.node_weight(rfn.cgid) != Some(&off))
+            .map(|(&off, _)| vec![off])
+            .collect::<Vec<_>>();
+        // SCCs are found in reverse topological order, which puts callees first.
+        for scc in tarjan_scc(&self.callgraph) {
+            let mut group = scc.into_iter()
+                .map(|n| self.callgraph[n])
+                .filter(|off| self.functions.contains_key(off))
+                .collect::<Vec<_>>();
+            if !group.is_empty() {
+                group.sort();
+                order.push(group);
+            }
+        }
+        order
+    }
+}
+
+impl RadecoFunction {
+    pub fn new() -> RadecoFunction {
+        RadecoFunction::default()
+    }
+
+    pub fn instructions(&self) -> &[LOpInfo] {
+        self.instructions.as_slice()
+    }
+
+    /// Decoded view of `instructions`, in order. Instructions without an offset or size are
+    /// skipped.
+    pub fn iter_instructions<'a>(&'a self) -> Box<Iterator<Item = Instruction> + 'a> {
+        box self.instructions.iter().filter_map(Instruction::decode)
+    }
+
+    pub fn ssa(&self) -> &SSAStorage {
+        &self.ssa
+    }
+
+    pub fn ssa_mut(&mut self) -> &mut SSAStorage {
+        &mut self.ssa
+    }
+
+    /// Type of the SSA node `idx`. `None` for nodes that are not values, such as blocks.
+    pub fn node_type(&self, idx: NodeIndex) -> Option<NodeType> {
+        self.ssa.node_data(idx).ok().map(|nd| nd.nt)
+    }
+
+    /// Text of the SSA node `idx`, if it is a comment, e.g. the name of a register at entry.
+    pub fn node_comment(&self, idx: NodeIndex) -> Option<String> {
+        self.ssa.comment(idx)
+    }
+
+    /// Recover conditionals and loops from the CFG of the SSA. Regions that cannot be
+    /// structured, e.g. irreducible ones, are left as `StructuredAst::Goto`s.
+    pub fn structure_cfg(&self) -> StructuredAst {
+        structure::structure(&self.ssa)
+    }
+
+    /// Dominator tree of the CFG of the SSA, rooted at the entry block
+    pub fn dominators(&self) -> DominatorTree {
+        dominators::dominator_tree(&self.ssa)
+    }
+
+    /// Conservative ranges of the unsigned values the SSA values of the function can take,
+    /// see `value_ranges::value_ranges`
+    pub fn value_ranges(&self) -> HashMap<NodeIndex, ValueRange> {
+        value_ranges::value_ranges(&self.ssa)
+    }
+
+    /// Loads and stores in the SSA of the function, in order of address
+    pub fn memory_accesses(&self) -> Vec<MemAccess> {
+        memory_accesses::memory_accesses(&self.ssa)
+    }
+
+    /// Value of the SSA node `idx`, if it is a constant. That is the case for immediates and
+    /// for values that were resolved by constant propagation. The value is truncated to the
+    /// width of the node, as given by `const_width`.
+    pub fn const_value(&self, idx: NodeIndex) -> Option<u64> {
+        let value = match self.ssa.node_data(idx) {
+            Ok(NodeData { nt: NodeType::Op(ir::MOpcode::OpConst(value)), .. }) => value,
+            _ => return None,
+        };
+        match self.const_width(idx) {
+            Some(width) if width > 0 && width < 64 => Some(value & ((1 << width) - 1)),
+            _ => Some(value),
+        }
+    }
+
+    /// Width in bits of the SSA node `idx`, if it is a constant of known width
+    pub fn const_width(&self, idx: NodeIndex) -> Option<u16> {
+        match self.ssa.node_data(idx) {
+            Ok(NodeData { vt, nt: NodeType::Op(ir::MOpcode::OpConst(_)) }) => {
+                vt.width().get_width()
+            }
+            _ => None,
+        }
+    }
+
+    /// Basic blocks in the SSA of this function. Empty if the SSA was not constructed.
+    pub fn basic_blocks(&self) -> Vec<NodeIndex> {
+        self.ssa.blocks()
+    }
+
+    /// Entry block in the SSA of this function, if the SSA was constructed
+    pub fn entry_block(&self) -> Option<NodeIndex> {
+        self.ssa.entry_node()
+    }
+
+    /// Exit block in the SSA of this function, if the SSA was constructed
+    pub fn exit_block(&self) -> Option<NodeIndex> {
+        self.ssa.exit_node()
+    }
+
+    /// Returns the id in the call graph for this function.
+    pub fn cgid(&self) -> NodeIndex {
+        self.cgid
+    }
+
+    pub fn bindings(&self) -> &VarBindings {
+        &self.bindings
+    }
+
+    /// Rename the binding for the SSA node `idx`. Returns `false` if there is no such
+    /// binding.
+    pub fn rename_binding<T: Into<Cow<'static, str>>>(&mut self, idx: NodeIndex, name: T) -> bool {
+        match self.bindings.0.iter_mut().find(|vb| vb.idx == idx) {
+            Some(vb) => {
+                vb.set_name(name);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Replace nodes in the SSA that always evaluate to a constant by the constant, using
+    /// sparse conditional constant propagation, which runs until a fixpoint is reached.
+    /// Returns the number of nodes that were replaced.
+    pub fn propagate_constants(&mut self) -> usize {
+        let (ssa, consts) = {
+            let mut analyzer = sccp::Analyzer::new(&mut self.ssa);
+            analyzer.analyze();
+            let consts = analyzer.constants();
+            (analyzer.emit_ssa(), consts)
+        };
+        self.ssa = ssa;
+
+        // Bindings to replaced nodes now refer to the constant.
+        for vb in &mut self.bindings.0 {
+            if let Some(&val) = consts.get(&vb.idx) {
+                if let Some(cnode) = self.ssa.insert_const(val) {
+                    vb.idx = cnode;
+                }
+            }
+        }
+        consts.len()
+    }
+
+    /// Remove nodes from the SSA whose values are never used and that have no side effects,
+    /// until no more such nodes remain. Nodes referred to by bindings are kept. Returns the
+    /// number of nodes removed.
+    pub fn eliminate_dead_code(&mut self) -> usize {
+        let bound = self.bindings.0.iter().map(|vb| vb.idx).collect::<HashSet<_>>();
+        let mut removed = 0;
+        loop {
+            let dead = {
+                let ssa = &self.ssa;
+                ssa.values()
+                    .into_iter()
+                    .filter(|n| !bound.contains(n) && !ssa.is_selector(*n))
+                    .filter(|&n| match ssa.node_data(n) {
+                        // Constants are shared and tracked separately by the SSA
+                        Ok(NodeData { nt: NodeType::Op(ir::MOpcode::OpConst(_)), .. }) => false,
+                        Ok(NodeData { nt: NodeType::Op(ref op), .. }) => !op.has_sideeffects(),
+                        Ok(_) => true,
+                        // Register states
+                        Err(_) => false,
+                    })
+                    .filter(|&n| ssa.uses_of(n).is_empty())
+                    .collect::<Vec<_>>()
+            };
+            if dead.is_empty() {
+                break;
+            }
+            removed += dead.len();
+            for node in dead {
+                self.ssa.remove_value(node);
+            }
+        }
+        removed
+    }
+
+    /// Drop the register argument bindings whose registers are never read in the function
+    /// body. Registers that are passed on to callees are read by the call, and hence kept.
+    /// Returns the number of bindings dropped.
+    pub fn refine_arguments(&mut self) -> usize {
+        let before = self.bindings.0.len();
+        let ssa = &self.ssa;
+        self.bindings.0.retain(|vb| {
+            if let BindingType::RegisterArgument(_) = vb.btype {
+                // Register state nodes only carry the value in and out of the function, and
+                // are not uses of it.
+                ssa.uses_of(vb.idx).into_iter().any(|u| ssa.node_data(u).is_ok())
+            } else {
+                true
+            }
+        });
+        before - self.bindings.0.len()
+    }
+
+    /// Render the function as C-like pseudocode, see `pseudocode::emit`
+    pub fn to_pseudocode(&self, sub_reg_f: &SubRegisterFile) -> String {
+        pseudocode::emit(self, sub_reg_f)
+    }
+
+    /// Returns the address and `OpCall` node of every call made by the function, sorted by
+    /// address. Unlike the callgraph, this also includes indirect calls. The address is 0 if
+    /// it is unknown.
+    pub fn callsites(&self) -> Vec<(u64, NodeIndex)> {
+        let mut csites = self.ssa
+            .values()
+            .into_iter()
+            .filter(|&n| self.ssa.opcode(n) == Some(ir::MOpcode::OpCall))
+            .map(|n| (self.ssa.address(n).map_or(0, |a| a.address), n))
+            .collect::<Vec<_>>();
+        csites.sort();
+        csites
+    }
+
+    /// Returns `true` if the function never returns to its caller. That is the case if the
+    /// exit block cannot be reached, or if every path to it goes through a call to one of
+    /// `llanalyzer::NORETURN_IMPORTS`. Always `false` if the SSA was not constructed. See
+    /// `RadecoModule::is_function_noreturn` to take the functions of a module into account.
+    pub fn is_noreturn(&self) -> bool {
+        self.calls_noreturn(|name| {
+            llanalyzer::NORETURN_IMPORTS.contains(&llanalyzer::import_name(name))
+        })
+    }
+
+    // Check of `is_noreturn`, with `noreturn` telling whether the called function of the
+    // given name does not return.
+    fn calls_noreturn<F: Fn(&str) -> bool>(&self, noreturn: F) -> bool {
+        let ssa = &self.ssa;
+        if ssa.entry_node().is_none() {
+            return false;
+        }
+        let exit = match ssa.exit_node() {
+            Some(exit) => exit,
+            None => return true,
+        };
+        ssa.preds_of(exit).into_iter().all(|block| {
+            ssa.exprs_in(block).into_iter().any(|expr| {
+                ssa.opcode(expr) == Some(ir::MOpcode::OpCall) &&
+                ssa.sparse_operands_of(expr)
+                    .into_iter()
+                    .find(|&(i, _)| i == 0)
+                    .and_then(|(_, target)| ssa.comment(target))
+                    .map_or(false, |target| noreturn(target.as_str()))
+            })
+        })
+    }
+
+    /// (Data-) addresses referenced by this function
+    pub fn datarefs(&self) -> &[u64] {
+        &self.datarefs
+    }
+
+    /// Is the value returned by this function used by its callers. `None` unless
+    /// `llanalyzer::analyze_return_liveness` was run and the function has a return binding.
+    pub fn is_return_live(&self) -> Option<bool> {
+        self.return_live
+    }
+
+    pub fn set_return_live(&mut self, live: bool) {
+        self.return_live = Some(live);
+    }
+
+    /// Render the SSA of the function as Graphviz DOT, for debugging. Values are labeled by
+    /// their opcode, constant or comment and grouped in one cluster per basic block, and data
+    /// edges are labeled by the operand index.
+    pub fn ssa_dot(&self) -> String {
+        dot::emit_dot(&self.ssa)
+    }
+
+    /// Check the structural invariants of the SSA of the function, such as every operand
+    /// being a valid value. See `verifier::verify_structure` for the full list. All
+    /// violations found are returned.
+    pub fn verify_ssa(&self) -> Result<(), Vec<SsaError>> {
+        let errors = verifier::verify_structure(&self.ssa);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Estimate the size of the stack frame of the function, see `stack::frame_size`
+    pub fn stack_frame_size(&self) -> Option<u64> {
+        stack::frame_size(&self.ssa)
+    }
+
+    /// Loads of arguments passed on the stack, as `(index, load)` sorted by index. These are
+    /// the loads in the first block of the function from a constant offset above the return
+    /// address, `[SP + k]` relative to the stack pointer at entry. The size of a stack slot is
+    /// the width of the stack pointer, as narrowed on the way to the address (e.g. `esp`).
+    /// Only the first load of every slot is returned.
+    pub fn stack_arguments(&self) -> Vec<(usize, NodeIndex)> {
+        let ssa = &self.ssa;
+        let sp = match stack::entry_stack_pointer(ssa) {
+            Some(sp) => sp,
+            None => return Vec::new(),
+        };
+        let entry = ssa.entry_node();
+        let in_first_block = |n: NodeIndex| {
+            ssa.block_for(n).map_or(false, |b| {
+                Some(b) == entry ||
+                ssa.starting_address(b).map_or(false, |a| a.address == self.offset)
+            })
+        };
+
+        // Offset from the entry stack pointer and width of every address derived from it
+        let sp_width = ssa.node_data(sp).ok().and_then(|d| d.vt.width().get_width()).unwrap_or(64);
+        let mut offsets = HashMap::new();
+        offsets.insert(sp, (0i64, sp_width));
+        let mut worklist = vec![sp];
+        let mut args = BTreeMap::new();
+        while let Some(node) = worklist.pop() {
+            let (offset, width) = offsets[&node];
+            let mut users = ssa.uses_of(node);
+            users.sort();
+            for user in users {
+                let operands = ssa.operands_of(user);
+                let imm = operands.iter().find(|&&o| o != node).and_then(|&o| ssa.constant(o));
+                let new = match (ssa.opcode(user), imm) {
+                    (Some(ir::MOpcode::OpAdd), Some(imm)) => {
+                        (offset.wrapping_add(imm as i64), width)
+                    }
+                    (Some(ir::MOpcode::OpSub), Some(imm)) if operands[0] == node => {
+                        (offset.wrapping_sub(imm as i64), width)
+                    }
+                    (Some(ir::MOpcode::OpNarrow(w)), _) => (offset, cmp::min(width, w)),
+                    (Some(ir::MOpcode::OpZeroExt(_)), _) => (offset, width),
+                    (Some(ir::MOpcode::OpLoad), _) if operands.get(1) == Some(&node) => {
+                        // The return address takes the first slot.
+                        let slot = cmp::max(width as i64 / 8, 1);
+                        if offset >= slot && offset % slot == 0 && in_first_block(user) {
+                            args.entry((offset / slot - 1) as usize).or_insert(user);
+                        }
+                        continue;
+                    }
+                    _ => continue,
+                };
+                if !offsets.contains_key(&user) {
+                    offsets.insert(user, new);
+                    worklist.push(user);
+                }
+            }
+        }
+        args.into_iter().collect()
+    }
+
+    /// Returns true if the function is instrumented with a stack canary, see
+    /// `stack::has_canary`
+    pub fn has_stack_canary(&self) -> bool {
+        stack::has_canary(&self.ssa)
+    }
+
+    /// Hash of the instructions of the function that does not depend on where the function
+    /// is loaded or on the registers it uses. Immediates that are jump or call targets, or
+    /// that point into the function itself, are masked out. Registers, the names in ESIL
+    /// expressions, are renamed in order of first use. Functions with the same hash most likely
+    /// have the same code. See `signature_hash_with` to also mask out pointers into a module.
+    pub fn signature_hash(&self) -> u64 {
+        self.hash_code(None, None)
+    }
+
+    /// `signature_hash` that also masks out immediates that point into a section of `rmod`,
+    /// the module of the function, and only renames the registers of `regfile`. Registers are
+    /// then also renamed in instructions without ESIL.
+    pub fn signature_hash_with(&self, rmod: &RadecoModule, regfile: &SubRegisterFile) -> u64 {
+        self.hash_code(Some(rmod), Some(regfile))
+    }
+
+    fn hash_code(&self, rmod: Option<&RadecoModule>, regfile: Option<&SubRegisterFile>) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        let mut regs = HashMap::new();
+        for op in &self.instructions {
+            let (text, is_esil) = match (op.esil.as_ref(), op.opcode.as_ref()) {
+                (Some(esil), _) => (esil.as_str(), true),
+                (None, Some(opcode)) => (opcode.as_str(), false),
+                (None, None) => ("", false),
+            };
+            for token in text.split(|c| c == ',' || c == ' ').filter(|t| !t.is_empty()) {
+                let value = if token.starts_with("0x") {
+                    u64::from_str_radix(&token[2..], 16).ok()
+                } else {
+                    token.parse::<u64>().ok()
+                };
+                // ESIL operators are made up of symbols and its internal variables start
+                // with `$`, so any other name is a register.
+                let is_reg = match regfile {
+                    Some(regfile) => regfile.named_registers.contains_key(token),
+                    None => is_esil && token.starts_with(|c: char| c.is_alphabetic()),
+                };
+                match value {
+                    Some(v) if op.jump == Some(v) ||
+                               (v >= self.offset && v - self.offset < self.size) ||
+                               rmod.map_or(false, |rmod| rmod.section_at(v).is_some()) => {
+                        "addr".hash(&mut hasher)
+                    }
+                    Some(v) => v.hash(&mut hasher),
+                    None if is_reg => {
+                        let n = regs.len();
+                        regs.entry(token).or_insert(n).hash(&mut hasher)
+                    }
+                    None => token.hash(&mut hasher),
+                }
+            }
+            // Keep instruction boundaries
+            ';'.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// Returns true if the function takes a variable number of arguments. Only known for
+    /// imports with a known signature.
+    pub fn is_variadic(&self) -> bool {
+        self.variadic
+    }
+
+    /// Attach a comment to `addr`, replacing any previous one.
+    pub fn set_comment<T: Into<String>>(&mut self, addr: u64, text: T) {
+        self.comments.insert(addr, text.into());
+    }
+
+    /// Comment attached to `addr`, if any
+    pub fn comment(&self, addr: u64) -> Option<&str> {
+        self.comments.get(&addr).map(|c| c.as_str())
+    }
+
+    /// All comments of the function, by address
+    pub fn comments(&self) -> &BTreeMap<u64, String> {
+        &self.comments
+    }
+
+    /// Name of the function as found in the binary, before any demangling.
+    pub fn mangled_name(&self) -> &str {
+        self.mangled_name.as_ref().unwrap_or(&self.name)
+    }
+
+    /// Returns true if the function is a thunk, that is a single unconditional jump to an
+    /// imported function.
+    pub fn is_thunk(&self) -> bool {
+        self.is_thunk
+    }
+
+    /// Kind of the function, `FunctionType::Trampoline` for thunks and
+    /// `FunctionType::Function` otherwise
+    pub fn kind(&self) -> FunctionType {
+        if self.is_thunk {
+            FunctionType::Trampoline
+        } else {
+            FunctionType::Function
+        }
+    }
+
+    /// Addresses other than `offset` at which the function can be entered, in ascending
+    /// order. Added through `RadecoModule::add_alt_entry`.
+    pub fn alt_entries(&self) -> &[u64] {
+        &self.alt_entries
+    }
+
+    /// Returns true if the function is made up of ARM Thumb instructions.
+    pub fn is_thumb(&self) -> bool {
+        self.is_thumb
+    }
+
+    /// Were the instructions of this function truncated while loading
+    pub fn truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Bytes of the function as read from the `Source`, as many as were disassembled. Empty
+    /// unless the module was loaded with `ModuleLoader::keep_bytes`.
+    pub fn raw_bytes(&self) -> &[u8] {
+        &self.raw_bytes
+    }
+
+    /// Returns `Some(true)` if the function is known to be recursive, either directly or
+    /// mutually. `None` if the callgraph was not built.
+    pub fn is_recursive(&self) -> Option<bool> {
+        self.is_recursive
+    }
+
+    /// Size of the function in bytes
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Correct the size of the function. Only affects instructions loaded after the change.
+    pub fn set_size(&mut self, size: u64) {
+        self.size = size;
+    }
+}
+
+/// Split an ARM code address into the actual address and whether it refers to Thumb code.
+fn split_thumb_bit(addr: u64) -> (u64, bool) {
+    (addr & !1, addr & 1 == 1)
+}
+
+/// Demangle a Rust or C++ symbol name, optionally prefixed by r2's `sym.`. Returns `None` for
+/// names that are not mangled.
+fn demangle_name(name: &str) -> Option<String> {
+    let name = if name.starts_with("sym.") { &name[4..] } else { name };
+    if let Ok(demangled) = rustc_demangle::try_demangle(name) {
+        // The alternate format leaves out the trailing hash
+        return Some(format!("{:#}", demangled));
+    }
+    cpp_demangle::Symbol::new(name).ok().map(|sym| sym.to_string())
+}
+
+// Does `op` have `addr` as an immediate in its ESIL or opcode
+fn mentions_addr(op: &LOpInfo, addr: u64) -> bool {
+    let text = op.esil.as_ref().or(op.opcode.as_ref()).map_or("", |s| s.as_str());
+    text.split(|c| c == ',' || c == ' ' || c == '[' || c == ']').any(|token| {
+        let value = if token.starts_with("0x") {
+            u64::from_str_radix(&token[2..], 16).ok()
+        } else {
+            token.parse::<u64>().ok()
+        };
+        value == Some(addr)
+    })
+}
+
+// Layout of the output of `RadecoModule::callgraph_json`
+#[derive(Serialize)]
+struct CallGraphJson<'a> {
+    nodes: Vec<CallGraphJsonNode<'a>>,
+    edges: Vec<CallGraphJsonEdge>,
+}
+
+#[derive(Serialize)]
+struct CallGraphJsonNode<'a> {
+    addr: u64,
+    name: Option<&'a str>,
+    is_import: bool,
+}
+
+#[derive(Serialize)]
+struct CallGraphJsonEdge {
+    from: u64,
+    to: u64,
+    callsite: u64,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CallContextInfo {
+    /// NodeIndex mapping from a node in the caller's context to a node in callee's context
+    pub map: Vec<(NodeIndex, NodeIndex)>,
+    /// NodeIndex corresponding to callsite (`OpCall`) in the caller context
+    pub csite_node: NodeIndex,
+    /// Address of callsite
+    pub csite: u64,
+    /// Is the call a jump to another function, which reuses the frame of the caller
+    #[serde(default)]
+    pub is_tailcall: bool,
+    /// Was the edge added by resolving the target of an indirect call
+    #[serde(default)]
+    pub resolved_indirect: bool,
+}
+
+#[cfg(test)]
 mod test {
     use super::*;
+    use analysis::memory_accesses::AccessKind;
+    use frontend::radeco_source::{BufferSource, GoblinSource, SourceErr};
+    use frontend::ssaconstructor::SSAConstruct;
+    use middle::ir::MAddress;
+    use middle::ssa::ssa_traits::ValueInfo;
+    use middle::ssa::ssastorage;
+    use r2api::structs::{LCallInfo, LFlagInfo, LFunctionInfo, LVarRef};
+    use std::env;
+    use std::io::{Read, Write};
+    use std::sync::atomic::ATOMIC_USIZE_INIT;
+
+    const REGISTER_PROFILE: &'static str = "test_files/x86_register_profile.json";
+
+    // `Source` serving canned information that records the disassembly requests made to it.
+    #[derive(Default)]
+    struct MockSource {
+        functions: Vec<FunctionInfo>,
+        symbols: Vec<LSymbolInfo>,
+        sections: Vec<LSectionInfo>,
+        entrypoint: Vec<LEntryInfo>,
+        imports: Vec<LImportInfo>,
+        instructions: Vec<LOpInfo>,
+        disassembled: RefCell<Vec<(u64, u64)>>,
+    }
+
+    impl Source for MockSource {
+        fn functions(&self) -> Result<Vec<FunctionInfo>, SourceErr> {
+            Ok(self.functions.clone())
+        }
+
+        fn instructions_at(&self, _: u64) -> Result<Vec<LOpInfo>, SourceErr> {
+            Ok(Vec::new())
+        }
+
+        fn register_profile(&self) -> Result<LRegInfo, SourceErr> {
+            let mut s = String::new();
+            File::open(REGISTER_PROFILE)?.read_to_string(&mut s)?;
+            Ok(serde_json::from_str(&s)?)
+        }
+
+        fn flags(&self) -> Result<Vec<LFlagInfo>, SourceErr> {
+            Ok(Vec::new())
+        }
+
+        fn sections(&self) -> Result<Vec<LSectionInfo>, SourceErr> {
+            Ok(self.sections.clone())
+        }
+
+        fn symbols(&self) -> Result<Vec<LSymbolInfo>, SourceErr> {
+            Ok(self.symbols.clone())
+        }
+
+        fn imports(&self) -> Result<Vec<LImportInfo>, SourceErr> {
+            Ok(self.imports.clone())
+        }
+
+        fn exports(&self) -> Result<Vec<LExportInfo>, SourceErr> {
+            Ok(Vec::new())
+        }
+
+        fn relocs(&self) -> Result<Vec<LRelocInfo>, SourceErr> {
+            Ok(Vec::new())
+        }
+
+        fn libraries(&self) -> Result<Vec<String>, SourceErr> {
+            Ok(Vec::new())
+        }
+
+        fn entrypoint(&self) -> Result<Vec<LEntryInfo>, SourceErr> {
+            Ok(self.entrypoint.clone())
+        }
+
+        fn disassemble_n_bytes(&self, n: u64, at: u64) -> Result<Vec<LOpInfo>, SourceErr> {
+            self.disassembled.borrow_mut().push((n, at));
+            Ok(self.instructions
+                .iter()
+                .filter(|op| op.offset.map_or(false, |o| o >= at && o < at + n))
+                .cloned()
+                .collect())
+        }
+    }
+
+    fn register_profile() -> LRegInfo {
+        MockSource::default().register_profile().unwrap()
+    }
+
+    fn load_instructions(file: &str) -> Vec<LOpInfo> {
+        let mut s = String::new();
+        File::open(file).unwrap().read_to_string(&mut s).unwrap();
+        let info: LFunctionInfo = serde_json::from_str(&s).unwrap();
+        info.ops.unwrap()
+    }
+
+    // Function with SSA constructed for the instructions in `file`.
+    fn ssa_function(file: &str) -> RadecoFunction {
+        let mut rfn = RadecoFunction::default();
+        rfn.instructions = load_instructions(file);
+        rfn.offset = rfn.instructions[0].offset.unwrap();
+        SSAConstruct::<SSAStorage>::construct(&mut rfn, &register_profile(), false).unwrap();
+        rfn
+    }
+
+    // Function with SSA constructed from one instruction per ESIL string.
+    fn esil_function(esil: &[&str]) -> RadecoFunction {
+        ops_function(esil.iter().map(|e| esil_op(e)).collect())
+    }
+
+    fn esil_op(esil: &str) -> LOpInfo {
+        let mut op = LOpInfo::default();
+        op.size = Some(1);
+        op.esil = Some(esil.to_owned());
+        op
+    }
+
+    // Function made of `ops`, placed at consecutive offsets starting from 0
+    fn ops_function(mut ops: Vec<LOpInfo>) -> RadecoFunction {
+        for (i, op) in ops.iter_mut().enumerate() {
+            op.offset = Some(i as u64);
+        }
+        let mut rfn = RadecoFunction::default();
+        rfn.size = ops.len() as u64;
+        rfn.instructions = ops;
+        SSAConstruct::<SSAStorage>::construct(&mut rfn, &register_profile(), false).unwrap();
+        rfn
+    }
+
+    // Values of the registers at the exit of `rfn` that are constants
+    fn exit_constants(rfn: &RadecoFunction) -> Vec<u64> {
+        let ssa = rfn.ssa();
+        let rs = ssa.registers_in(ssa.exit_node().unwrap()).unwrap();
+        ssa.operands_of(rs).into_iter().filter_map(|n| ssa.constant(n)).collect()
+    }
+
+    fn mock_function(offset: u64, size: u64, name: &str) -> RadecoFunction {
+        let mut rfn = RadecoFunction::default();
+        rfn.offset = offset;
+        rfn.name = Cow::from(name.to_owned());
+        rfn.set_size(size);
+        rfn
+    }
+
+    fn strat_small_fn(_: Option<&Rc<Source>>, _: &FLResult, _: &RadecoModule) -> FLResult {
+        let mut fl = FLResult::default();
+        fl.functions.insert(0x1000, mock_function(0x1000, 0x10, "small"));
+        fl.new = 1;
+        fl
+    }
+
+    fn strat_fix_size(_: Option<&Rc<Source>>, last: &FLResult, _: &RadecoModule) -> FLResult {
+        let mut fl = FLResult::default();
+        for (off, rfn) in &last.functions {
+            let mut rfn = rfn.clone();
+            rfn.set_size(0x20);
+            fl.functions.insert(*off, rfn);
+        }
+        fl
+    }
+
+    #[test]
+    fn test_load_libs() {
+        // `fixture` links against `libfixture.so` next to it. `libc.so.6` is not found there
+        // and is skipped.
+        let rp = ProjectLoader::default()
+            .path("./test_files/load_libs/fixture")
+            .load_libs()
+            .load_library_path("./test_files/load_libs")
+            .load()
+            .unwrap();
+        assert_eq!(rp.iter().count(), 2);
+        let lib = rp.nth_module(1).unwrap();
+        assert_eq!(lib.name(), "libfixture.so");
+        assert!(lib.load_config().stub_imports);
+    }
+
+    #[test]
+    fn test_load_libs_parallel() {
+        let load = |parallel: bool| {
+            let mut loader = ProjectLoader::default()
+                .path("./test_files/load_libs/fixture")
+                .load_libs()
+                .load_library_path("./test_files/load_libs")
+                .module_loader(ModuleLoader::default().build_ssa());
+            if parallel {
+                loader = loader.parallel();
+            }
+            loader.load().unwrap()
+        };
+        let serial = load(false);
+        let parallel = load(true);
+        let names = |rp: &RadecoProject| {
+            rp.iter().map(|m| m.module.name().to_owned()).collect::<Vec<_>>()
+        };
+        assert_eq!(names(&parallel), vec!["fixture", "libfixture.so"]);
+        assert_eq!(names(&parallel), names(&serial));
+
+        // The library comes back with its functions, imports and r2 session.
+        let (lib, serial_lib) = (parallel.nth_module(1).unwrap(), serial.nth_module(1).unwrap());
+        assert_eq!(lib.functions.keys().collect::<Vec<_>>(),
+                   serial_lib.functions.keys().collect::<Vec<_>>());
+        assert_eq!(lib.imports.len(), serial_lib.imports.len());
+        assert!(lib.load_config().build_ssa);
+        assert!(lib.source.is_some());
+    }
+
+    #[test]
+    fn test_import_by_name() {
+        let mut rp = ProjectLoader::default()
+            .path("./ex-bins/simple2")
+            .load()
+            .unwrap();
+        let rmod = rp.nth_module_mut(0).unwrap();
+        let plt = rmod.import_by_name("__libc_start_main").map(|ii| ii.plt);
+        assert!(plt.is_some());
+        assert_eq!(rmod.import_by_name_mut("__libc_start_main").map(|ii| ii.plt), plt);
+        assert!(rmod.import_by_name("not_an_import").is_none());
+    }
+
+    // Universal binary with an x86_64 and an arm64 slice, holding 4 bytes each
+    fn fat_binary() -> Vec<u8> {
+        let mut data = vec![0u8; 0x1004];
+        let header: &[u32] = &[0xcafebabe, 2, 0x0100_0007, 3, 0x1000, 4, 12, 0x0100_000c, 0,
+                               0x800, 4, 14];
+        for (i, word) in header.iter().enumerate() {
+            for b in 0..4 {
+                data[i * 4 + b] = (word >> (24 - 8 * b)) as u8;
+            }
+        }
+        data[0x800..0x804].copy_from_slice(b"arm!");
+        data[0x1000..0x1004].copy_from_slice(b"x64!");
+        data
+    }
+
+    #[test]
+    fn test_fat_slices() {
+        assert_eq!(fat_slices(&fat_binary()),
+                   Some(vec![("x86_64".to_owned(), 0x1000, 4), ("arm64".to_owned(), 0x800, 4)]));
+        // Java class file, version 52
+        assert_eq!(fat_slices(&[0xca, 0xfe, 0xba, 0xbe, 0, 0, 0, 0x34]), None);
+        assert_eq!(fat_slices(&[0x7f, b'E', b'L', b'F']), None);
+        // Truncated slice table
+        assert_eq!(fat_slices(&fat_binary()[..24]), None);
+
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().subsec_nanos();
+        let path = env::temp_dir().join(format!("radeco_test_fat_slices.{:x}", nanos));
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .unwrap()
+            .write_all(&fat_binary())
+            .unwrap();
+        assert_eq!(ProjectLoader::list_slices(&path).unwrap(), vec!["x86_64", "arm64"]);
+        // Every extraction gets a copy of its own.
+        let slice_path = extract_slice(&path, "arm64").unwrap();
+        let other_path = extract_slice(&path, "arm64").unwrap();
+        assert!(slice_path != other_path);
+        let mut slice = Vec::new();
+        File::open(&slice_path).unwrap().read_to_end(&mut slice).unwrap();
+        assert_eq!(slice, b"arm!");
+        match extract_slice(&path, "ppc") {
+            Err(LoadError::NoSlice(ref arch)) if arch == "ppc" => {}
+            _ => panic!("Expected LoadError::NoSlice"),
+        }
+        for p in &[path, slice_path, other_path] {
+            fs::remove_file(p).unwrap();
+        }
+        assert!(ProjectLoader::list_slices("test_files/file").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_load_error() {
+        match ProjectLoader::default().load() {
+            Err(LoadError::NoSource) => {}
+            _ => panic!("Expected LoadError::NoSource"),
+        }
+        match ProjectLoader::default().path("./ex-bins/does-not-exist").load() {
+            Err(LoadError::Io(_)) => {}
+            _ => panic!("Expected LoadError::Io"),
+        }
+    }
+
+    #[test]
+    fn test_section_permissions() {
+        let section = |name: &str, vaddr, size, flags: &str| {
+            let mut section = LSectionInfo::default();
+            section.name = Some(name.to_owned());
+            section.vaddr = Some(vaddr);
+            section.size = Some(size);
+            section.flags = Some(flags.to_owned());
+            section
+        };
+        let mut rmod = RadecoModule::default();
+        rmod.sections = Arc::new(vec![section(".data", 0x2000, 0x100, "-rw-"),
+                                      section(".text", 0x1000, 0x100, "-r-x"),
+                                      // Overlaps the end of `.data`
+                                      section(".rodata", 0x2080, 0x80, "-r--")]);
+
+        assert!(rmod.is_executable(0x1000));
+        assert!(!rmod.is_writable(0x1000));
+        assert!(rmod.is_executable(0x10ff));
+        assert!(!rmod.is_executable(0x1100));
+        assert!(rmod.is_writable(0x2000));
+        assert!(!rmod.is_executable(0x2000));
+        assert!(!rmod.is_writable(0x2080));
+        assert!(!rmod.is_writable(0x3000));
+    }
+
+    #[test]
+    fn test_symbol_at() {
+        let mut rmod = RadecoModule::default();
+        rmod.symbols = [("a", 0x1000, 0x10), ("b", 0x1010, 0x20)]
+            .iter()
+            .map(|&(name, vaddr, size)| {
+                let mut sym = LSymbolInfo::default();
+                sym.name = Some(name.to_owned());
+                sym.vaddr = Some(vaddr);
+                sym.size = Some(size);
+                sym
+            })
+            .collect();
+        let name_at = |addr| rmod.symbol_at(addr).and_then(|s| s.name.clone());
+
+        assert_eq!(name_at(0x1000), Some("a".to_owned()));
+        assert_eq!(name_at(0x1008), Some("a".to_owned()));
+        assert_eq!(name_at(0x1010), Some("b".to_owned()));
+        assert_eq!(name_at(0x1030), None);
+        assert_eq!(name_at(0xfff), None);
+    }
+
+    #[test]
+    fn test_resolve_address() {
+        let section = |name: &str, vaddr, size| {
+            let mut section = LSectionInfo::default();
+            section.name = Some(name.to_owned());
+            section.vaddr = Some(vaddr);
+            section.size = Some(size);
+            section
+        };
+        let mut main = RadecoModule::new("main".to_owned());
+        main.sections = Arc::new(vec![section(".text", 0x1000, 0x1000)]);
+        main.functions.insert(0x1000, mock_function(0x1000, 0x10, "a"));
+        let mut lib = RadecoModule::new("lib".to_owned());
+        lib.sections = Arc::new(vec![section(".text", 0x1800, 0x1000)]);
+        let mut sym = LSymbolInfo::default();
+        sym.name = Some("b".to_owned());
+        sym.vaddr = Some(0x2400);
+        sym.size = Some(0x20);
+        lib.symbols = vec![sym];
+        let mut rp = RadecoProject::new();
+        rp.modules = vec![main, lib];
+
+        assert_eq!(rp.resolve_address(0x1008),
+                   Some(SymbolRef {
+                       module: 0,
+                       function: Some(0x1000),
+                       name: Some("a"),
+                       offset: 8,
+                       section: Some(".text"),
+                       overlaps: Vec::new(),
+                   }));
+        let sref = rp.resolve_address(0x1900).unwrap();
+        assert_eq!((sref.module, sref.function, sref.name), (0, None, None));
+        assert_eq!((sref.offset, sref.overlaps), (0x900, vec![1]));
+        let sref = rp.resolve_address(0x2410).unwrap();
+        assert_eq!((sref.module, sref.name, sref.offset), (1, Some("b"), 0x10));
+        assert!(rp.resolve_address(0x3000).is_none());
+    }
+
+    #[test]
+    fn test_function_containing() {
+        let mut rmod = RadecoModule::default();
+        rmod.functions.insert(0x1000, mock_function(0x1000, 0x10, "a"));
+        rmod.functions.insert(0x2000, mock_function(0x2000, 0, "b"));
+        let offset_of = |addr| rmod.function_containing(addr).map(|(&off, _)| off);
+
+        assert_eq!(offset_of(0x1000), Some(0x1000));
+        assert_eq!(offset_of(0x1008), Some(0x1000));
+        assert_eq!(offset_of(0x1010), None);
+        assert_eq!(offset_of(0xfff), None);
+        assert_eq!(offset_of(0x2000), Some(0x2000));
+        assert_eq!(offset_of(0x2001), None);
+    }
+
+    #[test]
+    fn test_xrefs_to_data() {
+        let mut rmod = mock_module(&[0x1000, 0x2000, 0x3000], &[]);
+        rmod.function_mut(0x1000).unwrap().datarefs = vec![0x6000, 0x6008, 0x6000];
+        rmod.function_mut(0x3000).unwrap().datarefs = vec![0x6000];
+        rmod.function_mut(0x2000).unwrap().datarefs = vec![0x6010];
+
+        assert_eq!(rmod.xrefs_to_data(0x6000), vec![0x1000, 0x3000]);
+        assert_eq!(rmod.xrefs_to_data(0x6008), vec![0x1000]);
+        assert!(rmod.xrefs_to_data(0x7000).is_empty());
+
+        rmod.remove_function(0x1000);
+        assert_eq!(rmod.xrefs_to_data(0x6000), vec![0x3000]);
+    }
+
+    #[test]
+    fn test_stack_frame_size() {
+        // push rbp; mov rbp, rsp; sub rsp, 0x20; ...; add rsp, 0x20; pop rbp
+        let fixed = esil_function(&["rbp,8,rsp,-=,rsp,=[8]",
+                                    "rsp,rbp,=",
+                                    "0x20,rsp,-=",
+                                    "0x20,rsp,+=",
+                                    "rsp,[8],rbp,=,8,rsp,+="]);
+        assert_eq!(fixed.stack_frame_size(), Some(0x28));
+
+        let leaf = esil_function(&["rdi,rax,="]);
+        assert_eq!(leaf.stack_frame_size(), Some(0));
+
+        // sub rsp, 0x10; sub rsp, rax
+        let dynamic = esil_function(&["0x10,rsp,-=", "rax,rsp,-="]);
+        assert_eq!(dynamic.stack_frame_size(), None);
+
+        assert_eq!(RadecoFunction::default().stack_frame_size(), None);
+    }
+
+    fn strat_mangled(_: Option<&Rc<Source>>, _: &FLResult, _: &RadecoModule) -> FLResult {
+        let mut fl = FLResult::default();
+        for &(off, name) in &[(0x1000, "_ZN4core3fmt5write17h0123456789abcdefE"),
+                              (0x2000, "sym._ZN3foo3barEv"),
+                              (0x3000, "main")] {
+            fl.functions.insert(off, mock_function(off, 0x10, name));
+        }
+        fl.new = 3;
+        fl
+    }
+
+    #[test]
+    fn test_demangle() {
+        let src: Rc<Source> = Rc::new(MockSource::default());
+        let rmod = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .function_loader(FunctionLoader::default().strategy(&strat_mangled))
+            .demangle()
+            .load(src)
+            .unwrap();
+
+        let rust = rmod.function(0x1000).unwrap();
+        assert_eq!(rust.name, "core::fmt::write");
+        assert_eq!(rust.mangled_name(), "_ZN4core3fmt5write17h0123456789abcdefE");
+        let cpp = rmod.function(0x2000).unwrap();
+        assert_eq!(cpp.name, "foo::bar()");
+        assert_eq!(cpp.mangled_name(), "sym._ZN3foo3barEv");
+        let plain = rmod.function(0x3000).unwrap();
+        assert_eq!(plain.name, "main");
+        assert_eq!(plain.mangled_name(), "main");
+    }
+
+    #[test]
+    fn test_module_diff() {
+        let function = |offset, name, esil: &[&str]| {
+            let mut rfn = esil_function(esil);
+            rfn.offset = offset;
+            rfn.name = Cow::from(name);
+            rfn
+        };
+        let mut old = RadecoModule::default();
+        old.add_function(function(0x1000, "main", &["rdi,rax,="])).unwrap();
+        old.add_function(function(0x2000, "helper", &["1,rax,="])).unwrap();
+        old.add_function(function(0x3000, "fcn.00003000", &["rsi,rdi,+,rax,="])).unwrap();
+        old.add_function(function(0x4000, "gone", &["2,rax,="])).unwrap();
+        let mut new = RadecoModule::default();
+        new.add_function(function(0x1000, "main", &["rdi,rax,="])).unwrap();
+        new.add_function(function(0x2000, "helper", &["2,rax,="])).unwrap();
+        new.add_function(function(0x3100, "fcn.00003100", &["rsi,rdi,+,rax,="])).unwrap();
+        new.add_function(function(0x5000, "fresh", &["3,rax,="])).unwrap();
+
+        let regfile = SubRegisterFile::new(&register_profile());
+        for diff in vec![old.diff(&new), old.diff_with(&new, &regfile)] {
+            assert_eq!(diff.matched, vec![(0x1000, 0x1000), (0x3000, 0x3100)]);
+            assert_eq!(diff.changed, vec![(0x2000, 0x2000)]);
+            assert_eq!(diff.removed, vec![(0x4000, "gone".to_owned())]);
+            assert_eq!(diff.added, vec![(0x5000, "fresh".to_owned())]);
+        }
+    }
+
+    #[test]
+    fn test_recover_jump_tables() {
+        // jmp [rax*8 + 0x4000]; case 1: mov rbx, 1; jmp 4; case 3: mov rbx, 2; mov rax, rbx
+        let mut rfn = esil_function(&["0x4000,rax,8,*,+,[8],rip,=",
+                                      "1,rbx,=",
+                                      "4,rip,=",
+                                      "2,rbx,=",
+                                      "rbx,rax,="]);
+        let mut table = Vec::new();
+        for &entry in &[1u64, 3, 1, 0x20] {
+            for i in 0..8 {
+                table.push((entry >> (8 * i)) as u8);
+            }
+        }
+        let src = BufferSource::new(table, 0x4000, "x86_64");
+
+        let block_at = |rfn: &RadecoFunction, addr: u64| {
+            let ssa = rfn.ssa();
+            ssa.blocks()
+                .into_iter()
+                .find(|&b| ssa.starting_address(b) == Some(MAddress::new(addr, 0)))
+                .unwrap()
+        };
+        let jump = block_at(&rfn, 0);
+        assert_eq!(rfn.ssa().succs_of(jump).len(), 1);
+        assert!(rfn.ssa().preds_of(block_at(&rfn, 3)).is_empty());
+
+        assert_eq!(llanalyzer::recover_jump_tables(&mut rfn, &src), 1);
+        let mut succs = rfn.ssa().succs_of(jump);
+        succs.sort();
+        let mut expected = vec![block_at(&rfn, 1), block_at(&rfn, 3)];
+        expected.sort();
+        assert_eq!(succs, expected);
+
+        // Nothing left to recover
+        assert_eq!(llanalyzer::recover_jump_tables(&mut rfn, &src), 0);
+    }
+
+    #[test]
+    fn test_function_comments() {
+        let mut rfn = mock_function(0x1000, 0x10, "main");
+        assert!(rfn.comments().is_empty());
+        rfn.set_comment(0x1004, "loop header");
+        rfn.set_comment(0x1000, "entry");
+        rfn.set_comment(0x1004, "checks argc");
+        assert_eq!(rfn.comment(0x1004), Some("checks argc"));
+        assert_eq!(rfn.comment(0x1008), None);
+        assert_eq!(rfn.comments().keys().cloned().collect::<Vec<_>>(), vec![0x1000, 0x1004]);
+
+        let json = serde_json::to_string(&rfn).unwrap();
+        let rfn: RadecoFunction = serde_json::from_str(&json).unwrap();
+        assert_eq!(rfn.comment(0x1000), Some("entry"));
+    }
+
+    #[test]
+    fn test_arch_override() {
+        let src: Rc<Source> = Rc::new(BufferSource::new(vec![0xc3], 0x1000, "x86"));
+        assert!(src.register_profile().is_err());
+        let rp = ProjectLoader::default()
+            .source(Rc::clone(&src))
+            .arch("x86")
+            .bits(64)
+            .load()
+            .unwrap();
+        let expected = BufferSource::new(Vec::new(), 0, "x86_64").register_profile().unwrap();
+        assert_eq!(rp.reginfo.whole_names, SubRegisterFile::new(&expected).whole_names);
+        assert_eq!(rp.reginfo.alias_info, SubRegisterFile::new(&expected).alias_info);
+
+        let res = ProjectLoader::default()
+            .source(Rc::new(BufferSource::new(vec![0xc3], 0x1000, "x86")))
+            .arch("arm")
+            .load();
+        match res {
+            Err(LoadError::ArchOverride(SourceErr::NotSupported)) => {}
+            _ => panic!("Expected LoadError::ArchOverride"),
+        }
+    }
+
+    #[test]
+    fn test_stack_canary() {
+        // mov rax, fs:[0x28]; mov [rsp], rax; ...; mov rdx, [rsp]; xor rdx, fs:[0x28]
+        let canary = esil_function(&["0x28,[8],rax,=",
+                                     "rax,rsp,=[8]",
+                                     "1,rax,=",
+                                     "rsp,[8],rdx,=",
+                                     "0x28,[8],rdx,^="]);
+        assert!(canary.has_stack_canary());
+
+        // Only the prologue, the canary is never checked
+        let unchecked = esil_function(&["0x28,[8],rax,=", "rax,rsp,=[8]", "1,rax,="]);
+        assert!(!unchecked.has_stack_canary());
+
+        let plain = esil_function(&["0x28,rax,=", "rax,rsp,=[8]", "rsp,[8],rdx,=", "rax,rdx,^="]);
+        assert!(!plain.has_stack_canary());
+        assert!(!RadecoFunction::default().has_stack_canary());
+    }
+
+    #[test]
+    fn test_var_bindings_lookup() {
+        let mut bindings = VarBindings::default();
+        assert!(bindings.is_empty());
+        bindings.0.push(VarBinding::new(BindingType::RegisterArgument(0),
+                                        None,
+                                        NodeIndex::new(3),
+                                        Some(5)));
+        bindings.0.push(VarBinding::new(BindingType::StackLocal(8), None, NodeIndex::end(), None));
+        assert_eq!(bindings.len(), 2);
+        assert!(!bindings.is_empty());
+
+        assert_eq!(bindings.by_ridx(5).map(|vb| vb.btype()),
+                   Some(BindingType::RegisterArgument(0)));
+        assert!(bindings.by_ridx(3).is_none());
+        assert_eq!(bindings.by_node(NodeIndex::new(3)).and_then(|vb| vb.ridx), Some(5));
+        assert_eq!(bindings.by_node(NodeIndex::end()).map(|vb| vb.btype()),
+                   Some(BindingType::StackLocal(8)));
+        assert!(bindings.by_node(NodeIndex::new(5)).is_none());
+    }
+
+    fn strat_thunks(_: Option<&Rc<Source>>, _: &FLResult, _: &RadecoModule) -> FLResult {
+        let mut fl = FLResult::default();
+        fl.functions.insert(0x1000, mock_function(0x1000, 0x4, "thunk"));
+        fl.functions.insert(0x2000, mock_function(0x2000, 0x4, "jump"));
+        fl.functions.insert(0x3000, mock_function(0x3000, 0x20, "main"));
+        fl.new = 3;
+        fl
+    }
+
+    fn thunks_source() -> Rc<Source> {
+        let mut mock = MockSource::default();
+        let mut import = LImportInfo::default();
+        import.plt = Some(0x400);
+        import.name = Some("puts".to_owned());
+        mock.imports = vec![import];
+        // 0x1000 jumps to an import, 0x2000 to a local function and 0x3000 only starts with
+        // the jump to the import.
+        for &(off, target) in &[(0x1000, 0x400), (0x2000, 0x3000), (0x3000, 0x400), (0x3004, 0x400)] {
+            let mut op = LOpInfo::default();
+            op.offset = Some(off);
+            op.size = Some(4);
+            op.optype = Some("jmp".to_owned());
+            op.jump = Some(target);
+            mock.instructions.push(op);
+        }
+        Rc::new(mock)
+    }
+
+    #[test]
+    fn test_min_fn_size() {
+        let src = thunks_source();
+        let rmod = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .function_loader(FunctionLoader::default().strategy(&strat_thunks))
+            .min_fn_size(8)
+            .load(src)
+            .unwrap();
+        assert_eq!(rmod.functions.keys().cloned().collect::<Vec<_>>(), vec![0x3000]);
+    }
+
+    fn strat_huge_fn(_: Option<&Rc<Source>>, _: &FLResult, _: &RadecoModule) -> FLResult {
+        let mut fl = FLResult::default();
+        fl.functions.insert(0x1000, mock_function(0x1000, 0x200, "huge"));
+        fl.functions.insert(0x2000, mock_function(0x2000, 0x2, "small"));
+        fl.new = 2;
+        fl
+    }
+
+    #[test]
+    fn test_max_ssa_nodes() {
+        let mut mock = MockSource::default();
+        let offsets = (0x1000..0x1200).chain(0x2000..0x2002);
+        mock.instructions = offsets.map(|off| {
+                let mut op = esil_op("rax,rbx,+,rcx,=");
+                op.offset = Some(off);
+                op
+            })
+            .collect();
+        let src: Rc<Source> = Rc::new(mock);
+        let rmod = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .function_loader(FunctionLoader::default().strategy(&strat_huge_fn))
+            .build_ssa()
+            .max_ssa_nodes(300)
+            .load(src)
+            .unwrap();
+
+        assert_eq!(rmod.failed_ssa(), &[0x1000]);
+        assert!(rmod.function(0x1000).unwrap().ssa().entry_node().is_none());
+        assert!(rmod.function(0x2000).unwrap().ssa().entry_node().is_some());
+    }
+
+    #[test]
+    fn test_load_config() {
+        let src = thunks_source();
+        let rmod = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .function_loader(FunctionLoader::default().strategy(&strat_thunks))
+            .build_ssa()
+            .build_callgraph()
+            .load(Rc::clone(&src))
+            .unwrap();
+        assert!(rmod.load_config().build_ssa);
+        assert!(!rmod.load_config().assume_cc);
+        assert!(rmod.has_ssa());
+        assert!(rmod.has_callgraph());
+        assert!(!rmod.has_bindings());
+
+        let rmod = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .function_loader(FunctionLoader::default().strategy(&strat_thunks))
+            .build_ssa()
+            .build_callgraph()
+            .assume_cc()
+            .metadata_only()
+            .load(src)
+            .unwrap();
+        assert!(rmod.load_config().metadata_only);
+        assert!(!rmod.has_ssa());
+        assert!(!rmod.has_callgraph());
+        assert!(!rmod.has_bindings());
+        assert!(!RadecoModule::default().has_ssa());
+    }
+
+    fn mark_visited(rfn: &mut RadecoFunction, sub_reg_f: &SubRegisterFile) {
+        assert!(!sub_reg_f.whole_names.is_empty());
+        let offset = rfn.offset;
+        rfn.set_comment(offset, "visited");
+    }
+
+    #[test]
+    fn test_post_ssa() {
+        let mut mock = MockSource::default();
+        mock.instructions = [0x1000, 0x1004, 0x2000, 0x3000]
+            .iter()
+            .map(|&off| {
+                let mut op = esil_op("1,rax,=");
+                op.offset = Some(off);
+                op
+            })
+            .collect();
+        let src: Rc<Source> = Rc::new(mock);
+        for &parallel in &[false, true] {
+            let mut loader = ModuleLoader::default()
+                .source(Rc::clone(&src))
+                .function_loader(FunctionLoader::default().strategy(&strat_three_fns))
+                .build_ssa()
+                .post_ssa(mark_visited);
+            if parallel {
+                loader = loader.parallel();
+            }
+            let rmod = loader.load(Rc::clone(&src)).unwrap();
+            assert_eq!(rmod.functions.len(), 3);
+            for (&off, rfn) in &rmod.functions {
+                assert_eq!(rfn.comment(off), Some("visited"));
+            }
+        }
+    }
+
+    fn sub_name(offset: u64) -> String {
+        format!("sub_{:x}", offset)
+    }
+
+    #[test]
+    fn test_name_scheme() {
+        let src = thunks_source();
+        let loader = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .function_loader(FunctionLoader::default().strategy(&strat_overlap));
+        let rmod = loader.clone().load(Rc::clone(&src)).unwrap();
+        assert_eq!(rmod.function(0x2000).unwrap().name, "fcn.00002000");
+        assert_eq!(rmod.function(0x4000).unwrap().name, "d");
+        assert_eq!(rmod.imports[&0x400].name, "puts");
+        assert_eq!(rmod.imports[&0x400].rfn.borrow().name, "sym.imp.puts");
+
+        let rmod = loader.name_scheme(sub_name).load(src).unwrap();
+        assert_eq!(rmod.function(0x2000).unwrap().name, "sub_2000");
+        assert_eq!(rmod.function(0x4000).unwrap().name, "d");
+    }
+
+    #[test]
+    fn test_trampoline_without_ssa() {
+        let src = thunks_source();
+        let rmod = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .function_loader(FunctionLoader::default().strategy(&strat_thunks))
+            .build_ssa()
+            .build_callgraph()
+            .assume_cc()
+            .load(src)
+            .unwrap();
+        let plt_stub = rmod.function(0x1000).unwrap();
+        assert_eq!(plt_stub.kind(), FunctionType::Trampoline);
+        assert!(plt_stub.ssa().entry_node().is_none());
+        assert!(plt_stub.bindings().into_iter().next().is_none());
+        assert!(rmod.failed_ssa().is_empty());
+        for &off in &[0x2000, 0x3000] {
+            assert_eq!(rmod.function(off).unwrap().kind(), FunctionType::Function);
+            assert!(rmod.function(off).unwrap().ssa().entry_node().is_some());
+        }
+    }
+
+    #[test]
+    fn test_is_thunk() {
+        let src = thunks_source();
+        let rmod = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .function_loader(FunctionLoader::default().strategy(&strat_thunks))
+            .load(src)
+            .unwrap();
+        assert!(rmod.function(0x1000).unwrap().is_thunk());
+        assert!(!rmod.function(0x2000).unwrap().is_thunk());
+        assert!(!rmod.function(0x3000).unwrap().is_thunk());
+    }
+
+    #[test]
+    fn test_read_bytes() {
+        let src = BufferSource::new(vec![0xde, 0xad, 0xbe, 0xef], 0x1000, "x86_64");
+        let mut rmod = RadecoModule::default();
+        rmod.sections = Arc::new(src.sections().unwrap());
+        assert!(rmod.read_bytes(0x1000, 1).is_err());
+
+        rmod.source = Some(Rc::new(src));
+        assert_eq!(rmod.read_bytes(0x1001, 2).unwrap(), vec![0xad, 0xbe]);
+        assert_eq!(rmod.read_bytes(0x1000, 4).unwrap(), vec![0xde, 0xad, 0xbe, 0xef]);
+        assert!(rmod.read_bytes(0x1002, 4).is_err());
+        assert!(rmod.read_bytes(0x2000, 1).is_err());
+    }
+
+    #[test]
+    fn test_string_at() {
+        let rp = ProjectLoader::default()
+            .path("./ex-bins/bin1")
+            .load()
+            .unwrap();
+        let rmod = rp.nth_module(0).unwrap();
+        assert_eq!(rmod.string_at(0x4006b4), Some("%d %d %d".to_owned()));
+        assert_eq!(rmod.string_at(0x10), None);
+    }
+
+    #[test]
+    fn test_attach_r2() {
+        let r2 = R2::new(Some("./ex-bins/bin1")).unwrap();
+        // Without a path, loading would fail if a new r2 had to be spawned.
+        let rp = ProjectLoader::default().attach_r2(r2).load().unwrap();
+        let rmod = rp.nth_module(0).unwrap();
+        assert!(rmod.functions.len() > 0);
+        assert_eq!(rmod.string_at(0x4006b4), Some("%d %d %d".to_owned()));
+    }
+
+    #[test]
+    fn test_module_name() {
+        let rp = ProjectLoader::default()
+            .path("./test_files/load_libs/fixture")
+            .load_libs()
+            .load_library_path("./test_files/load_libs")
+            .load()
+            .unwrap();
+        let names = rp.iter().map(|m| m.module.name().to_owned()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["fixture", "libfixture.so"]);
+    }
+
+    fn strat_three_fns(_: Option<&Rc<Source>>, _: &FLResult, _: &RadecoModule) -> FLResult {
+        let mut fl = FLResult::default();
+        for &(off, name) in &[(0x1000, "a"), (0x2000, "b"), (0x3000, "c")] {
+            fl.functions.insert(off, mock_function(off, 0x10, name));
+        }
+        fl.new = 3;
+        fl
+    }
+
+    // Overlaps with `strat_three_fns` at 0x2000 with a larger, unnamed function.
+    fn strat_overlap(_: Option<&Rc<Source>>, _: &FLResult, _: &RadecoModule) -> FLResult {
+        let mut fl = FLResult::default();
+        fl.functions.insert(0x2000, mock_function(0x2000, 0x40, ""));
+        fl.functions.insert(0x4000, mock_function(0x4000, 0x10, "d"));
+        fl.new = 2;
+        fl
+    }
+
+    fn load_overlapping(policy: MergePolicy) -> FLResult {
+        FunctionLoader::default()
+            .strategy(&strat_three_fns)
+            .strategy(&strat_overlap)
+            .merge_policy(policy)
+            .load(&RadecoModule::default())
+    }
+
+    #[test]
+    fn test_merge_policy() {
+        let fl = load_overlapping(MergePolicy::FirstWins);
+        assert_eq!(fl.functions[&0x2000].name, "b");
+        assert_eq!(fl.functions[&0x2000].size(), 0x10);
+        assert_eq!(fl.new, 4);
+
+        let fl = load_overlapping(MergePolicy::LastWins);
+        assert_eq!(fl.functions[&0x2000].name, "");
+        assert_eq!(fl.functions[&0x2000].size(), 0x40);
+        assert_eq!(fl.new, 4);
+
+        let fl = load_overlapping(MergePolicy::PreferNamed);
+        assert_eq!(fl.functions[&0x2000].name, "b");
+        assert_eq!(fl.new, 4);
+    }
+
+    #[test]
+    fn test_fl_count() {
+        let fl = FunctionLoader::default()
+            .strategy(&strat_three_fns)
+            .strategy(&strat_three_fns)
+            .strategy(&strat_overlap)
+            .load(&RadecoModule::default());
+        assert_eq!(fl.count(), 4);
+        assert_eq!(fl.count() as usize, fl.functions.len());
+    }
+
+    #[test]
+    fn test_merge_prefer_larger() {
+        let mut a = mock_function(0x1000, 0x10, "a");
+        let b = mock_function(0x1000, 0x20, "b");
+        assert!(MergePolicy::PreferNamed.replaces(&a, &b));
+        assert!(!MergePolicy::PreferNamed.replaces(&b, &a));
+        a.set_size(0x20);
+        assert!(!MergePolicy::PreferNamed.replaces(&a, &b));
+    }
+
+    fn named_a_or_b(rfn: &RadecoFunction) -> bool {
+        rfn.name == "a" || rfn.name == "b"
+    }
+
+    #[test]
+    fn test_filter_range() {
+        let mock = Rc::new(MockSource::default());
+        let src: Rc<Source> = mock.clone();
+        let rmod = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .function_loader(FunctionLoader::default().strategy(&strat_three_fns))
+            .filter(named_a_or_b)
+            .filter_range(0x1800, 0x3800)
+            .load(src).unwrap();
+
+        assert_eq!(rmod.functions.keys().cloned().collect::<Vec<_>>(), vec![0x2000]);
+        assert_eq!(*mock.disassembled.borrow(), vec![(0x10, 0x2000)]);
+    }
+
+    #[test]
+    fn test_strategy_boxed() {
+        let known = vec![0x1000, 0x4000];
+        let strat = move |_: Option<&Rc<Source>>, _: &FLResult, _: &RadecoModule| {
+            let mut fl = FLResult::default();
+            for &off in &known {
+                fl.functions.insert(off, mock_function(off, 0x10, "known"));
+                fl.new += 1;
+            }
+            fl
+        };
+
+        let src: Rc<Source> = Rc::new(MockSource::default());
+        let rmod = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .function_loader(FunctionLoader::default()
+                                 .strategy(&strat_three_fns)
+                                 .strategy_boxed(Box::new(strat)))
+            .load(src).unwrap();
+
+        assert_eq!(rmod.functions.keys().cloned().collect::<Vec<_>>(),
+                   vec![0x1000, 0x2000, 0x3000, 0x4000]);
+        assert_eq!(rmod.function(0x1000).unwrap().name, "known");
+    }
+
+    #[test]
+    fn test_strat_from_call_targets() {
+        let mut sym = LSymbolInfo::default();
+        sym.name = Some("main".to_owned());
+        sym.stype = Some(LSymbolType::Func);
+        sym.vaddr = Some(0x1000);
+        sym.size = Some(0x10);
+
+        let mut call_known = esil_op("0x1000,rip,=");
+        call_known.offset = Some(0x1000);
+        call_known.optype = Some("call".to_owned());
+        call_known.jump = Some(0x1000);
+        let mut call_leaf = esil_op("0x2000,rip,=");
+        call_leaf.offset = Some(0x1001);
+        call_leaf.optype = Some("call".to_owned());
+        call_leaf.jump = Some(0x2000);
+
+        let mut mock = MockSource::default();
+        mock.symbols = vec![sym];
+        mock.instructions = vec![call_known, call_leaf];
+        let src: Rc<Source> = Rc::new(mock);
+        let floader = FunctionLoader::default()
+            .include_defaults()
+            .strategy_boxed(loader_defaults::strat_from_call_targets());
+        let rmod = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .function_loader(floader)
+            .load(src)
+            .unwrap();
+
+        assert_eq!(rmod.functions.keys().cloned().collect::<Vec<_>>(), vec![0x1000, 0x2000]);
+        assert_eq!(rmod.function(0x2000).unwrap().name, "fcn.00002000");
+        assert_eq!(rmod.function(0x2000).unwrap().size(), 0);
+    }
+
+    #[test]
+    fn test_node_type() {
+        let rfn = esil_function(&["rdi,rsi,+,rax,="]);
+        let ssa = rfn.ssa();
+        let add = ssa.values()
+            .into_iter()
+            .find(|&n| ssa.opcode(n) == Some(ir::MOpcode::OpAdd))
+            .unwrap();
+        match rfn.node_type(add) {
+            Some(NodeType::Op(ir::MOpcode::OpAdd)) => {}
+            nt => panic!("Expected OpAdd, found {:?}", nt),
+        }
+        assert_eq!(rfn.node_comment(add), None);
+
+        let regs = ssa.operands_of(add)
+            .into_iter()
+            .filter_map(|n| match rfn.node_type(n) {
+                Some(NodeType::Comment(c)) => {
+                    assert_eq!(rfn.node_comment(n), Some(c.clone()));
+                    Some(c)
+                }
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+        assert_eq!(regs, ["rdi", "rsi"].iter().map(|r| r.to_string()).collect());
+        assert!(rfn.node_type(ssa.entry_node().unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_structure_cfg() {
+        // if (rdi) { rax = 2 } else { rax = 1 }; rbx = rax
+        let rfn = esil_function(&["rdi,?{,3,rip,=,}", "1,rax,=", "4,rip,=", "2,rax,=", "rax,rbx,="]);
+        let seq = match rfn.structure_cfg() {
+            StructuredAst::Seq(seq) => seq,
+            ast => panic!("Expected Seq, found {:?}", ast),
+        };
+        assert!(seq.iter().all(|s| match *s {
+            StructuredAst::Goto(_) | StructuredAst::Loop(_) => false,
+            _ => true,
+        }));
+        let (then_, else_) = seq.iter()
+            .filter_map(|s| match *s {
+                StructuredAst::If { ref then_, ref else_, .. } => Some((then_, else_)),
+                _ => None,
+            })
+            .next()
+            .expect("No If recovered");
+        assert_ne!(**then_, StructuredAst::Seq(Vec::new()));
+        assert!(else_.is_some());
+        assert!(match *seq.last().unwrap() {
+            StructuredAst::BasicBlock(_) => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn test_const_value() {
+        let rfn = esil_function(&["0x1234,rax,=", "rax,rbx,+,rcx,="]);
+        let ssa = rfn.ssa();
+        let imm = ssa.values()
+            .into_iter()
+            .find(|&n| ssa.constant(n) == Some(0x1234))
+            .unwrap();
+        assert_eq!(rfn.const_value(imm), Some(0x1234));
+        assert_eq!(rfn.const_width(imm), Some(64));
+
+        let add = ssa.values()
+            .into_iter()
+            .find(|&n| ssa.opcode(n) == Some(ir::MOpcode::OpAdd))
+            .unwrap();
+        assert_eq!(rfn.const_value(add), None);
+        assert_eq!(rfn.const_width(add), None);
+        assert_eq!(rfn.const_value(ssa.entry_node().unwrap()), None);
+    }
+
+    #[test]
+    fn test_add_call_edge() {
+        let mut rmod = mock_module(&[0x1000, 0x2000], &[]);
+        rmod.functions.insert(0x3000, mock_function(0x3000, 0x10, "fcn.00003000"));
+        let caller = rmod.function(0x1000).unwrap().cgid();
+
+        let edge = rmod.add_call_edge(0x1000, 0x2000, 0x1004);
+        assert_eq!(rmod.add_call_edge(0x1000, 0x2000, 0x1004), edge);
+        rmod.add_call_edge(0x1000, 0x2000, 0x1008);
+        // 0x3000 has no node in the callgraph yet.
+        rmod.add_call_edge(0x1000, 0x3000, 0x100c);
+        let callee = rmod.function(0x3000).unwrap().cgid();
+        assert_eq!(rmod.callgraph.node_weight(callee), Some(&0x3000));
+
+        let mut callees = rmod.callgraph
+            .callees(caller)
+            .map(|(csite, idx)| (csite, rmod.callgraph[idx]))
+            .collect::<Vec<_>>();
+        callees.sort();
+        assert_eq!(callees, vec![(0x1004, 0x2000), (0x1008, 0x2000), (0x100c, 0x3000)]);
+        assert_eq!(rmod.callgraph.edge_count(), 3);
+    }
+
+    #[test]
+    fn test_external_calls_from() {
+        // main calls a wrapper of `system`, and `puts` is only called by an unrelated function.
+        let mut rmod = mock_module(&[0x1000, 0x2000, 0x3000], &[(0, 1), (1, 1)]);
+        rmod.imports.insert(0x400, ImportInfo::new_stub(0x400, Cow::from("system")));
+        rmod.imports.insert(0x410, ImportInfo::new_stub(0x410, Cow::from("puts")));
+        rmod.add_call_edge(0x2000, 0x400, 0x2008);
+        rmod.add_call_edge(0x3000, 0x410, 0x3004);
+
+        let system = ["system".to_owned()].iter().cloned().collect::<HashSet<_>>();
+        assert_eq!(rmod.external_calls_from(0x1000), system);
+        assert_eq!(rmod.external_calls_from(0x2000), system);
+        assert!(rmod.external_calls_from(0x3000).contains("puts"));
+        assert!(rmod.external_calls_from(0x5000).is_empty());
+    }
+
+    #[test]
+    fn test_alt_entries() {
+        let mut rmod = mock_module(&[0x1000, 0x2000], &[]);
+        let mut sym = LSymbolInfo::default();
+        sym.name = Some("main".to_owned());
+        sym.vaddr = Some(0x1000);
+        sym.size = Some(0x10);
+        rmod.symbols = vec![sym];
+
+        // 0xff0 is entered before falling through to 0x1000.
+        rmod.add_alt_entry(0x1000, 0xff0).unwrap();
+        rmod.add_alt_entry(0x1000, 0x1008).unwrap();
+        assert!(rmod.add_alt_entry(0x2000, 0x1008).is_err());
+        assert!(rmod.add_alt_entry(0x1000, 0x2000).is_err());
+        assert!(rmod.add_alt_entry(0x3000, 0x3008).is_err());
+        assert_eq!(rmod.function(0x1000).unwrap().alt_entries(), &[0xff0, 0x1008]);
+
+        assert_eq!(rmod.alt_entry_of(0xff0), Some(0x1000));
+        assert_eq!(rmod.alt_entry_of(0x1000), None);
+        assert_eq!(rmod.function_containing(0xff0).map(|(&off, _)| off), Some(0x1000));
+        assert_eq!(rmod.function_containing(0xfe0).map(|(&off, _)| off), None);
+        assert_eq!(rmod.symbol_at(0xff0).and_then(|s| s.name.clone()),
+                   Some("main".to_owned()));
+
+        rmod.remove_function(0x1000);
+        assert_eq!(rmod.alt_entry_of(0xff0), None);
+        assert!(rmod.function_containing(0xff0).is_none());
+    }
+
+    #[test]
+    fn test_analysis_order() {
+        // 0x1000 calls 0x2000, which is mutually recursive with 0x3000, which calls 0x4000.
+        let mut rmod = mock_module(&[0x1000, 0x2000, 0x3000, 0x4000],
+                                   &[(0, 1), (1, 2), (2, 1), (2, 3)]);
+        rmod.functions.insert(0x5000, mock_function(0x5000, 0x10, "orphan"));
+        assert_eq!(rmod.analysis_order(),
+                   vec![vec![0x5000], vec![0x4000], vec![0x2000, 0x3000], vec![0x1000]]);
+    }
+
+    #[test]
+    fn test_function_at_node() {
+        let mut rmod = mock_module(&[0x1000, 0x2000], &[(0, 1)]);
+        let import = ImportInfo::new_stub(0x400, Cow::from("puts"));
+        let caller = rmod.function(0x1000).unwrap().cgid();
+        let puts = rmod.callgraph.add_node(0x400);
+        import.rfn.borrow_mut().cgid = puts;
+        rmod.imports.insert(0x400, import);
+        rmod.callgraph.add_edge(caller, puts, CallContextInfo::default());
+
+        let mut callees = rmod.callgraph
+            .callees(caller)
+            .map(|(_, idx)| {
+                let rfn = rmod.function_at_node(idx).unwrap();
+                (rfn.name.to_string(), rfn.is_import())
+            })
+            .collect::<Vec<_>>();
+        callees.sort();
+        assert_eq!(callees, vec![("fcn.00002000".to_owned(), false), ("puts".to_owned(), true)]);
+
+        let unknown = rmod.callgraph.add_node(0x5000);
+        assert!(rmod.function_at_node(unknown).is_none());
+        assert!(rmod.function_at_node(NodeIndex::end()).is_none());
+    }
+
+    #[test]
+    fn test_strategy_front() {
+        let mut call = esil_op("0x400,rip,=");
+        call.offset = Some(0x180);
+        call.optype = Some("call".to_owned());
+        call.jump = Some(0x400);
+        let mut sym = LSymbolInfo::default();
+        sym.name = Some("sym".to_owned());
+        sym.stype = Some(LSymbolType::Func);
+        sym.vaddr = Some(0x1000);
+        sym.size = Some(0x10);
+        let mut mock = MockSource::default();
+        mock.symbols = vec![sym];
+        mock.instructions = vec![call];
+        let src: Rc<Source> = Rc::new(mock);
+        let load = |floader: FunctionLoader| {
+            let rmod = ModuleLoader::default()
+                .source(Rc::clone(&src))
+                .function_loader(floader)
+                .load(Rc::clone(&src))
+                .unwrap();
+            rmod.functions.keys().cloned().collect::<Vec<_>>()
+        };
+
+        // Call targets only run if functions were identified before them.
+        let appended = FunctionLoader::default()
+            .strategy_boxed(loader_defaults::strat_from_call_targets())
+            .strategy(&strat_small_main);
+        assert_eq!(load(appended), vec![0x180]);
+        let prepended = FunctionLoader::default()
+            .strategy_boxed(loader_defaults::strat_from_call_targets())
+            .strategy_front(&strat_small_main);
+        assert_eq!(load(prepended), vec![0x180, 0x400]);
+
+        let cleared = FunctionLoader::default()
+            .include_defaults()
+            .clear_strategies()
+            .strategy(&strat_small_main);
+        assert_eq!(load(cleared), vec![0x180]);
+    }
+
+    #[test]
+    fn test_strat_use_entrypoint() {
+        let mut sym = LSymbolInfo::default();
+        sym.name = Some("_start".to_owned());
+        sym.stype = Some(LSymbolType::Func);
+        sym.vaddr = Some(0x1000);
+        sym.size = Some(0x10);
+
+        let mut mock = MockSource::default();
+        mock.symbols = vec![sym];
+        mock.entrypoint = [0x1000, 0x2000]
+            .iter()
+            .map(|&vaddr| {
+                let mut entry = LEntryInfo::default();
+                entry.vaddr = Some(vaddr);
+                entry
+            })
+            .collect();
+
+        let src: Rc<Source> = Rc::new(mock);
+        let rmod = ModuleLoader::default().source(Rc::clone(&src)).load(src).unwrap();
+
+        assert_eq!(rmod.function(0x1000).unwrap().name, "_start");
+        assert_eq!(rmod.function(0x2000).unwrap().name, "entry1");
+    }
+
+    #[test]
+    fn test_entrypoint_names() {
+        let mut rmod = RadecoModule::default();
+        rmod.entrypoint = [(0x1000, "program"), (0x2000, "init"), (0x3000, "init")]
+            .iter()
+            .map(|&(vaddr, etype)| {
+                let mut entry = LEntryInfo::default();
+                entry.vaddr = Some(vaddr);
+                entry.etype = Some(etype.to_owned());
+                entry
+            })
+            .collect();
+        let names = |fl: FLResult| {
+            fl.functions.values().map(|rfn| rfn.name.to_string()).collect::<Vec<_>>()
+        };
+
+        let fl = loader_defaults::strat_use_entrypoint(None, &FLResult::default(), &rmod);
+        assert_eq!(names(fl), vec!["entry.main", "entry.init0", "entry.init1"]);
+
+        let strat = loader_defaults::strat_use_entrypoint_named(|entry: &LEntryInfo, n| {
+            format!("{}_{}", entry.etype.as_ref().unwrap(), n)
+        });
+        let fl = strat.strategy(None, &FLResult::default(), &rmod);
+        assert_eq!(names(fl), vec!["program_0", "init_0", "init_1"]);
+    }
+
+    #[test]
+    fn test_calling_convention() {
+        let sub_reg_f = SubRegisterFile::new(&register_profile());
+        let mut rfn = ssa_function("test_files/tiny_sccp_test_instructions.json");
+        // The test register profile has no `A0` or `SN` aliases, use others that it does have.
+        let cc = CallingConvention::new(&["A1", "A2"], "A3");
+        ModuleLoader::init_fn_bindings(&mut rfn, &sub_reg_f, &cc);
+
+        let bindings = rfn.bindings().into_iter().collect::<Vec<_>>();
+        assert_eq!(bindings.iter().filter(|b| b.btype().is_argument()).count(), 2);
+        assert_eq!(bindings.iter().filter(|b| b.btype().is_return()).count(), 1);
+        assert_eq!(bindings[1].btype(), BindingType::RegisterArgument(1));
+    }
+
+    #[test]
+    fn test_iter_instructions() {
+        let mut rfn = RadecoFunction::default();
+        rfn.instructions = ["push rbp",
+                            "mov qword [rbp - 8], rdi",
+                            "rep movsb byte [rdi], byte [rsi]",
+                            "ldr x0, [x1, 8]",
+                            "ret"]
+            .iter()
+            .enumerate()
+            .map(|(i, disasm)| {
+                let mut op = LOpInfo::default();
+                op.offset = Some(0x1000 + i as u64);
+                op.size = Some(1);
+                op.opcode = Some(disasm.to_string());
+                op
+            })
+            .collect();
+        rfn.instructions[3].size = None;
+
+        let insns = rfn.iter_instructions().collect::<Vec<_>>();
+        assert_eq!(insns.len(), 4);
+        assert_eq!(insns[0],
+                   Instruction {
+                       addr: 0x1000,
+                       size: 1,
+                       mnemonic: "push".to_owned(),
+                       operands: vec!["rbp".to_owned()],
+                   });
+        assert_eq!(insns[1].operands, vec!["qword [rbp - 8]", "rdi"]);
+        assert_eq!(insns[2].mnemonic, "rep movsb");
+        assert_eq!(insns[2].operands, vec!["byte [rdi]", "byte [rsi]"]);
+        assert_eq!((insns[3].addr, insns[3].mnemonic.as_str()), (0x1004, "ret"));
+        assert!(insns[3].operands.is_empty());
+
+        rfn.instructions[3].size = Some(1);
+        let ldr = rfn.iter_instructions().nth(3).unwrap();
+        assert_eq!(ldr.operands, vec!["x0", "[x1, 8]"]);
+    }
+
+    #[test]
+    fn test_memory_accesses() {
+        // rax = [rsp]; [rsp + 8] = rax
+        let rfn = esil_function(&["rsp,[8],rax,=", "rax,8,rsp,+,=[8]"]);
+        let accesses = rfn.memory_accesses();
+        assert_eq!(accesses.len(), 2);
+        let (load, store) = (accesses[0], accesses[1]);
+        assert_eq!((load.kind, store.kind), (AccessKind::Load, AccessKind::Store));
+        assert_eq!((load.width, store.width), (Some(64), Some(64)));
+        let ssa = rfn.ssa();
+        assert_eq!(ssa.opcode(load.node), Some(ir::MOpcode::OpLoad));
+        assert_eq!(ssa.opcode(store.addr), Some(ir::MOpcode::OpAdd));
+        assert!(ssa.operands_of(store.addr).contains(&load.addr));
+        // The stored value is the loaded one.
+        assert!(ssa.operands_of(store.node).contains(&load.node));
+
+        assert!(esil_function(&["rsi,rdi,="]).memory_accesses().is_empty());
+    }
+
+    #[test]
+    fn test_value_ranges() {
+        // rax = 0; while (rax < 10) { rax += 1 }; rbx = rax
+        let rfn = esil_function(&["0,rax,=",
+                                  "10,rax,<,?{,3,rip,=,}",
+                                  "5,rip,=",
+                                  "1,rax,+,rax,=",
+                                  "1,rip,=",
+                                  "rax,rbx,="]);
+        let ssa = rfn.ssa();
+        let ranges = rfn.value_ranges();
+        let find = |op: ir::MOpcode| {
+            ssa.values().into_iter().find(|&n| ssa.opcode(n) == Some(op.clone())).unwrap()
+        };
+        let add = find(ir::MOpcode::OpAdd);
+        let phi = ssa.operands_of(add).into_iter().find(|&n| ssa.is_phi(n)).unwrap();
+        assert_eq!(ranges[&phi], ValueRange { min: 0, max: 10 });
+        assert_eq!(ranges[&add], ValueRange { min: 1, max: 10 });
+        assert_eq!(ranges[&find(ir::MOpcode::OpLt)], ValueRange { min: 0, max: 1 });
+
+        // Nothing is known about the arguments.
+        let rfn = esil_function(&["rdi,rsi,+,rax,="]);
+        let add = rfn.ssa()
+            .values()
+            .into_iter()
+            .find(|&n| rfn.ssa().opcode(n) == Some(ir::MOpcode::OpAdd))
+            .unwrap();
+        assert_eq!(rfn.value_ranges()[&add], ValueRange::full(64));
+
+        assert!(RadecoFunction::default().value_ranges().is_empty());
+    }
+
+    #[test]
+    fn test_dominators() {
+        // if (rdi) { rax = 2 } else { rax = 1 }; rbx = rax
+        let rfn =
+            esil_function(&["rdi,?{,3,rip,=,}", "1,rax,=", "4,rip,=", "2,rax,=", "rax,rbx,="]);
+        let ssa = rfn.ssa();
+        let doms = rfn.dominators();
+        let entry = ssa.entry_node().unwrap();
+        assert_eq!(doms.root(), Some(entry));
+        assert_eq!(doms.idom(entry), None);
+        let blocks = ssa.blocks().into_iter().filter(|&b| doms.is_reachable(b)).collect::<Vec<_>>();
+        assert!(blocks.len() >= 4);
+        for &b in &blocks {
+            assert!(doms.dominates(entry, b));
+            assert!(doms.dominates(b, b));
+            if b != entry {
+                assert!(!doms.dominates(b, entry));
+                assert!(blocks.contains(&doms.idom(b).unwrap()));
+            }
+        }
+        // Neither side of the branch dominates the block where they join.
+        let join = *blocks.iter().find(|&&b| ssa.preds_of(b).len() == 2).unwrap();
+        for side in ssa.preds_of(join) {
+            assert!(!doms.dominates(side, join));
+            assert!(doms.dominates(doms.idom(join).unwrap(), side));
+        }
+        // The join post-dominates both sides and the branch, which do not post-dominate it.
+        let postdoms = dominators::post_dominator_tree(ssa);
+        assert_eq!(postdoms.root(), ssa.exit_node());
+        for side in ssa.preds_of(join) {
+            assert!(postdoms.dominates(join, side));
+            assert!(!postdoms.dominates(side, join));
+            assert!(postdoms.dominates(join, doms.idom(join).unwrap()));
+        }
+        assert!(!doms.dominates(entry, NodeIndex::end()));
+        assert!(!doms.is_reachable(NodeIndex::end()));
+
+        assert!(RadecoFunction::default().dominators().root().is_none());
+    }
+
+    #[test]
+    fn test_ssa_dot() {
+        let rfn = esil_function(&["rax,1,+,rbx,=", "rbx,0,==,?{,2,rip,=,}"]);
+        let dot = rfn.ssa_dot();
+        assert!(dot.starts_with("digraph"));
+        assert!(dot.contains("OpAdd"));
+        assert!(dot.contains("\"rax\""));
+        assert!(dot.contains("subgraph cluster_"));
+        for b in rfn.ssa().blocks() {
+            assert!(dot.contains(&format!("subgraph cluster_{} {{", b.index())));
+        }
+    }
+
+    #[test]
+    fn test_stack_arguments() {
+        let sub_reg_f = SubRegisterFile::new(&register_profile());
+        // 32-bit code reading its second and first argument, and the return address
+        let rfn = || esil_function(&["esp,8,+,[4],ecx,=", "esp,4,+,[4],eax,=", "esp,[4],edx,="]);
+
+        let mut cdecl = rfn();
+        ModuleLoader::init_fn_bindings(&mut cdecl, &sub_reg_f, &CallingConvention::stack("A3"));
+        let args = cdecl.bindings()
+            .into_iter()
+            .filter(|vb| vb.btype().is_argument())
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(args.iter().map(|vb| vb.btype()).collect::<Vec<_>>(),
+                   vec![BindingType::StackArgument(0), BindingType::StackArgument(1)]);
+        let ssa = cdecl.ssa();
+        assert!(args.iter().all(|vb| ssa.opcode(vb.idx) == Some(ir::MOpcode::OpLoad)));
+        assert_eq!(cdecl.stack_arguments().iter().map(|&(_, n)| n).collect::<Vec<_>>(),
+                   args.iter().map(|vb| vb.idx).collect::<Vec<_>>());
+        assert!(llanalyzer::detect_calling_convention(&cdecl, &sub_reg_f).stack_args);
+
+        // Register-based conventions do not look at the stack.
+        let mut regs = rfn();
+        ModuleLoader::init_fn_bindings(&mut regs, &sub_reg_f, &CallingConvention::new(&[], "A3"));
+        assert!(regs.bindings().into_iter().all(|vb| !vb.btype().is_argument()));
+    }
+
+    #[test]
+    fn test_wide_return() {
+        let sub_reg_f = SubRegisterFile::new(&register_profile());
+        // rdx:rax of the return value, using aliases of the test profile for the two registers
+        let mut rfn = esil_function(&["1,r10,=", "2,r8,="]);
+        let cc = CallingConvention::new(&["A1"], "A3").wide_ret(&["A4"]);
+        assert_eq!(cc.rets(), vec!["A3", "A4"]);
+        ModuleLoader::init_fn_bindings(&mut rfn, &sub_reg_f, &cc);
+
+        let rets = rfn.bindings()
+            .into_iter()
+            .filter(|vb| vb.btype().is_return())
+            .map(|vb| vb.ridx)
+            .collect::<Vec<_>>();
+        let ridx = |alias| sub_reg_f.register_id_by_alias(alias);
+        assert_eq!(rets, vec![ridx("A3"), ridx("A4")]);
+        let code = rfn.to_pseudocode(&sub_reg_f);
+        assert!(code.lines().any(|l| l.starts_with("    return ") && l.contains(':')),
+                "{}",
+                code);
+
+        assert!(CallingConvention::new(&[], "").wide_ret(&["A4"]).rets() == vec!["A4"]);
+    }
+
+    #[test]
+    fn test_variadic_binding() {
+        let vb = VarBinding::new(BindingType::Variadic(1), None, NodeIndex::end(), None);
+        assert!(vb.btype().is_variadic());
+        assert!(!vb.btype().is_argument());
+        assert!(!vb.btype().is_local());
+        assert!(!BindingType::RegisterArgument(0).is_variadic());
+
+        let sub_reg_f = SubRegisterFile::new(&register_profile());
+        let mut rfn = ssa_function("test_files/tiny_sccp_test_instructions.json");
+        rfn.variadic = true;
+        let cc = CallingConvention::new(&["A1", "A2"], "A3");
+        ModuleLoader::init_fn_bindings(&mut rfn, &sub_reg_f, &cc);
+
+        let btypes = rfn.bindings().into_iter().map(|b| b.btype()).collect::<Vec<_>>();
+        assert_eq!(btypes[..3].to_vec(),
+                   vec![BindingType::RegisterArgument(0),
+                        BindingType::RegisterArgument(1),
+                        BindingType::Variadic(2)]);
+        assert!(rfn.to_pseudocode(&sub_reg_f).contains(", ...)"));
+    }
+
+    #[test]
+    fn test_buffer_source() {
+        // push rbp; mov rbp, rsp; ret
+        let src = BufferSource::new(vec![0x55, 0x48, 0x89, 0xe5, 0xc3], 0x1000, "x86_64");
+        let ops = src.disassemble_n_bytes(5, 0x1000).unwrap();
+        let offsets = ops.iter().map(|op| op.offset.unwrap()).collect::<Vec<_>>();
+        assert_eq!(offsets, vec![0x1000, 0x1001, 0x1004]);
+        assert_eq!(src.disassemble_n_insts(1, 0x1001).unwrap().len(), 1);
+        assert_eq!(src.sections().unwrap().len(), 1);
+        assert!(src.register_profile().is_ok());
+        match src.functions() {
+            Err(SourceErr::NotSupported) => {}
+            _ => panic!("Expected SourceErr::NotSupported"),
+        }
+
+        let src: Rc<Source> = Rc::new(src);
+        let rmod = ModuleLoader::default().source(Rc::clone(&src)).load(src).unwrap();
+        assert!(rmod.function(0x1000).is_some());
+    }
+
+    #[test]
+    fn test_buffer_source_return() {
+        // mov eax, 1; ret
+        let bytes = vec![0xb8, 0x01, 0x00, 0x00, 0x00, 0xc3];
+        let src: Rc<Source> = Rc::new(BufferSource::new(bytes, 0x1000, "x86_64"));
+        let reg_p = src.register_profile().unwrap();
+        let sub_reg_f = SubRegisterFile::new(&reg_p);
+        let rmod = ModuleLoader::default().source(Rc::clone(&src)).load(src).unwrap();
+        let rfn = rmod.function(0x1000).unwrap();
+
+        // The return value is bound through the `SN` alias of the bundled profile.
+        let ret = rfn.bindings().into_iter().find(|vb| vb.btype().is_return());
+        assert_eq!(ret.and_then(|vb| vb.ridx), sub_reg_f.register_id_by_alias("SN"));
+        assert!(sub_reg_f.register_id_by_alias("SN").is_some());
+    }
+
+    #[test]
+    fn test_keep_bytes() {
+        // push rbp; mov rbp, rsp; nop * 11; ret
+        let mut bytes = vec![0x55, 0x48, 0x89, 0xe5];
+        bytes.extend(iter::repeat(0x90).take(11));
+        bytes.push(0xc3);
+        let src: Rc<Source> = Rc::new(BufferSource::new(bytes.clone(), 0x1000, "x86_64"));
+        let loader = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .function_loader(FunctionLoader::default().strategy(&strat_small_fn));
+
+        let rmod = loader.clone().keep_bytes().load(Rc::clone(&src)).unwrap();
+        let rfn = rmod.function(0x1000).unwrap();
+        assert_eq!(rfn.raw_bytes().len() as u64, rfn.size);
+        assert_eq!(rfn.raw_bytes(), bytes.as_slice());
+        assert!(rmod.load_config().keep_bytes);
+
+        let rmod = loader.load(src).unwrap();
+        assert!(rmod.function(0x1000).unwrap().raw_bytes().is_empty());
+    }
+
+    #[test]
+    fn test_only_libs() {
+        let src: Rc<Source> = Rc::new(GoblinSource::open("test_files/file").unwrap());
+        let rmod = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .metadata_only()
+            .load(src)
+            .unwrap();
+        assert_eq!(rmod.libs, vec!["libmagic.so.1", "libc.so.6"]);
+
+        let lib_dir = env::temp_dir().join("radeco_test_only_libs");
+        fs::create_dir_all(&lib_dir).unwrap();
+        for lib in &rmod.libs {
+            File::create(lib_dir.join(lib)).unwrap();
+        }
+        let mut loader = ProjectLoader::default().load_libs().only_libs(&["libc.so.6"]);
+        loader.load_library_path = Some(Cow::from(lib_dir.to_string_lossy().into_owned()));
+        assert_eq!(loader.wanted_libs(&rmod.libs), vec!["libc.so.6"]);
+        assert_eq!(loader.library_paths(&loader.wanted_libs(&rmod.libs)),
+                   vec![lib_dir.join("libc.so.6")]);
+
+        let loader = ProjectLoader::default().load_libs();
+        assert_eq!(loader.wanted_libs(&rmod.libs), vec!["libmagic.so.1", "libc.so.6"]);
+    }
+
+    #[test]
+    fn test_goblin_source() {
+        let src = GoblinSource::open("test_files/file").unwrap();
+        let sections = src.sections().unwrap();
+        let section = |name: &str| {
+            sections.iter().find(|s| s.name.as_ref().map_or(false, |n| n == name)).unwrap()
+        };
+        assert_eq!(sections.len(), 29);
+        assert_eq!(section(".text").vaddr, Some(0x1890));
+        assert_eq!(section(".text").size, Some(0x1502));
+        assert_eq!(section(".text").flags, Some("-r-x".to_owned()));
+        assert_eq!(section(".data").flags, Some("-rw-".to_owned()));
+        assert_eq!(section(".bss").size, Some(0));
+        assert_eq!(section(".bss").vsize, Some(0x70));
+
+        let imports = src.imports().unwrap();
+        assert_eq!(imports.len(), 39);
+        let plt = |name: &str| {
+            imports.iter().find(|i| i.name.as_ref().map_or(false, |n| n == name)).unwrap().plt
+        };
+        assert_eq!(plt("getenv"), Some(0x1610));
+        assert_eq!(plt("free"), Some(0x1620));
+        assert_eq!(src.libraries().unwrap(), vec!["libmagic.so.1", "libc.so.6"]);
+        assert_eq!(src.entrypoint().unwrap()[0].vaddr, Some(0x2640));
+        // push qword [rip + ...], the first entry of the PLT
+        assert_eq!(src.read_bytes(0x1600, 2).unwrap(), vec![0xff, 0x35]);
+        assert!(src.read_bytes(0x205240, 1).is_err());
+        match src.functions() {
+            Err(SourceErr::NotSupported) => {}
+            _ => panic!("Expected SourceErr::NotSupported"),
+        }
+
+        let src: Rc<Source> = Rc::new(src);
+        let rmod = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .metadata_only()
+            .load(src)
+            .unwrap();
+        assert_eq!(rmod.sections.len(), 29);
+        assert!(rmod.imports.contains_key(&0x1620));
+        assert!(rmod.is_executable(0x2640));
+    }
+
+    fn call_info(call_type: &str, source: u64, target: u64) -> LCallInfo {
+        let mut call = LCallInfo::default();
+        call.call_type = Some(call_type.to_owned());
+        call.source = Some(source);
+        call.target = Some(target);
+        call
+    }
+
+    #[test]
+    fn test_callgraph_without_cc() {
+        let mut mock = MockSource::default();
+        for &off in &[0x1000, 0x2000, 0x3000] {
+            let mut info = FunctionInfo::default();
+            info.offset = Some(off);
+            mock.functions.push(info);
+        }
+        mock.functions[0].callrefs = Some(vec![call_info("C", 0x1004, 0x2000),
+                                               call_info("J", 0x1008, 0x3000)]);
+        mock.functions[1].callrefs = Some(vec![call_info("C", 0x2004, 0x3000)]);
+        let src: Rc<Source> = Rc::new(mock);
+        let rmod = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .function_loader(FunctionLoader::default().strategy(&strat_three_fns))
+            .build_callgraph()
+            .load(src)
+            .unwrap();
+
+        let cg = rmod.callgraph();
+        assert_eq!(cg.edge_count(), 2);
+        for rfn in rmod.functions.values() {
+            assert_eq!(cg[rfn.cgid()], rfn.offset);
+            assert!(rfn.bindings().into_iter().next().is_none());
+        }
+        let a = rmod.function(0x1000).unwrap().cgid();
+        let b = rmod.function(0x2000).unwrap().cgid();
+        let c = rmod.function(0x3000).unwrap().cgid();
+        assert_eq!(cg.callees(a).collect::<Vec<_>>(), vec![(0x1004, b)]);
+        assert_eq!(cg.callees(b).collect::<Vec<_>>(), vec![(0x2004, c)]);
+    }
+
+    #[test]
+    fn test_tailcall() {
+        let mut mock = MockSource::default();
+        for &off in &[0x1000, 0x2000, 0x3000] {
+            let mut info = FunctionInfo::default();
+            info.offset = Some(off);
+            mock.functions.push(info);
+        }
+        // 0x1000 tail-jumps to 0x2000 and has a conditional jump to 0x3000. 0x2000 jumps
+        // inside itself and calls 0x3000.
+        mock.functions[0].callrefs = Some(vec![call_info("J", 0x1000, 0x3000),
+                                               call_info("J", 0x1004, 0x2000)]);
+        mock.functions[1].callrefs = Some(vec![call_info("J", 0x2000, 0x2000),
+                                               call_info("C", 0x2004, 0x3000)]);
+        for &(off, ty) in &[(0x1000, "cjmp"), (0x1004, "jmp"), (0x2000, "jmp"), (0x2004, "call")] {
+            let mut op = LOpInfo::default();
+            op.offset = Some(off);
+            op.size = Some(4);
+            op.optype = Some(ty.to_owned());
+            mock.instructions.push(op);
+        }
+        let src: Rc<Source> = Rc::new(mock);
+        let rmod = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .function_loader(FunctionLoader::default().strategy(&strat_three_fns))
+            .build_callgraph()
+            .load(src)
+            .unwrap();
+
+        let cg = rmod.callgraph();
+        let edges = cg.edge_indices()
+            .map(|e| (cg[e].csite, cg[e].is_tailcall))
+            .collect::<HashSet<_>>();
+        assert_eq!(edges, [(0x1004, true), (0x2004, false)].iter().cloned().collect());
+    }
+
+    fn export(name: &str, vaddr: u64) -> LExportInfo {
+        let mut export = LExportInfo::default();
+        export.name = Some(name.to_owned());
+        export.vaddr = Some(vaddr);
+        export
+    }
+
+    #[test]
+    fn test_resolve_imports() {
+        let mut main = RadecoModule::new("main".to_owned());
+        main.imports.insert(0x400, ImportInfo::new_stub(0x400, Cow::from("puts")));
+        main.imports.insert(0x410, ImportInfo::new_stub(0x410, Cow::from("missing")));
+        let mut libc = RadecoModule::new("libc".to_owned());
+        libc.exports = vec![export("puts", 0x1000)];
+        let mut other = RadecoModule::new("other".to_owned());
+        other.exports = vec![export("puts", 0x2000)];
+
+        let mut rp = RadecoProject::new();
+        rp.modules = vec![main, libc, other];
+        rp.resolve_imports();
+
+        let main = rp.nth_module(0).unwrap();
+        assert_eq!(main.imports[&0x400].resolved(), Some((1, 0x1000)));
+        assert_eq!(main.imports[&0x410].resolved(), None);
+    }
+
+    #[test]
+    fn test_iter_resolved_imports() {
+        let mut main = RadecoModule::new("main".to_owned());
+        main.imports.insert(0x410, ImportInfo::new_stub(0x410, Cow::from("missing")));
+        main.imports.insert(0x400, ImportInfo::new_stub(0x400, Cow::from("puts")));
+        let mut libc = RadecoModule::new("libc".to_owned());
+        libc.exports = vec![export("puts", 0x1000)];
+        libc.imports.insert(0x800, ImportInfo::new_stub(0x800, Cow::from("malloc")));
+
+        let mut rp = RadecoProject::new();
+        rp.modules = vec![main, libc];
+        assert!(rp.iter_resolved_imports().all(|(_, _, _, resolved)| resolved.is_none()));
+        rp.resolve_imports();
+
+        assert_eq!(rp.nth_module(0).unwrap().iter_imports().collect::<Vec<_>>(),
+                   vec![(0x400, "puts", Some((1, 0x1000))), (0x410, "missing", None)]);
+        assert_eq!(rp.iter_resolved_imports().collect::<Vec<_>>(),
+                   vec![(0, 0x400, "puts", Some((1, 0x1000))),
+                        (0, 0x410, "missing", None),
+                        (1, 0x800, "malloc", None)]);
+    }
+
+    #[test]
+    fn test_rename_binding() {
+        let mut rfn = RadecoFunction::default();
+        rfn.bindings.0.push(VarBinding::new(BindingType::RegisterArgument(0),
+                                            Some("arg0".to_owned()),
+                                            NodeIndex::new(3),
+                                            None));
+        assert!(rfn.rename_binding(NodeIndex::new(3), "argc"));
+        assert!(!rfn.rename_binding(NodeIndex::new(4), "argv"));
+        let names = rfn.bindings().into_iter().map(|vb| vb.name()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["argc"]);
+    }
+
+    fn strat_small_main(_: Option<&Rc<Source>>, _: &FLResult, _: &RadecoModule) -> FLResult {
+        let mut fl = FLResult::default();
+        fl.functions.insert(0x180, mock_function(0x180, 0x10, "main"));
+        fl.new = 1;
+        fl
+    }
+
+    #[test]
+    fn test_reanalyze_function() {
+        let mut mock = MockSource::default();
+        mock.instructions = load_instructions("test_files/tiny_sccp_test_instructions.json");
+        let src: Rc<Source> = Rc::new(mock);
+        let mut rmod = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .function_loader(FunctionLoader::default().strategy(&strat_small_main))
+            .build_ssa()
+            .load(src).unwrap();
+        assert_eq!(rmod.function(0x180).unwrap().instructions().len(), 2);
+
+        rmod.function_mut(0x180).unwrap().set_size(0x1c);
+        rmod.reanalyze_function(0x180).unwrap();
+        assert_eq!(rmod.function(0x180).unwrap().instructions().len(), 6);
+        assert!(rmod.reanalyze_function(0x200).is_err());
+
+        rmod.source = None;
+        assert!(rmod.reanalyze_function(0x180).is_err());
+    }
+
+    static TEMP_FILES: AtomicUsize = ATOMIC_USIZE_INIT;
+
+    // Create a file in the temporary directory named after `name`, that no other test or test
+    // run uses.
+    fn temp_file(name: &str) -> (PathBuf, File) {
+        loop {
+            let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().subsec_nanos();
+            let n = TEMP_FILES.fetch_add(1, atomic::Ordering::SeqCst);
+            let path = env::temp_dir().join(format!("radeco_test_{}.{:x}{:x}", name, nanos, n));
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(f) => return (path, f),
+                Err(ref e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => panic!("{}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_attach_sources() {
+        let mut mock = MockSource::default();
+        mock.instructions = load_instructions("test_files/tiny_sccp_test_instructions.json");
+        let src: Rc<Source> = Rc::new(mock);
+        let rmod = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .function_loader(FunctionLoader::default().strategy(&strat_small_main))
+            .build_ssa()
+            .load(Rc::clone(&src))
+            .unwrap();
+        let mut rp = RadecoProject::new();
+        rp.modules.push(rmod);
+        let (path, _) = temp_file("attach_sources");
+        rp.save(&path).unwrap();
+
+        let mut loaded = RadecoProject::load_from(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(loaded.nth_module_mut(0).unwrap().reanalyze_function(0x180).is_err());
+        loaded.attach_sources(|rmod| if rmod.functions.contains_key(&0x180) {
+            Some(Rc::clone(&src))
+        } else {
+            None
+        });
+
+        let rmod = loaded.nth_module_mut(0).unwrap();
+        rmod.function_mut(0x180).unwrap().set_size(0x1c);
+        rmod.reanalyze_function(0x180).unwrap();
+        assert_eq!(rmod.function(0x180).unwrap().instructions().len(), 6);
+    }
+
+    #[test]
+    fn test_thumb_mode() {
+        let mut rmod = RadecoModule::default();
+        rmod.symbols = [0x1001, 0x2000]
+            .iter()
+            .map(|&vaddr| {
+                let mut sym = LSymbolInfo::default();
+                sym.vaddr = Some(vaddr);
+                sym
+            })
+            .collect();
+        let mut entry = LEntryInfo::default();
+        entry.vaddr = Some(0x3001);
+        rmod.entrypoint = vec![entry];
+        for &off in &[0x1000, 0x2000, 0x3001, 0x4001] {
+            rmod.functions.insert(off, mock_function(off, 0x10, "f"));
+        }
+
+        ModuleLoader::apply_thumb_mode(&mut rmod);
+
+        let offsets = rmod.functions.keys().cloned().collect::<Vec<_>>();
+        assert_eq!(offsets, vec![0x1000, 0x2000, 0x3000, 0x4000]);
+        assert!(rmod.functions.iter().all(|(&off, rfn)| off == rfn.offset));
+        let thumb = rmod.functions.values().map(|rfn| rfn.is_thumb()).collect::<Vec<_>>();
+        assert_eq!(thumb, vec![true, false, true, true]);
+    }
+
+    #[test]
+    fn test_add_function() {
+        let mut ops = vec![esil_op("1,rax,="), esil_op("rax,rbx,=")];
+        for (i, op) in ops.iter_mut().enumerate() {
+            op.offset = Some(0x5000 + i as u64);
+        }
+        let mut mock = MockSource::default();
+        mock.instructions = ops;
+        let src: Rc<Source> = Rc::new(mock);
+        let mut rmod = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .function_loader(FunctionLoader::default().strategy(&strat_three_fns))
+            .load(src)
+            .unwrap();
+
+        assert!(rmod.add_function(mock_function(0x5000, 2, "added")).is_ok());
+        assert!(rmod.add_function(mock_function(0x1000, 2, "duplicate")).is_err());
+        assert_eq!(rmod.function(0x1000).unwrap().name, "a");
+        assert!(rmod.build_function_ssa(0x6000).is_err());
+
+        rmod.build_function_ssa(0x5000).unwrap();
+        let rfn = rmod.function(0x5000).unwrap();
+        assert_eq!(rfn.instructions().len(), 2);
+        assert!(rfn.entry_block().is_some());
+        assert_eq!(rmod.callgraph[rfn.cgid()], 0x5000);
+    }
+
+    #[test]
+    fn test_remove_function() {
+        let mut rmod = mock_module(&[0x1000, 0x2000, 0x3000], &[(0, 1), (1, 2), (2, 0)]);
+        let removed = rmod.remove_function(0x1000).unwrap();
+        assert_eq!(removed.offset, 0x1000);
+        assert!(rmod.function(0x1000).is_none());
+        assert!(rmod.remove_function(0x1000).is_none());
+
+        assert_eq!(rmod.callgraph.node_count(), 2);
+        assert_eq!(rmod.callgraph.edge_count(), 1);
+        for (&off, rfn) in &rmod.functions {
+            assert_eq!(rmod.callgraph[rfn.cgid()], off);
+        }
+        let b = rmod.function(0x2000).unwrap().cgid();
+        let c = rmod.function(0x3000).unwrap().cgid();
+        assert!(rmod.callgraph.find_edge(b, c).is_some());
+    }
+
+    #[test]
+    fn test_callgraph_json() {
+        let mut rmod = mock_module(&[0x1000, 0x2000], &[(0, 1)]);
+        let mut ifn = ImportInfo::new_stub(0x400, Cow::from("puts"));
+        let inode = rmod.callgraph.add_node(0x400);
+        ifn.rfn.borrow_mut().cgid = inode;
+        rmod.imports.insert(0x400, ifn);
+        let mut cctx = CallContextInfo::default();
+        cctx.csite = 0x2004;
+        let caller = rmod.function(0x2000).unwrap().cgid();
+        rmod.callgraph.add_edge(caller, inode, cctx);
+
+        let json: serde_json::Value = serde_json::from_str(&rmod.callgraph_json()).unwrap();
+        let nodes = json["nodes"].as_array().unwrap();
+        let edges = json["edges"].as_array().unwrap();
+        assert_eq!(nodes.len(), 3);
+        assert_eq!(edges.len(), 2);
+        let import = nodes.iter().find(|n| n["addr"] == 0x400).unwrap();
+        assert_eq!(import["name"], "puts");
+        assert_eq!(import["is_import"], true);
+        assert!(nodes.iter().filter(|n| n["addr"] != 0x400).all(|n| n["is_import"] == false));
+        assert!(edges.iter().any(|e| e["from"] == 0x2000 && e["to"] == 0x400 &&
+                                     e["callsite"] == 0x2004));
+    }
+
+    #[test]
+    fn test_verify_ssa() {
+        let mut rfn = esil_function(&["2,rax,=", "3,rax,+=", "rax,rbx,="]);
+        assert_eq!(rfn.verify_ssa(), Ok(()));
+
+        let add = {
+            let ssa = rfn.ssa();
+            ssa.values()
+                .into_iter()
+                .find(|&n| ssa.opcode(n) == Some(ir::MOpcode::OpAdd))
+                .unwrap()
+        };
+        let entry = rfn.ssa().entry_node().unwrap();
+        // Use a block as an operand, and remove another operand in place.
+        rfn.ssa_mut().op_use(add, 2, entry);
+        let removed = rfn.ssa().operands_of(add)[0];
+        rfn.ssa_mut().g[removed] = ssastorage::NodeData::Removed;
+
+        let errors = rfn.verify_ssa().unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(errors.contains(&SsaError::DanglingOperand(add, entry)));
+        assert!(errors.contains(&SsaError::DanglingOperand(add, removed)));
+    }
+
+    #[test]
+    fn test_verify_ssa_phis() {
+        // if (rdi) { rax = rsi + 1 } else { rax = rsi + 2 }; rbx = rax
+        let mut rfn = esil_function(&["rdi,?{,3,rip,=,}",
+                                      "1,rsi,+,rax,=",
+                                      "4,rip,=",
+                                      "2,rsi,+,rax,=",
+                                      "rax,rbx,="]);
+        assert_eq!(rfn.verify_ssa(), Ok(()));
+
+        let (phi, ops) = {
+            let ssa = rfn.ssa();
+            let phi = ssa.values()
+                .into_iter()
+                .find(|&n| {
+                    let ops = ssa.operands_of(n);
+                    ssa.is_phi(n) && ops.len() == 2 &&
+                    ops.iter().all(|&op| ssa.opcode(op) == Some(ir::MOpcode::OpAdd))
+                })
+                .unwrap();
+            (phi, ssa.operands_of(phi))
+        };
+        // The side of the branch that defined the dropped operand is left without one.
+        let missing = {
+            let ssa = rfn.ssa();
+            let doms = rfn.dominators();
+            let side = ssa.block_for(ops[0]).unwrap();
+            ssa.preds_of(ssa.block_for(phi).unwrap())
+                .into_iter()
+                .find(|&pred| doms.dominates(side, pred))
+                .unwrap()
+        };
+        rfn.ssa_mut().phi_unuse(phi, ops[0]);
+        assert_eq!(rfn.verify_ssa(), Err(vec![SsaError::PhiMissingOperand(phi, missing)]));
+
+        rfn.ssa_mut().phi_unuse(phi, ops[1]);
+        assert_eq!(rfn.verify_ssa(), Err(vec![SsaError::PhiOperands(phi, 2, 0)]));
+
+        // Operations outside of any block are reported, constants are not.
+        let mut rfn = esil_function(&["rax,rbx,="]);
+        let vt = ValueInfo::new_unresolved(ir::WidthSpec::from(64));
+        let add = rfn.ssa_mut().insert_op(ir::MOpcode::OpAdd, vt, None).unwrap();
+        rfn.ssa_mut().insert_const(0x1234).unwrap();
+        assert_eq!(rfn.verify_ssa(), Err(vec![SsaError::NoBlock(add)]));
+    }
+
+    #[test]
+    fn test_propagate_constants() {
+        let mut rfn = esil_function(&["2,rax,=", "3,rax,+=", "rax,rbx,="]);
+        assert!(!exit_constants(&rfn).contains(&5));
+
+        assert!(rfn.propagate_constants() > 0);
+        // Both `rax` and `rbx`, which depends on it, are now the constant.
+        assert_eq!(exit_constants(&rfn).iter().filter(|&&c| c == 5).count(), 2);
+        let ssa = rfn.ssa();
+        assert!(ssa.values().into_iter().all(|n| match ssa.node_data(n) {
+            Ok(NodeData { nt: NodeType::Op(ir::MOpcode::OpAdd), .. }) => false,
+            _ => true,
+        }));
+        assert_eq!(rfn.propagate_constants(), 0);
+    }
+
+    fn count_adds(rfn: &RadecoFunction) -> usize {
+        let ssa = rfn.ssa();
+        ssa.values()
+            .into_iter()
+            .filter(|&n| match ssa.node_data(n) {
+                Ok(NodeData { nt: NodeType::Op(ir::MOpcode::OpAdd), .. }) => true,
+                _ => false,
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_eliminate_dead_code() {
+        // The sum written to `rbx` is overwritten before it is used.
+        let mut rfn = esil_function(&["rax,rcx,+,rbx,=", "rax,rbx,="]);
+        let vt = ValueInfo::new_unresolved(ir::WidthSpec::from(64));
+        let arg = rfn.ssa_mut().insert_comment(vt, "arg".to_owned()).unwrap();
+        rfn.bindings.0.push(VarBinding::new(BindingType::RegisterArgument(0), None, arg, None));
+        assert_eq!(count_adds(&rfn), 1);
+
+        assert!(rfn.eliminate_dead_code() > 0);
+        assert_eq!(count_adds(&rfn), 0);
+        assert!(rfn.ssa().node_data(arg).is_ok());
+        assert_eq!(rfn.eliminate_dead_code(), 0);
+    }
+
+    #[test]
+    fn test_basic_blocks() {
+        let rfn = RadecoFunction::default();
+        assert!(rfn.basic_blocks().is_empty());
+        assert_eq!(rfn.entry_block(), None);
+        assert_eq!(rfn.exit_block(), None);
+
+        let rfn = ssa_function("test_files/tiny_sccp_test_instructions.json");
+        let blocks = rfn.basic_blocks();
+        assert!(blocks.contains(&rfn.entry_block().unwrap()));
+        assert!(blocks.contains(&rfn.exit_block().unwrap()));
+    }
+
+    #[test]
+    fn test_refine_arguments() {
+        let sub_reg_f = SubRegisterFile::new(&register_profile());
+        let mut rfn = esil_function(&["rsi,rdx,+,rax,="]);
+        let cc = CallingConvention::new(&["A1", "A2", "A3"], "A4");
+        ModuleLoader::init_fn_bindings(&mut rfn, &sub_reg_f, &cc);
+        assert_eq!(rfn.bindings().into_iter().filter(|vb| vb.btype().is_argument()).count(), 3);
+
+        assert_eq!(rfn.refine_arguments(), 1);
+        let args = rfn.bindings()
+            .into_iter()
+            .filter(|vb| vb.btype().is_argument())
+            .map(|vb| vb.btype())
+            .collect::<Vec<_>>();
+        assert_eq!(args, vec![BindingType::RegisterArgument(0), BindingType::RegisterArgument(1)]);
+        assert!(rfn.bindings().into_iter().any(|vb| vb.btype().is_return()));
+    }
+
+    #[test]
+    fn test_set_size() {
+        let mock = Rc::new(MockSource::default());
+        let src: Rc<Source> = mock.clone();
+        let rmod = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .function_loader(FunctionLoader::default()
+                                 .strategy(&strat_small_fn)
+                                 .strategy(&strat_fix_size))
+            .load(src).unwrap();
+
+        assert_eq!(rmod.function(0x1000).map(|f| f.size()), Some(0x20));
+        assert_eq!(*mock.disassembled.borrow(), vec![(0x20, 0x1000)]);
+    }
+
+    #[test]
+    fn test_metadata_only() {
+        let mut mock = MockSource::default();
+        let mut section = LSectionInfo::default();
+        section.vaddr = Some(0x1000);
+        section.size = Some(0x100);
+        section.flags = Some("-r-x".to_owned());
+        mock.sections = vec![section];
+        let mut sym = LSymbolInfo::default();
+        sym.vaddr = Some(0x1000);
+        mock.symbols = vec![sym];
+        let mut entry = LEntryInfo::default();
+        entry.vaddr = Some(0x1000);
+        mock.entrypoint = vec![entry];
+        let mock = Rc::new(mock);
+        let src: Rc<Source> = mock.clone();
+        let rmod = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .function_loader(FunctionLoader::default().strategy(&strat_three_fns))
+            .build_ssa()
+            .build_callgraph()
+            .metadata_only()
+            .load(src)
+            .unwrap();
+
+        assert_eq!(rmod.sections.len(), 1);
+        assert_eq!(rmod.symbols.len(), 1);
+        assert_eq!(rmod.entrypoint.len(), 1);
+        assert!(rmod.is_executable(0x1000));
+        assert!(rmod.functions.is_empty());
+        assert!(rmod.function_containing(0x1000).is_none());
+        assert!(rmod.dead_functions().is_empty());
+        assert!(mock.disassembled.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_import_signatures() {
+        let mut mock = MockSource::default();
+        mock.imports = [(0x400, "printf"), (0x410, "puts"), (0x420, "log_msg")]
+            .iter()
+            .map(|&(plt, name)| {
+                let mut ii = LImportInfo::default();
+                ii.plt = Some(plt);
+                ii.name = Some(name.to_owned());
+                ii
+            })
+            .collect();
+        let src: Rc<Source> = Rc::new(mock);
+        let mut sigs = HashMap::new();
+        sigs.insert("log_msg".to_owned(), ImportSignature::new(2, true));
+        let rmod = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .function_loader(FunctionLoader::default().strategy(&strat_three_fns))
+            .build_ssa()
+            .build_callgraph()
+            .assume_cc()
+            .calling_convention(CallingConvention::new(&["A1", "A2", "A3"], "A4"))
+            .stub_imports()
+            .import_signatures(sigs)
+            .load(src)
+            .unwrap();
+
+        let nargs = |plt| {
+            rmod.imports[&plt].rfn.borrow().bindings().into_iter()
+                .filter(|vb| vb.btype.is_argument())
+                .count()
+        };
+        assert!(rmod.imports[&0x400].rfn.borrow().is_variadic());
+        assert!(!rmod.imports[&0x410].rfn.borrow().is_variadic());
+        assert!(rmod.imports[&0x420].rfn.borrow().is_variadic());
+        assert_eq!(nargs(0x400), 1);
+        assert_eq!(nargs(0x410), 1);
+        assert_eq!(nargs(0x420), 2);
+    }
+
+    #[test]
+    fn test_load_timed() {
+        let mut mock = MockSource::default();
+        mock.instructions = load_instructions("test_files/tiny_sccp_test_instructions.json");
+        let src: Rc<Source> = Rc::new(mock);
+        let (rmod, timings) = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .function_loader(FunctionLoader::default().strategy(&strat_small_main))
+            .build_ssa()
+            .load_timed(src)
+            .unwrap();
+
+        assert!(rmod.function(0x180).is_some());
+        let zero = Duration::new(0, 0);
+        assert!(timings.metadata > zero);
+        assert!(timings.functions > zero);
+        assert!(timings.disassembly > zero);
+        assert!(timings.ssa > zero);
+    }
+
+    #[test]
+    fn test_parallel_with() {
+        let src: Rc<Source> = Rc::new(MockSource::default());
+        let rmod = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .function_loader(FunctionLoader::default().strategy(&strat_three_fns))
+            .build_ssa()
+            .parallel_with(1)
+            .load(src)
+            .unwrap();
+
+        assert_eq!(rmod.functions.len(), 3);
+        assert!(rmod.failed_ssa().is_empty());
+        assert!(rmod.functions.values().all(|rfn| rfn.ssa().entry_node().is_some()));
+    }
+
+    #[test]
+    fn test_max_fn_bytes() {
+        let mock = Rc::new(MockSource::default());
+        let src: Rc<Source> = mock.clone();
+        let rmod = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .function_loader(FunctionLoader::default().strategy(&strat_three_fns))
+            .filter_range(0x1000, 0x2000)
+            .max_fn_bytes(0x8)
+            .load(src)
+            .unwrap();
+
+        assert!(rmod.function(0x1000).unwrap().truncated());
+        assert_eq!(rmod.function(0x1000).unwrap().size(), 0x10);
+        assert_eq!(*mock.disassembled.borrow(), vec![(0x8, 0x1000)]);
+    }
+
+    #[test]
+    fn test_to_pseudocode() {
+        let sub_reg_f = SubRegisterFile::new(&register_profile());
+        let mut rfn = esil_function(&["rsi,rdx,+,r10,=", "0,[8],rax,="]);
+        rfn.name = Cow::from("add");
+        let cc = CallingConvention::new(&["A1", "A2"], "A3");
+        ModuleLoader::init_fn_bindings(&mut rfn, &sub_reg_f, &cc);
+        let first = rfn.bindings().into_iter().next().unwrap().idx;
+        assert!(rfn.rename_binding(first, "x"));
+
+        let code = rfn.to_pseudocode(&sub_reg_f);
+        assert!(code.starts_with("add(x, rdx) {\n"), "{}", code);
+        assert!(code.contains(" = x + rdx;\n") || code.contains(" = rdx + x;\n"),
+                "{}",
+                code);
+        assert!(code.contains(" = *(0x0);\n"), "{}", code);
+        assert!(code.contains("    return t"), "{}", code);
+        assert!(code.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_noreturn() {
+        let sub_reg_f = SubRegisterFile::new(&register_profile());
+        let cc = CallingConvention::new(&["A1", "A2"], "A3");
+
+        // Loops forever between 0x1 and 0x2
+        let rmod = RadecoModule::default();
+        let mut rfn = esil_function(&["1,rax,=", "rsi,r10,=", "0x1,rip,="]);
+        assert!(rfn.is_noreturn());
+        assert!(rmod.is_function_noreturn(&rfn));
+        ModuleLoader::init_fn_bindings(&mut rfn, &sub_reg_f, &cc);
+        assert_eq!(rfn.bindings().into_iter().filter(|vb| vb.btype().is_argument()).count(), 2);
+        assert!(!rfn.bindings().into_iter().any(|vb| vb.btype().is_return()));
+
+        let mut call = esil_op("0x3000,rip,=");
+        call.optype = Some("call".to_owned());
+        call.opcode = Some("call sym.imp.exit".to_owned());
+        let exits = ops_function(vec![esil_op("0,rdi,="), call]);
+        assert!(exits.is_noreturn());
+        assert!(rmod.is_function_noreturn(&exits));
+
+        assert!(!esil_function(&["1,rax,="]).is_noreturn());
+        assert!(!calling_function(0x3000, &[]).is_noreturn());
+        assert!(!rmod.is_function_noreturn(&calling_function(0x3000, &[])));
+    }
+
+    #[test]
+    fn test_noreturn_functions() {
+        let mut sigs = HashMap::new();
+        sigs.insert("fatal".to_owned(), ImportSignature::new(1, false).noreturn());
+        sigs.insert("warn".to_owned(), ImportSignature::new(1, false));
+        let src: Rc<Source> = Rc::new(MockSource::default());
+        let rmod = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .import_signatures(sigs)
+            .noreturn_functions(&["die", "panic_impl"])
+            .load(src)
+            .unwrap();
+
+        for name in &["die", "sym.imp.panic_impl", "fatal", "exit", "sym.imp.abort"] {
+            assert!(rmod.is_noreturn_import(name), "{}", name);
+        }
+        assert!(!rmod.is_noreturn_import("warn"));
+        assert!(!rmod.is_noreturn_import("puts"));
+        assert!(!RadecoModule::default().is_noreturn_import("die"));
+
+        let sigs = rmod.noreturn_signatures();
+        assert!(sigs["die"].noreturn);
+        assert!(sigs["exit"].noreturn);
+        assert!(!sigs["puts"].noreturn);
+    }
+
+    #[test]
+    fn test_noreturn_custom() {
+        let die = || {
+            let mut call = esil_op("0x3000,rip,=");
+            call.optype = Some("call".to_owned());
+            call.opcode = Some("call sym.imp.die".to_owned());
+            call
+        };
+        // The branch on rsi after the call to `die` is dead if `die` does not return.
+        let ops = || {
+            vec![esil_op("rdi,?{,4,rip,=,}"),
+                 die(),
+                 esil_op("rsi,?{,4,rip,=,}"),
+                 esil_op("1,rax,="),
+                 esil_op("rax,rbx,=")]
+        };
+        let mut mock = MockSource::default();
+        mock.instructions = ops();
+        for (i, op) in mock.instructions.iter_mut().enumerate() {
+            op.offset = Some(i as u64);
+        }
+        let mut info = FunctionInfo::default();
+        info.name = Some("f".to_owned());
+        info.offset = Some(0);
+        info.size = Some(5);
+        mock.functions.push(info);
+        let src: Rc<Source> = Rc::new(mock);
+        let rmod = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .noreturn_functions(&["die"])
+            .build_ssa()
+            .load(src)
+            .unwrap();
+        let plain = RadecoModule::default();
+
+        let ends_in_die = ops_function(vec![esil_op("0,rdi,="), die()]);
+        assert!(rmod.is_function_noreturn(&ends_in_die));
+        assert!(!plain.is_function_noreturn(&ends_in_die));
+        // `die` is not one of the built-in functions that do not return.
+        assert!(!ends_in_die.is_noreturn());
+
+        // Pruned while loading
+        let nblocks = ops_function(ops()).basic_blocks().len();
+        assert_eq!(rmod.function(0).unwrap().basic_blocks().len(), nblocks - 2);
+
+        let mut rfn = ops_function(ops());
+        assert_eq!(llanalyzer::prune_after_noreturn_with(&mut rfn, &plain.noreturn_signatures()),
+                   0);
+        assert_eq!(llanalyzer::prune_after_noreturn_with(&mut rfn, &rmod.noreturn_signatures()),
+                   2);
+    }
+
+    #[test]
+    fn test_prune_after_noreturn() {
+        let abort = || {
+            let mut call = esil_op("0x3000,rip,=");
+            call.optype = Some("call".to_owned());
+            call.opcode = Some("call sym.imp.abort".to_owned());
+            call
+        };
+        // The branch on rsi after the call to `abort` is dead.
+        let ops = || {
+            vec![esil_op("rdi,?{,4,rip,=,}"),
+                 abort(),
+                 esil_op("rsi,?{,4,rip,=,}"),
+                 esil_op("1,rax,="),
+                 esil_op("rax,rbx,=")]
+        };
+        let mut rfn = ops_function(ops());
+        let nblocks = rfn.basic_blocks().len();
+        assert_eq!(llanalyzer::prune_after_noreturn(&mut rfn), 2);
+        assert_eq!(rfn.basic_blocks().len(), nblocks - 2);
+        let ssa = rfn.ssa();
+        let caller = ssa.blocks()
+            .into_iter()
+            .find(|&b| {
+                ssa.exprs_in(b).into_iter().any(|e| ssa.opcode(e) == Some(ir::MOpcode::OpCall))
+            })
+            .unwrap();
+        assert!(ssa.succs_of(caller).is_empty());
+        assert!(ssa.selector_in(caller).is_none());
+        assert!(!ssa.preds_of(ssa.exit_node().unwrap()).is_empty());
+
+        // Only the imports marked as `noreturn` are pruned.
+        let mut rfn = ops_function(ops());
+        let mut sigs = HashMap::new();
+        sigs.insert("abort".to_owned(), ImportSignature::new(0, false));
+        assert_eq!(llanalyzer::prune_after_noreturn_with(&mut rfn, &sigs), 0);
+        assert_eq!(rfn.basic_blocks().len(), nblocks);
+    }
+
+    #[test]
+    fn test_detect_calling_convention() {
+        // Unlike the test profile, the bundled one has an `A0` alias.
+        let reg_p = BufferSource::new(Vec::new(), 0, "x86_64").register_profile().unwrap();
+        let sub_reg_f = SubRegisterFile::new(&reg_p);
+        let mut rfn = esil_function(&["rdi,rsi,+,rax,=", "rcx,rdx,="]);
+
+        let cc = llanalyzer::detect_calling_convention(&rfn, &sub_reg_f);
+        assert_eq!(cc.args, vec!["A0".to_owned(), "A1".to_owned()]);
+        assert_eq!(cc.ret, "SN");
+        ModuleLoader::init_fn_bindings(&mut rfn, &sub_reg_f, &cc);
+        assert_eq!(rfn.bindings().into_iter().filter(|vb| vb.btype().is_argument()).count(), 2);
+
+        let cc = llanalyzer::detect_calling_convention(&esil_function(&["1,rax,="]), &sub_reg_f);
+        assert!(cc.args.is_empty());
+    }
+
+    #[test]
+    fn test_failed_ssa() {
+        let mut ok = esil_op("1,rax,=");
+        ok.offset = Some(0x1000);
+        // As reported by r2 for bytes that cannot be disassembled
+        let mut invalid = LOpInfo::default();
+        invalid.offset = Some(0x2000);
+        invalid.size = Some(1);
+        invalid.esil = Some(String::new());
+        invalid.optype = Some("invalid".to_owned());
+        invalid.bytes = Some("ff".to_owned());
+
+        let mut mock = MockSource::default();
+        mock.instructions = vec![ok, invalid];
+        let src: Rc<Source> = Rc::new(mock);
+        let rmod = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .function_loader(FunctionLoader::default().strategy(&strat_three_fns))
+            .filter_range(0x1000, 0x3000)
+            .build_ssa()
+            .build_callgraph()
+            .assume_cc()
+            .calling_convention(CallingConvention::new(&["A1", "A2"], "A3"))
+            .load(src)
+            .unwrap();
+
+        assert_eq!(rmod.failed_ssa(), &[0x2000]);
+        assert!(rmod.function(0x1000).unwrap().bindings().into_iter().count() > 0);
+        assert_eq!(rmod.function(0x2000).unwrap().bindings().into_iter().count(), 0);
+    }
+
+    #[test]
+    fn test_failed_import_ssa() {
+        // Stub left without an entry, as when its SSA construction fails
+        let stub = ImportInfo::new_stub(0x400, Cow::from("exit"));
+        let sub_reg_f = SubRegisterFile::new(&register_profile());
+        let cc = CallingConvention::new(&["A0", "A1"], "SN");
+        ModuleLoader::init_fn_bindings(&mut stub.rfn.borrow_mut(), &sub_reg_f, &cc);
+        assert!(stub.rfn.borrow().bindings().is_empty());
+    }
+
+    static PROGRESS_CALLS: AtomicUsize = ATOMIC_USIZE_INIT;
+    static PROGRESS_DONE: AtomicUsize = ATOMIC_USIZE_INIT;
+
+    fn count_progress(done: usize, total: usize) {
+        assert_eq!(total, 3);
+        PROGRESS_CALLS.fetch_add(1, atomic::Ordering::SeqCst);
+        PROGRESS_DONE.store(done, atomic::Ordering::SeqCst);
+    }
+
+    #[test]
+    fn test_load_streaming() {
+        let mut events = ProjectLoader::default()
+            .path("./test_files/load_libs/fixture")
+            .module_loader(ModuleLoader::default().build_ssa())
+            .load_streaming()
+            .iter()
+            .collect::<Vec<_>>();
+        let rp = match events.pop() {
+            Some(LoadEvent::Done(loaded)) => loaded.into_project(),
+            _ => panic!("Expected LoadEvent::Done"),
+        };
+        assert!(match events.pop() {
+            Some(LoadEvent::ModuleLoaded(ref name)) => name == "fixture",
+            _ => false,
+        });
+        // Every other event is for a function of the module, which comes back with its source.
+        let rmod = rp.nth_module(0).unwrap();
+        assert_eq!(rp.iter().count(), 1);
+        assert!(!events.is_empty());
+        for event in &events {
+            match *event {
+                LoadEvent::FunctionAnalyzed(off) => assert!(rmod.functions.contains_key(&off)),
+                _ => panic!("Expected LoadEvent::FunctionAnalyzed"),
+            }
+        }
+        assert!(rmod.source.is_some());
+
+        // Sources set up front cannot be sent to the loading thread.
+        let src: Rc<Source> = Rc::new(MockSource::default());
+        let events = ProjectLoader::default().source(src).load_streaming();
+        let events = events.iter().collect::<Vec<_>>();
+        assert_eq!(events.len(), 1);
+        assert!(match events[0] {
+            LoadEvent::Failed(_) => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn test_on_progress() {
+        let src: Rc<Source> = Rc::new(MockSource::default());
+        ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .function_loader(FunctionLoader::default().strategy(&strat_three_fns))
+            .build_ssa()
+            .on_progress(count_progress)
+            .load(src)
+            .unwrap();
+
+        assert_eq!(PROGRESS_CALLS.load(atomic::Ordering::SeqCst), 3);
+        assert_eq!(PROGRESS_DONE.load(atomic::Ordering::SeqCst), 3);
+    }
+
+    // Function with a single call to `target`, followed by the instructions in `esil`
+    fn calling_function(target: u64, esil: &[&str]) -> RadecoFunction {
+        let mut call = esil_op(&format!("{:#x},rip,=", target));
+        call.optype = Some("call".to_owned());
+        ops_function(iter::once(call).chain(esil.iter().map(|e| esil_op(e))).collect())
+    }
+
+    fn call_node(rfn: &RadecoFunction) -> NodeIndex {
+        let ssa = rfn.ssa();
+        ssa.values()
+            .into_iter()
+            .find(|&n| match ssa.node_data(n) {
+                Ok(NodeData { nt: NodeType::Op(ir::MOpcode::OpCall), .. }) => true,
+                _ => false,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_callsites() {
+        let call = |esil: &str, optype: &str| {
+            let mut op = esil_op(esil);
+            op.optype = Some(optype.to_owned());
+            op
+        };
+        let rfn = ops_function(vec![call("0x2000,rip,=", "call"),
+                                    call("0x3000,rip,=", "call"),
+                                    call("rax,rip,=", "ucall")]);
+
+        let csites = rfn.callsites();
+        assert_eq!(csites.iter().map(|&(addr, _)| addr).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert!(csites.iter().all(|&(_, n)| rfn.ssa().opcode(n) == Some(ir::MOpcode::OpCall)));
+    }
+
+    // Function returning 1 in `rax`, with a return binding for it.
+    fn returning_function() -> RadecoFunction {
+        let sub_reg_f = SubRegisterFile::new(&register_profile());
+        let mut rfn = esil_function(&["1,rax,="]);
+        let ridx = sub_reg_f.register_id_by_name("rax");
+        let ret = {
+            let ssa = rfn.ssa();
+            let rs = ssa.registers_in(ssa.exit_node().unwrap()).unwrap();
+            ssa.operands_of(rs)[ridx.unwrap() as usize]
+        };
+        rfn.bindings.0.push(VarBinding::new(BindingType::Return, None, ret, ridx));
+        rfn
+    }
+
+    #[test]
+    fn test_return_liveness() {
+        // 0x1000 overwrites the value returned by its call, 0x2000 returns it as is.
+        let mut functions = vec![(0x1000, calling_function(0x3000, &["0,rax,="])),
+                                 (0x2000, calling_function(0x4000, &[])),
+                                 (0x3000, returning_function()),
+                                 (0x4000, returning_function()),
+                                 (0x5000, returning_function())];
+        let mut rmod = RadecoModule::default();
+        for &mut (off, ref mut rfn) in &mut functions {
+            rfn.offset = off;
+            rfn.cgid = rmod.callgraph.add_node(off);
+        }
+        for &(caller, callee) in &[(0, 2), (1, 3), (0, 3)] {
+            let mut cctx = CallContextInfo::default();
+            cctx.csite_node = call_node(&functions[caller].1);
+            rmod.callgraph.add_edge(functions[caller].1.cgid, functions[callee].1.cgid, cctx);
+        }
+        rmod.functions = functions.into_iter().collect();
+
+        llanalyzer::analyze_return_liveness(&mut rmod);
+        assert_eq!(rmod.function(0x1000).unwrap().is_return_live(), None);
+        assert_eq!(rmod.function(0x3000).unwrap().is_return_live(), Some(false));
+        assert_eq!(rmod.function(0x4000).unwrap().is_return_live(), Some(true));
+        // No known callers
+        assert_eq!(rmod.function(0x5000).unwrap().is_return_live(), Some(true));
+    }
+
+    // Module with functions at `offsets` and call edges between them given as
+    // (caller, callee) index pairs.
+    fn mock_module(offsets: &[u64], calls: &[(usize, usize)]) -> RadecoModule {
+        let mut rmod = RadecoModule::default();
+        let nodes = offsets.iter().map(|&off| rmod.callgraph.add_node(off)).collect::<Vec<_>>();
+        for (&off, &nidx) in offsets.iter().zip(nodes.iter()) {
+            let mut rfn = mock_function(off, 0x10, &format!("fcn.{:08x}", off));
+            rfn.cgid = nidx;
+            rmod.functions.insert(off, rfn);
+        }
+        for &(from, to) in calls {
+            let mut cctx = CallContextInfo::default();
+            cctx.csite = offsets[from] + 4;
+            rmod.callgraph.add_edge(nodes[from], nodes[to], cctx);
+        }
+        rmod
+    }
+
+    #[test]
+    fn test_resolve_indirect_calls() {
+        let mut rmod = mock_module(&[0x1000, 0x2000], &[]);
+        let mut text = LSectionInfo::default();
+        text.vaddr = Some(0x1000);
+        text.size = Some(0x2000);
+        text.flags = Some("-r-x".to_owned());
+        rmod.sections = Arc::new(vec![text]);
+
+        let call = |reg: &str| {
+            let mut op = esil_op(&format!("{},rip,=", reg));
+            op.optype = Some("ucall".to_owned());
+            op.opcode = Some(format!("call {}", reg));
+            op
+        };
+        // Calls to a function through `rax`, and to an address outside of code through `rbx`.
+        let mut rfn = ops_function(vec![esil_op("0x2000,rax,="),
+                                        call("rax"),
+                                        esil_op("0x5000,rbx,="),
+                                        call("rbx")]);
+        rfn.offset = 0x1000;
+        rfn.cgid = rmod.functions[&0x1000].cgid;
+        rmod.functions.insert(0x1000, rfn);
+
+        llanalyzer::resolve_indirect_calls(&mut rmod);
+        let caller = rmod.functions[&0x1000].cgid();
+        let callees = rmod.callgraph
+            .edges_directed(caller, Direction::Outgoing)
+            .map(|e| (rmod.callgraph[e.target()], e.weight().csite, e.weight().resolved_indirect))
+            .collect::<Vec<_>>();
+        assert_eq!(callees, vec![(0x2000, 1, true)]);
+
+        // Resolving again does not duplicate the edge.
+        llanalyzer::resolve_indirect_calls(&mut rmod);
+        assert_eq!(rmod.callgraph.edge_count(), 1);
+    }
+
+    #[test]
+    fn test_self_recursive() {
+        let mut rmod = mock_module(&[0x100, 0x200], &[(0, 0), (0, 1)]);
+        ModuleLoader::mark_recursive(&mut rmod);
+        assert_eq!(rmod.function(0x100).unwrap().is_recursive(), Some(true));
+        assert_eq!(rmod.function(0x200).unwrap().is_recursive(), Some(false));
+    }
+
+    #[test]
+    fn test_mutually_recursive() {
+        let mut rmod = mock_module(&[0x100, 0x200, 0x300], &[(0, 1), (1, 2), (2, 1)]);
+        ModuleLoader::mark_recursive(&mut rmod);
+        assert_eq!(rmod.function(0x100).unwrap().is_recursive(), Some(false));
+        assert_eq!(rmod.function(0x200).unwrap().is_recursive(), Some(true));
+        assert_eq!(rmod.function(0x300).unwrap().is_recursive(), Some(true));
+    }
+
+    #[test]
+    fn test_load_locals() {
+        let local = |name: &str, base: &str, offset: i64| {
+            let mut local = LVarInfo::default();
+            local.name = Some(name.to_owned());
+            local.reference = Some(LVarRef {
+                base: Some(base.to_owned()),
+                offset: Some(offset),
+            });
+            local
+        };
+
+        let mut with_locals = FunctionInfo::default();
+        with_locals.name = Some("main".to_owned());
+        with_locals.offset = Some(0x1000);
+        with_locals.size = Some(0x10);
+        // Without the SSA the frame size is unknown, so `rsp` based locals cannot be placed.
+        with_locals.locals = Some(vec![local("local_8h", "rbp", -8),
+                                       local("arg_10h", "rbp", 0x10),
+                                       local("var_8h", "rsp", 8),
+                                       local("bogus", "rbp", i64::min_value())]);
+
+        let mut without_locals = FunctionInfo::default();
+        without_locals.name = Some("helper".to_owned());
+        without_locals.offset = Some(0x2000);
+        without_locals.size = Some(0x10);
+
+        let mut mock = MockSource::default();
+        mock.functions = vec![with_locals, without_locals];
+        let src: Rc<Source> = Rc::new(mock);
+        let rmod = ModuleLoader::default()
+            .source(Rc::clone(&src))
+            .load_locals()
+            .load(src)
+            .unwrap();
+
+        let bindings = rmod.function(0x1000).unwrap().bindings().into_iter().collect::<Vec<_>>();
+        assert_eq!(bindings.len(), 2);
+        // `rbp` is 8 bytes below the stack pointer at entry.
+        assert_eq!(bindings[0].btype(), BindingType::StackLocal(16));
+        assert_eq!(bindings[0].index(), NodeIndex::end());
+        assert_eq!(bindings[1].btype(), BindingType::StackArgument(0));
+        assert_eq!(rmod.function(0x2000).unwrap().bindings().into_iter().count(), 0);
+    }
+
+    #[test]
+    fn test_validate_arch() {
+        let mut rp = RadecoProject::new();
+        rp.reginfo = Arc::new(SubRegisterFile::new(&register_profile()));
+        let mut main = mock_module(&[0x1000], &[]);
+        main.source = Some(Rc::new(MockSource::default()));
+        let mut lib = mock_module(&[0x2000], &[]);
+        lib.source = Some(Rc::new(BufferSource::new(Vec::new(), 0, "x86_64")));
+        rp.modules = vec![main, RadecoModule::default()];
+        assert!(rp.validate_arch().is_ok());
+
+        rp.modules.push(lib);
+        assert_eq!(rp.validate_arch().unwrap_err().module, 2);
+    }
+
+    // Function at `offset` copying `reg` to `rax`, calling `target` and looping back to the
+    // start while `zf` is not set.
+    fn hashed_function(offset: u64, reg: &str, target: u64) -> RadecoFunction {
+        let mut call = esil_op(&format!("{:#x},rip,=", target));
+        call.optype = Some("call".to_owned());
+        call.jump = Some(target);
+        let mut jump = esil_op(&format!("zf,!,?{{,{:#x},rip,=,}}", offset));
+        jump.jump = Some(offset);
+        let mut rfn = mock_function(offset, 3, "f");
+        rfn.instructions = vec![esil_op(&format!("{},rax,=", reg)), call, jump];
+        for (i, op) in rfn.instructions.iter_mut().enumerate() {
+            op.offset = Some(offset + i as u64);
+        }
+        rfn
+    }
+
+    #[test]
+    fn test_find_duplicates() {
+        let a = hashed_function(0x1000, "rdi", 0x5000);
+        let b = hashed_function(0x8000, "rsi", 0x9000);
+        let c = hashed_function(0x2000, "0x10", 0x5000);
+        let rmod = RadecoModule::default();
+        let regfile = SubRegisterFile::new(&register_profile());
+        assert_eq!(a.signature_hash(), b.signature_hash());
+        assert!(a.signature_hash() != c.signature_hash());
+        assert_eq!(a.signature_hash_with(&rmod, &regfile), b.signature_hash_with(&rmod, &regfile));
+        assert!(a.signature_hash_with(&rmod, &regfile) != c.signature_hash_with(&rmod, &regfile));
+
+        let mut rp = RadecoProject::new();
+        rp.reginfo = Arc::new(regfile);
+        rp.modules = vec![RadecoModule::default(), RadecoModule::default()];
+        rp.modules[0].functions.insert(0x1000, a);
+        rp.modules[0].functions.insert(0x2000, c);
+        rp.modules[1].functions.insert(0x8000, b);
+        rp.modules[1].functions.insert(0x9000, mock_function(0x9000, 0, "empty"));
+        rp.modules[1].functions.insert(0xa000, mock_function(0xa000, 0, "empty"));
+        assert_eq!(rp.find_duplicates(), vec![vec![(0, 0x1000), (1, 0x8000)]]);
+    }
+
+    #[test]
+    fn test_signature_hash_data() {
+        // Copies of a function loading from `.data`, in modules loaded at different bases
+        let module = |base: u64| {
+            let mut data = LSectionInfo::default();
+            data.vaddr = Some(base + 0x1000);
+            data.size = Some(0x100);
+            data.flags = Some("-rw-".to_owned());
+            let mut rmod = RadecoModule::default();
+            rmod.sections = Arc::new(vec![data]);
+            let load = format!("{:#x},[8],rax,=", base + 0x1010);
+            let mut rfn = esil_function(&[load.as_str(), "0x10,rax,+="]);
+            rfn.offset = base;
+            rfn.size = 2;
+            (rmod, rfn)
+        };
+        let regfile = SubRegisterFile::new(&register_profile());
+        let (amod, a) = module(0x400000);
+        let (bmod, b) = module(0x800000);
+        assert_eq!(a.signature_hash_with(&amod, &regfile), b.signature_hash_with(&bmod, &regfile));
+        // Without the module, the pointers into `.data` are kept.
+        assert!(a.signature_hash() != b.signature_hash());
+
+        // Without ESIL, mnemonics are kept while registers are still renamed.
+        let opcodes = |ops: &[&str]| {
+            let mut rfn = RadecoFunction::default();
+            rfn.instructions = ops.iter()
+                .map(|op| {
+                    let mut op_info = LOpInfo::default();
+                    op_info.opcode = Some(op.to_string());
+                    op_info
+                })
+                .collect();
+            rfn.signature_hash_with(&amod, &regfile)
+        };
+        assert_eq!(opcodes(&["mov rax, rbx"]), opcodes(&["mov rcx, rdx"]));
+        assert!(opcodes(&["mov rax, rbx"]) != opcodes(&["add rax, rbx"]));
+    }
+
+    #[test]
+    fn test_all_functions() {
+        let mut rp = RadecoProject::new();
+        rp.modules = vec![mock_module(&[0x1000, 0x2000], &[]),
+                          RadecoModule::default(),
+                          mock_module(&[0x3000], &[])];
+        let all = rp.all_functions().map(|(m, off, _)| (m, off)).collect::<Vec<_>>();
+        assert_eq!(all, vec![(0, 0x1000), (0, 0x2000), (2, 0x3000)]);
+
+        for (_, _, rfn) in rp.all_functions_mut() {
+            rfn.set_size(0x20);
+        }
+        assert!(rp.all_functions().all(|(_, _, rfn)| rfn.size() == 0x20));
+    }
+
+    #[derive(Default)]
+    struct Counter {
+        modules: usize,
+        functions: usize,
+        bindings: usize,
+    }
+
+    impl ProjectVisitor for Counter {
+        fn visit_module(&mut self, _: usize, _: &RadecoModule) {
+            self.modules += 1;
+        }
+
+        fn visit_function(&mut self, _: usize, _: &RadecoFunction) {
+            self.functions += 1;
+        }
+
+        fn visit_binding(&mut self, _: usize, _: &RadecoFunction, _: &VarBinding) {
+            self.bindings += 1;
+        }
+    }
+
+    struct Renamer;
+
+    impl ProjectVisitorMut for Renamer {
+        fn visit_binding(&mut self, midx: usize, vb: &mut VarBinding) {
+            vb.set_name(format!("m{}", midx));
+        }
+    }
+
+    #[test]
+    fn test_project_visitor() {
+        let mut rp = RadecoProject::new();
+        rp.modules = vec![mock_module(&[0x1000, 0x2000], &[]), mock_module(&[0x3000], &[])];
+        for (_, _, rfn) in rp.all_functions_mut() {
+            rfn.bindings.0.push(VarBinding::new(BindingType::Return, None, NodeIndex::end(), None));
+        }
+        rp.modules[1]
+            .functions
+            .get_mut(&0x3000)
+            .unwrap()
+            .bindings
+            .0
+            .push(VarBinding::new(BindingType::RegisterArgument(0), None, NodeIndex::end(), None));
+
+        let mut counter = Counter::default();
+        rp.accept(&mut counter);
+        assert_eq!((counter.modules, counter.functions, counter.bindings), (2, 3, 4));
+
+        rp.accept_mut(&mut Renamer);
+        let names = rp.modules[1].functions[&0x3000]
+            .bindings
+            .0
+            .iter()
+            .map(|vb| vb.name().to_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["m1", "m1"]);
+        assert_eq!(rp.modules[0].functions[&0x1000].bindings.0[0].name(), "m0");
+    }
+
+    #[test]
+    fn test_merge_functions() {
+        let mut rmod = mock_module(&[0x1000, 0x1010, 0x2000], &[(0, 1), (1, 2), (2, 1)]);
+        for (i, &off) in [0x1000, 0x1010].iter().enumerate() {
+            let rfn = rmod.function_mut(off).unwrap();
+            let mut op = esil_op("rax,rbx,=");
+            op.offset = Some(off);
+            rfn.instructions.push(op);
+            rfn.datarefs = vec![0x3000 + i as u64];
+            rfn.bindings.0.push(VarBinding::new(BindingType::Return, None, NodeIndex::end(), None));
+        }
+        assert!(rmod.merge_functions(0x1000, 0x2000).is_err());
+        assert!(rmod.merge_functions(0x1000, 0x1000).is_err());
+
+        assert_eq!(rmod.merge_functions(0x1010, 0x1000), Ok(0x1000));
+        assert!(rmod.function(0x1010).is_none());
+        let rfn = rmod.function(0x1000).unwrap();
+        assert_eq!(rfn.size(), 0x20);
+        assert_eq!(rfn.instructions().len(), 2);
+        assert_eq!(rfn.datarefs(), &[0x3000, 0x3001]);
+        // No source to rebuild the SSA from
+        assert_eq!(rfn.bindings().len(), 0);
+
+        // The calls between the two halves are gone, the ones to 0x2000 moved over.
+        let cg = rmod.callgraph();
+        assert_eq!(cg.node_count(), 2);
+        assert_eq!(cg.edge_count(), 2);
+        let callees = cg.callees(rfn.cgid()).map(|(_, n)| cg[n]).collect::<Vec<_>>();
+        assert_eq!(callees, vec![0x2000]);
+        let callers = cg.callers(rmod.function(0x2000).unwrap().cgid()).count();
+        assert_eq!(callers, 1);
+    }
+
+    #[test]
+    fn test_split_function() {
+        let mut ops = vec![esil_op("0x3000,rax,="), esil_op("rax,rbx,="), esil_op("0x3008,rcx,=")];
+        ops.extend((0..4).map(|_| esil_op("rbx,rdx,=")));
+        ops[0].size = Some(2);
+        let mut addr = 0x1000;
+        for op in &mut ops {
+            op.offset = Some(addr);
+            addr += op.size.unwrap();
+        }
+        let mut mock = MockSource::default();
+        mock.instructions = ops.clone();
+
+        let mut rmod = mock_module(&[0x1000, 0x2000], &[(0, 1)]);
+        {
+            let rfn = rmod.function_mut(0x1000).unwrap();
+            rfn.set_size(8);
+            rfn.instructions = ops;
+            rfn.datarefs = vec![0x3000, 0x3008];
+            rfn.comments.insert(0x1005, "tail".to_owned());
+        }
+        rmod.set_source(Rc::new(mock));
+        assert!(rmod.split_function(0x1000, 0x1001).is_err());
+        assert!(rmod.split_function(0x1000, 0x1008).is_err());
+        assert!(rmod.split_function(0x1000, 0x1000).is_err());
+        assert!(rmod.split_function(0x3000, 0x3002).is_err());
+
+        assert_eq!(rmod.split_function(0x1000, 0x1003), Ok(0x1003));
+        let offsets = |rfn: &RadecoFunction| {
+            rfn.instructions().iter().map(|op| op.offset.unwrap()).collect::<Vec<_>>()
+        };
+        let head = rmod.function(0x1000).unwrap();
+        let tail = rmod.function(0x1003).unwrap();
+        assert_eq!((head.size(), tail.size()), (3, 5));
+        assert_eq!(offsets(head), vec![0x1000, 0x1002]);
+        assert_eq!(offsets(tail), vec![0x1003, 0x1004, 0x1005, 0x1006, 0x1007]);
+        assert!(head.ssa().entry_node().is_some());
+        assert!(tail.ssa().entry_node().is_some());
+        assert_eq!(head.datarefs(), &[0x3000]);
+        assert_eq!(tail.datarefs(), &[0x3008]);
+        assert_eq!(tail.comment(0x1005), Some("tail"));
+        assert_eq!(tail.name, "fcn.00001003");
+
+        // The call at 0x1004 is made from the new function.
+        let cg = rmod.callgraph();
+        assert_eq!(cg.callees(head.cgid()).count(), 0);
+        let callees = cg.callees(tail.cgid()).map(|(_, n)| cg[n]).collect::<Vec<_>>();
+        assert_eq!(callees, vec![0x2000]);
+    }
+
+    #[test]
+    fn test_reachable_from() {
+        let rmod = mock_module(&[0x100, 0x200, 0x300], &[(0, 1), (1, 0)]);
+        let root = rmod.function(0x100).unwrap().cgid();
+        let reachable = rmod.callgraph().reachable_from(&[root]);
+        assert_eq!(reachable.len(), 2);
+        assert!(reachable.contains(&root));
+        assert!(!reachable.contains(&rmod.function(0x300).unwrap().cgid()));
+    }
+
+    #[test]
+    fn test_dead_functions() {
+        let mut rmod = mock_module(&[0x100, 0x200, 0x300], &[(0, 1)]);
+        let mut entry = LEntryInfo::default();
+        entry.vaddr = Some(0x100);
+        rmod.entrypoint = vec![entry];
+        assert_eq!(rmod.dead_functions(), vec![0x300]);
+    }
+
+    #[test]
+    fn test_strat_from_csv() {
+        let (path, mut f) = temp_file("strat_from_csv");
+        f.write_all(b"0x1000,foo,0x20\n# comment\n4096\nbar,0x2000,16\n8192,bar,16\n")
+            .unwrap();
+
+        let fl = FunctionLoader::default()
+            .strategy(&strat_three_fns)
+            .strategy_boxed(loader_defaults::strat_from_csv(&path))
+            .merge_policy(MergePolicy::LastWins)
+            .load(&RadecoModule::default());
+        fs::remove_file(&path).unwrap();
+        assert_eq!(fl.count(), 3);
+        assert_eq!(fl.functions[&0x1000].name, "foo");
+        assert_eq!(fl.functions[&0x1000].size(), 0x20);
+        assert_eq!(fl.functions[&0x2000].name, "bar");
+        assert_eq!(fl.functions[&0x3000].name, "c");
+    }
+
+    #[test]
+    fn test_save_load() {
+        let mut rmod = mock_module(&[0x100, 0x200], &[(0, 1)]);
+        rmod.function_mut(0x100).unwrap().bindings =
+            VarBindings(vec![VarBinding::new(BindingType::RegisterArgument(0),
+                                             Some("arg0".to_owned()),
+                                             NodeIndex::new(3),
+                                             Some(5))]);
+        let mut rp = RadecoProject::new();
+        rp.modules.push(rmod);
+
+        let (path, _) = temp_file("save_load");
+        rp.save(&path).unwrap();
+        let loaded = RadecoProject::load_from(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let rmod = loaded.nth_module(0).unwrap();
+        assert!(rmod.source.is_none());
+        assert_eq!(rmod.functions.keys().cloned().collect::<Vec<_>>(), vec![0x100, 0x200]);
+        assert_eq!(rmod.callgraph().edge_count(), 1);
+        let bindings = rmod.function(0x100).unwrap().bindings().into_iter().collect::<Vec<_>>();
+        assert_eq!(bindings.len(), 1);
+        assert_eq!(bindings[0].btype(), BindingType::RegisterArgument(0));
+        assert_eq!(bindings[0].index(), NodeIndex::new(3));
+        assert_eq!(bindings[0].ridx, Some(5));
+    }
 
     #[test]
     fn test_fn_loader() {
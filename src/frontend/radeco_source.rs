@@ -10,15 +10,22 @@ use std::cell::RefCell;
 use std::error::Error;
 use std::fmt;
 
+use capstone_rust::capstone as cs;
+use goblin::Object;
+use goblin::elf::{self, Elf};
+use goblin::mach::{self, Mach, MachO};
+use goblin::pe::{self, PE};
 use r2api::api_trait::R2Api;
 use r2pipe::r2::R2;
 use r2api::structs::{FunctionInfo, LFlagInfo, LOpInfo, LRegInfo, LSectionInfo, LStringInfo, LSymbolInfo,
-LImportInfo, LExportInfo, LRelocInfo, LEntryInfo};
+LImportInfo, LExportInfo, LRelocInfo, LEntryInfo, LSymbolType};
 
 #[derive(Debug)]
 pub enum SourceErr {
     SrcErr(&'static str),
     OtherErr(Box<Error>),
+    /// The `Source` is unable to provide the requested information
+    NotSupported,
 }
 
 impl fmt::Display for SourceErr {
@@ -26,6 +33,7 @@ impl fmt::Display for SourceErr {
         match self {
             &SourceErr::SrcErr(s) => write!(f, "{}", s),
             &SourceErr::OtherErr(ref e) => write!(f, "{}", e),
+            &SourceErr::NotSupported => write!(f, "Not supported by source"),
         }
     }
 }
@@ -57,6 +65,24 @@ pub trait Source {
     fn send(&self, _: &str) -> Result<(), SourceErr> { Ok(()) }
 
     // Non essential / functions with default implementation.
+
+    /// Disassemble `n` bytes at `at`, as ARM Thumb code if `thumb` is set. Sources that do not
+    /// distinguish between instruction set modes ignore `thumb`.
+    fn disassemble_n_bytes_mode(&self, n: u64, at: u64, _thumb: bool) -> Result<Vec<LOpInfo>, SourceErr> {
+        self.disassemble_n_bytes(n, at)
+    }
+
+    /// Read `len` bytes of the loaded image at `addr`.
+    fn read_bytes(&self, _addr: u64, _len: usize) -> Result<Vec<u8>, SourceErr> {
+        Err(SourceErr::NotSupported)
+    }
+
+    /// Override the architecture and/or the word size in bits the source assumes for the
+    /// loaded image. This affects the register profile and all disassembly done afterwards.
+    fn set_arch(&self, _arch: Option<&str>, _bits: Option<u32>) -> Result<(), SourceErr> {
+        Err(SourceErr::NotSupported)
+    }
+
     fn function_at(&self, address: u64) -> Result<FunctionInfo, SourceErr> {
         for f in self.functions()? {
             match f.offset {
@@ -182,10 +208,46 @@ impl<R: R2Api> Source for WrappedR2Api<R> {
         Ok(self.try_borrow_mut()?.disassemble_n_insts(n, Some(at))?)
     }
 
+    fn disassemble_n_bytes_mode(&self, n: u64, at: u64, thumb: bool) -> Result<Vec<LOpInfo>, SourceErr> {
+        if !thumb {
+            return self.disassemble_n_bytes(n, at);
+        }
+        // Switch to Thumb only for this function, and restore the previous mode afterwards.
+        let mut r2 = self.try_borrow_mut()?;
+        let bits = r2.raw("e asm.bits".to_owned());
+        r2.raw("e asm.bits=16".to_owned());
+        let ops = r2.disassemble_n_bytes(n, Some(at));
+        r2.raw(format!("e asm.bits={}", bits.trim()));
+        Ok(ops?)
+    }
+
     fn raw(&self, cmd: String) -> Result<String, SourceErr> {
         Ok(self.try_borrow_mut()?.raw(cmd))
     }
 
+    fn read_bytes(&self, addr: u64, len: usize) -> Result<Vec<u8>, SourceErr> {
+        // `p8` prints the bytes as a single string of hex digit pairs.
+        let hex = self.try_borrow_mut()?.raw(format!("p8 {} @ {:#x}", len, addr));
+        let hex = hex.trim();
+        if hex.len() != 2 * len {
+            return Err(SourceErr::SrcErr("Unexpected output from p8"));
+        }
+        Ok((0..len)
+            .map(|i| u8::from_str_radix(&hex[2 * i..2 * i + 2], 16))
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+
+    fn set_arch(&self, arch: Option<&str>, bits: Option<u32>) -> Result<(), SourceErr> {
+        let mut r2 = self.try_borrow_mut()?;
+        if let Some(arch) = arch {
+            r2.raw(format!("e asm.arch={}", arch));
+        }
+        if let Some(bits) = bits {
+            r2.raw(format!("e asm.bits={}", bits));
+        }
+        Ok(())
+    }
+
     fn send(&self, s: &str) -> Result<(), SourceErr> {
         unimplemented!()
     }
@@ -328,3 +390,539 @@ impl<R: R2Api> From<WrappedR2Api<R>> for FileSource {
         fsource
     }
 }
+
+#[derive(Clone, Debug)]
+/// Source backed by a raw buffer of code, for when spawning r2 is undesirable.
+///
+/// Instructions are decoded using capstone and register profiles are bundled with radeco.
+/// Supported architectures are `x86` (32-bit, no register profile) and `x86_64`.
+///
+/// The buffer is treated as a single executable section mapped at `base`, which is also
+/// reported as the only entrypoint. `symbols`, `imports`, `exports`, `relocs` and
+/// `libraries` are always empty. `functions`, `instructions_at`, `flags` and `raw` return
+/// `Err(SourceErr::NotSupported)`, as does `register_profile` for architectures without a
+/// bundled profile.
+///
+/// `set_arch` switches between the supported architectures, either by name or by setting the
+/// bits to 32 or 64.
+///
+/// Decoded instructions carry no ESIL, so they can be used to identify instruction
+/// boundaries, but not to construct the SSA.
+pub struct BufferSource {
+    bytes: Vec<u8>,
+    base: u64,
+    arch: RefCell<String>,
+}
+
+const X86_64_REGISTER_PROFILE: &'static str = include_str!("register_profiles/x86_64.json");
+
+impl BufferSource {
+    pub fn new<T: AsRef<str>>(bytes: Vec<u8>, base: u64, arch: T) -> BufferSource {
+        BufferSource {
+            bytes: bytes,
+            base: base,
+            arch: RefCell::new(arch.as_ref().to_owned()),
+        }
+    }
+
+    fn capstone(&self) -> Result<cs::Capstone, SourceErr> {
+        let mode = match self.arch.borrow().as_ref() {
+            "x86" => cs::CS_MODE_32,
+            "x86_64" => cs::CS_MODE_64,
+            _ => return Err(SourceErr::NotSupported),
+        };
+        cs::Capstone::new(cs::cs_arch::CS_ARCH_X86, mode)
+            .map_err(|_| SourceErr::SrcErr("Unable to initialize capstone"))
+    }
+
+    // Disassemble instructions in `[at, at + n_bytes)`, stopping after `n_insts` instructions
+    // if it is non-zero.
+    fn disassemble(&self,
+                   at: u64,
+                   n_bytes: u64,
+                   n_insts: usize)
+                   -> Result<Vec<LOpInfo>, SourceErr> {
+        if at < self.base || at - self.base > self.bytes.len() as u64 {
+            return Err(SourceErr::SrcErr("Address outside of buffer"));
+        }
+        let start = (at - self.base) as usize;
+        let end = start.saturating_add(n_bytes as usize).min(self.bytes.len());
+        let dis = self.capstone()?;
+        let buf = dis.disasm(&self.bytes[start..end], at, n_insts)
+            .map_err(|_| SourceErr::SrcErr("Unable to disassemble"))?;
+        Ok(buf.iter()
+            .map(|instr| {
+                let mut op = LOpInfo::default();
+                op.offset = Some(instr.address);
+                op.size = Some(instr.size as u64);
+                op.opcode = Some(format!("{} {}", instr.mnemonic, instr.op_str).trim().to_owned());
+                let start = (instr.address - self.base) as usize;
+                op.bytes = Some(self.bytes[start..start + instr.size as usize]
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect());
+                op
+            })
+            .collect())
+    }
+}
+
+impl Source for BufferSource {
+    fn functions(&self) -> Result<Vec<FunctionInfo>, SourceErr> {
+        Err(SourceErr::NotSupported)
+    }
+
+    fn instructions_at(&self, _: u64) -> Result<Vec<LOpInfo>, SourceErr> {
+        Err(SourceErr::NotSupported)
+    }
+
+    fn register_profile(&self) -> Result<LRegInfo, SourceErr> {
+        match self.arch.borrow().as_ref() {
+            "x86_64" => Ok(serde_json::from_str(X86_64_REGISTER_PROFILE)?),
+            _ => Err(SourceErr::NotSupported),
+        }
+    }
+
+    fn flags(&self) -> Result<Vec<LFlagInfo>, SourceErr> {
+        Err(SourceErr::NotSupported)
+    }
+
+    fn sections(&self) -> Result<Vec<LSectionInfo>, SourceErr> {
+        let mut section = LSectionInfo::default();
+        section.name = Some(".buffer".to_owned());
+        section.flags = Some("-r-x".to_owned());
+        section.paddr = Some(0);
+        section.vaddr = Some(self.base);
+        section.size = Some(self.bytes.len() as u64);
+        section.vsize = Some(self.bytes.len() as u64);
+        Ok(vec![section])
+    }
+
+    fn symbols(&self) -> Result<Vec<LSymbolInfo>, SourceErr> {
+        Ok(Vec::new())
+    }
+
+    fn imports(&self) -> Result<Vec<LImportInfo>, SourceErr> {
+        Ok(Vec::new())
+    }
+
+    fn exports(&self) -> Result<Vec<LExportInfo>, SourceErr> {
+        Ok(Vec::new())
+    }
+
+    fn relocs(&self) -> Result<Vec<LRelocInfo>, SourceErr> {
+        Ok(Vec::new())
+    }
+
+    fn libraries(&self) -> Result<Vec<String>, SourceErr> {
+        Ok(Vec::new())
+    }
+
+    fn entrypoint(&self) -> Result<Vec<LEntryInfo>, SourceErr> {
+        let mut entry = LEntryInfo::default();
+        entry.vaddr = Some(self.base);
+        entry.paddr = Some(0);
+        Ok(vec![entry])
+    }
+
+    fn disassemble_n_bytes(&self, n: u64, at: u64) -> Result<Vec<LOpInfo>, SourceErr> {
+        self.disassemble(at, n, 0)
+    }
+
+    fn disassemble_n_insts(&self, n: u64, at: u64) -> Result<Vec<LOpInfo>, SourceErr> {
+        self.disassemble(at, u64::max_value(), n as usize)
+    }
+
+    fn raw(&self, _: String) -> Result<String, SourceErr> {
+        Err(SourceErr::NotSupported)
+    }
+
+    fn set_arch(&self, arch: Option<&str>, bits: Option<u32>) -> Result<(), SourceErr> {
+        let current = self.arch.borrow().clone();
+        // The bits, if given, decide between the 32 and 64-bit variants.
+        let arch = match (arch.unwrap_or(&current), bits) {
+            ("x86", None) | ("x86", Some(32)) | ("x86_64", Some(32)) => "x86",
+            ("x86_64", None) | ("x86", Some(64)) | ("x86_64", Some(64)) => "x86_64",
+            _ => return Err(SourceErr::NotSupported),
+        };
+        *self.arch.borrow_mut() = arch.to_owned();
+        Ok(())
+    }
+
+    fn read_bytes(&self, addr: u64, len: usize) -> Result<Vec<u8>, SourceErr> {
+        if addr < self.base ||
+           (addr - self.base).saturating_add(len as u64) > self.bytes.len() as u64 {
+            return Err(SourceErr::SrcErr("Address outside of buffer"));
+        }
+        let start = (addr - self.base) as usize;
+        Ok(self.bytes[start..start + len].to_vec())
+    }
+}
+
+/// `Source` that reads an ELF, PE or Mach-O file with `goblin`, without running radare2.
+///
+/// The `sections`, `symbols`, `imports`, `exports`, `relocs`, `libraries` and `entrypoint` are
+/// read from the file once, when the `GoblinSource` is created. `read_bytes` returns the
+/// contents of the file that are mapped at the requested address.
+///
+/// There is no disassembly, so `functions`, `instructions_at`, `flags`, `raw` and the
+/// `disassemble_*` methods return `Err(SourceErr::NotSupported)`. It is meant for loading with
+/// `ModuleLoader::metadata_only`. `register_profile` is only available for x86_64.
+///
+/// ELF imports are placed at their PLT entry, assuming the 16-byte entries of x86. For PE and
+/// Mach-O, they are placed at their slot in the import address table, and the exports are also
+/// reported as `symbols`. Only single-architecture Mach-O files are understood.
+pub struct GoblinSource {
+    bytes: Vec<u8>,
+    arch: String,
+    sections: Vec<LSectionInfo>,
+    symbols: Vec<LSymbolInfo>,
+    imports: Vec<LImportInfo>,
+    exports: Vec<LExportInfo>,
+    relocs: Vec<LRelocInfo>,
+    libraries: Vec<String>,
+    entrypoint: Vec<LEntryInfo>,
+}
+
+// Size of an entry in the PLT on x86
+const PLT_ENTRY_SIZE: u64 = 16;
+
+impl GoblinSource {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<GoblinSource, SourceErr> {
+        let mut bytes = Vec::new();
+        File::open(path)?.read_to_end(&mut bytes)?;
+        GoblinSource::new(bytes)
+    }
+
+    pub fn new(bytes: Vec<u8>) -> Result<GoblinSource, SourceErr> {
+        let mut src = GoblinSource {
+            bytes: Vec::new(),
+            arch: String::new(),
+            sections: Vec::new(),
+            symbols: Vec::new(),
+            imports: Vec::new(),
+            exports: Vec::new(),
+            relocs: Vec::new(),
+            libraries: Vec::new(),
+            entrypoint: Vec::new(),
+        };
+        match Object::parse(&bytes)? {
+            Object::Elf(elf) => src.load_elf(&elf),
+            Object::PE(pe) => src.load_pe(&pe),
+            Object::Mach(Mach::Binary(macho)) => src.load_macho(&macho)?,
+            _ => return Err(SourceErr::NotSupported),
+        }
+        src.bytes = bytes;
+        Ok(src)
+    }
+
+    fn load_elf(&mut self, elf: &Elf) {
+        self.arch = match elf.header.e_machine {
+            elf::header::EM_X86_64 => "x86_64",
+            elf::header::EM_386 => "x86",
+            _ => "unknown",
+        }.to_owned();
+
+        let headers = elf.section_headers.iter();
+        for sh in headers.filter(|sh| sh.sh_type != elf::section_header::SHT_NULL) {
+            let flags = sh.sh_flags as u32;
+            let mut section = LSectionInfo::default();
+            section.name = Some(elf.shdr_strtab[sh.sh_name].to_owned());
+            section.flags = Some(section_flags(flags & elf::section_header::SHF_ALLOC != 0,
+                                               flags & elf::section_header::SHF_WRITE != 0,
+                                               flags & elf::section_header::SHF_EXECINSTR != 0));
+            section.paddr = Some(sh.sh_offset);
+            section.vaddr = Some(sh.sh_addr);
+            section.size = Some(if sh.sh_type == elf::section_header::SHT_NOBITS {
+                0
+            } else {
+                sh.sh_size
+            });
+            section.vsize = Some(sh.sh_size);
+            self.sections.push(section);
+        }
+
+        // Stripped binaries only have the dynamic symbols.
+        let (syms, strtab) = if elf.syms.iter().next().is_some() {
+            (elf.syms.iter().collect::<Vec<_>>(), &elf.strtab)
+        } else {
+            (elf.dynsyms.iter().collect::<Vec<_>>(), &elf.dynstrtab)
+        };
+        for sym in syms {
+            let name = &strtab[sym.st_name];
+            if name.is_empty() || sym.st_shndx == 0 || sym.st_value == 0 {
+                continue;
+            }
+            let mut symbol = LSymbolInfo::default();
+            symbol.name = Some(name.to_owned());
+            symbol.vaddr = Some(sym.st_value);
+            symbol.paddr = self.paddr_of(sym.st_value);
+            symbol.size = Some(sym.st_size);
+            if sym.is_function() {
+                symbol.stype = Some(LSymbolType::Func);
+            }
+            self.symbols.push(symbol);
+        }
+
+        for sym in elf.dynsyms.iter() {
+            let bind = sym.st_bind();
+            let name = &elf.dynstrtab[sym.st_name];
+            if name.is_empty() || sym.st_shndx == 0 || sym.st_value == 0 ||
+               (bind != elf::sym::STB_GLOBAL && bind != elf::sym::STB_WEAK) {
+                continue;
+            }
+            let mut export = LExportInfo::default();
+            export.name = Some(name.to_owned());
+            export.vaddr = Some(sym.st_value);
+            export.paddr = self.paddr_of(sym.st_value);
+            export.size = Some(sym.st_size);
+            self.exports.push(export);
+        }
+
+        let sym_name = |idx: usize| {
+            elf.dynsyms
+                .iter()
+                .nth(idx)
+                .map(|sym| elf.dynstrtab[sym.st_name].to_owned())
+                .and_then(|name| if name.is_empty() { None } else { Some(name) })
+        };
+        let plt = elf.section_headers
+            .iter()
+            .find(|sh| &elf.shdr_strtab[sh.sh_name] == ".plt")
+            .map(|sh| sh.sh_addr);
+        for (i, reloc) in elf.pltrelocs.iter().enumerate() {
+            let mut import = LImportInfo::default();
+            import.name = sym_name(reloc.r_sym);
+            // The first entry of the PLT calls the dynamic linker.
+            import.plt = plt.map(|plt| plt + (i as u64 + 1) * PLT_ENTRY_SIZE);
+            self.imports.push(import);
+        }
+
+        for reloc in elf.dynrelas.iter().chain(elf.dynrels.iter()).chain(elf.pltrelocs.iter()) {
+            let mut r = LRelocInfo::default();
+            r.name = if reloc.r_sym == 0 { None } else { sym_name(reloc.r_sym) };
+            r.vaddr = Some(reloc.r_offset);
+            r.paddr = self.paddr_of(reloc.r_offset);
+            self.relocs.push(r);
+        }
+
+        self.libraries = elf.libraries.iter().map(|lib| lib.to_string()).collect();
+        self.add_entrypoint(elf.entry);
+    }
+
+    fn load_pe(&mut self, pe: &PE) {
+        self.arch = if pe.is_64 { "x86_64" } else { "x86" }.to_owned();
+        let base = pe.image_base as u64;
+
+        for st in &pe.sections {
+            let chars = st.characteristics;
+            let mut section = LSectionInfo::default();
+            section.name = st.name().ok().map(|name| name.to_owned());
+            section.flags =
+                Some(section_flags(chars & pe::section_table::IMAGE_SCN_MEM_READ != 0,
+                                   chars & pe::section_table::IMAGE_SCN_MEM_WRITE != 0,
+                                   chars & pe::section_table::IMAGE_SCN_MEM_EXECUTE != 0));
+            section.paddr = Some(st.pointer_to_raw_data as u64);
+            section.vaddr = Some(base + st.virtual_address as u64);
+            section.size = Some(st.size_of_raw_data as u64);
+            section.vsize = Some(st.virtual_size as u64);
+            self.sections.push(section);
+        }
+
+        for exp in &pe.exports {
+            let vaddr = base + exp.rva as u64;
+            let mut export = LExportInfo::default();
+            export.name = Some(exp.name.to_owned());
+            export.vaddr = Some(vaddr);
+            export.paddr = self.paddr_of(vaddr);
+            export.size = Some(exp.size as u64);
+            self.exports.push(export);
+
+            let mut symbol = LSymbolInfo::default();
+            symbol.name = Some(exp.name.to_owned());
+            symbol.vaddr = Some(vaddr);
+            symbol.paddr = self.paddr_of(vaddr);
+            symbol.size = Some(exp.size as u64);
+            self.symbols.push(symbol);
+        }
+
+        for imp in &pe.imports {
+            let mut import = LImportInfo::default();
+            import.name = Some(imp.name.to_string());
+            import.plt = Some(base + imp.rva as u64);
+            self.imports.push(import);
+        }
+
+        self.libraries = pe.libraries.iter().map(|lib| lib.to_string()).collect();
+        self.add_entrypoint(base + pe.entry as u64);
+    }
+
+    fn load_macho(&mut self, macho: &MachO) -> Result<(), SourceErr> {
+        self.arch = match macho.header.cputype {
+            mach::cputype::CPU_TYPE_X86_64 => "x86_64",
+            mach::cputype::CPU_TYPE_X86 => "x86",
+            _ => "unknown",
+        }.to_owned();
+
+        for segment in macho.segments.iter() {
+            for (sect, _) in segment.sections()? {
+                let mut section = LSectionInfo::default();
+                section.name = sect.name().ok().map(|name| name.to_owned());
+                section.flags = Some(section_flags(segment.initprot & 1 != 0,
+                                                   segment.initprot & 2 != 0,
+                                                   segment.initprot & 4 != 0));
+                section.paddr = Some(sect.offset as u64);
+                section.vaddr = Some(sect.addr);
+                section.size = Some(sect.size);
+                section.vsize = Some(sect.size);
+                self.sections.push(section);
+            }
+        }
+
+        for sym in macho.symbols() {
+            let (name, nlist) = sym?;
+            if name.is_empty() || nlist.is_undefined() {
+                continue;
+            }
+            let mut symbol = LSymbolInfo::default();
+            symbol.name = Some(name.to_owned());
+            symbol.vaddr = Some(nlist.n_value);
+            symbol.paddr = self.paddr_of(nlist.n_value);
+            self.symbols.push(symbol);
+        }
+
+        for exp in macho.exports()? {
+            let mut export = LExportInfo::default();
+            export.name = Some(exp.name.clone());
+            export.paddr = Some(exp.offset);
+            export.vaddr = self.vaddr_of(exp.offset);
+            export.size = Some(exp.size as u64);
+            self.exports.push(export);
+        }
+
+        for imp in macho.imports()? {
+            let mut import = LImportInfo::default();
+            import.name = Some(imp.name.to_owned());
+            import.plt = Some(imp.address);
+            self.imports.push(import);
+        }
+
+        // The first library is the binary itself.
+        self.libraries = macho.libs.iter().skip(1).map(|lib| lib.to_string()).collect();
+        self.add_entrypoint(macho.entry);
+        Ok(())
+    }
+
+    fn add_entrypoint(&mut self, vaddr: u64) {
+        let mut entry = LEntryInfo::default();
+        entry.vaddr = Some(vaddr);
+        entry.paddr = self.paddr_of(vaddr);
+        self.entrypoint.push(entry);
+    }
+
+    // Section whose contents in the file are mapped at `vaddr`
+    fn mapped_section(&self, vaddr: u64) -> Option<&LSectionInfo> {
+        self.sections.iter().find(|s| {
+            let start = s.vaddr.unwrap_or(0);
+            start != 0 && vaddr >= start && vaddr - start < s.size.unwrap_or(0)
+        })
+    }
+
+    // Offset in the file of the contents mapped at `vaddr`
+    fn paddr_of(&self, vaddr: u64) -> Option<u64> {
+        self.mapped_section(vaddr)
+            .map(|s| s.paddr.unwrap_or(0) + vaddr - s.vaddr.unwrap_or(0))
+    }
+
+    // Address at which the contents at offset `paddr` in the file are mapped
+    fn vaddr_of(&self, paddr: u64) -> Option<u64> {
+        self.sections
+            .iter()
+            .find(|s| {
+                let start = s.paddr.unwrap_or(0);
+                s.vaddr.unwrap_or(0) != 0 && paddr >= start && paddr - start < s.size.unwrap_or(0)
+            })
+            .map(|s| s.vaddr.unwrap_or(0) + paddr - s.paddr.unwrap_or(0))
+    }
+}
+
+// Section permissions in the format used by radare2, e.g. `-r-x`
+fn section_flags(read: bool, write: bool, exec: bool) -> String {
+    format!("-{}{}{}",
+            if read { 'r' } else { '-' },
+            if write { 'w' } else { '-' },
+            if exec { 'x' } else { '-' })
+}
+
+impl Source for GoblinSource {
+    fn functions(&self) -> Result<Vec<FunctionInfo>, SourceErr> {
+        Err(SourceErr::NotSupported)
+    }
+
+    fn instructions_at(&self, _: u64) -> Result<Vec<LOpInfo>, SourceErr> {
+        Err(SourceErr::NotSupported)
+    }
+
+    fn register_profile(&self) -> Result<LRegInfo, SourceErr> {
+        match self.arch.as_ref() {
+            "x86_64" => Ok(serde_json::from_str(X86_64_REGISTER_PROFILE)?),
+            _ => Err(SourceErr::NotSupported),
+        }
+    }
+
+    fn flags(&self) -> Result<Vec<LFlagInfo>, SourceErr> {
+        Err(SourceErr::NotSupported)
+    }
+
+    fn sections(&self) -> Result<Vec<LSectionInfo>, SourceErr> {
+        Ok(self.sections.clone())
+    }
+
+    fn symbols(&self) -> Result<Vec<LSymbolInfo>, SourceErr> {
+        Ok(self.symbols.clone())
+    }
+
+    fn imports(&self) -> Result<Vec<LImportInfo>, SourceErr> {
+        Ok(self.imports.clone())
+    }
+
+    fn exports(&self) -> Result<Vec<LExportInfo>, SourceErr> {
+        Ok(self.exports.clone())
+    }
+
+    fn relocs(&self) -> Result<Vec<LRelocInfo>, SourceErr> {
+        Ok(self.relocs.clone())
+    }
+
+    fn libraries(&self) -> Result<Vec<String>, SourceErr> {
+        Ok(self.libraries.clone())
+    }
+
+    fn entrypoint(&self) -> Result<Vec<LEntryInfo>, SourceErr> {
+        Ok(self.entrypoint.clone())
+    }
+
+    fn disassemble_n_bytes(&self, _: u64, _: u64) -> Result<Vec<LOpInfo>, SourceErr> {
+        Err(SourceErr::NotSupported)
+    }
+
+    fn disassemble_n_insts(&self, _: u64, _: u64) -> Result<Vec<LOpInfo>, SourceErr> {
+        Err(SourceErr::NotSupported)
+    }
+
+    fn raw(&self, _: String) -> Result<String, SourceErr> {
+        Err(SourceErr::NotSupported)
+    }
+
+    fn read_bytes(&self, addr: u64, len: usize) -> Result<Vec<u8>, SourceErr> {
+        let section = self.mapped_section(addr)
+            .ok_or(SourceErr::SrcErr("Address not mapped from file"))?;
+        let offset = addr - section.vaddr.unwrap_or(0);
+        let start = (section.paddr.unwrap_or(0) + offset) as usize;
+        if offset.saturating_add(len as u64) > section.size.unwrap_or(0) ||
+           start.saturating_add(len) > self.bytes.len() {
+            return Err(SourceErr::SrcErr("Address not mapped from file"));
+        }
+        Ok(self.bytes[start..start + len].to_vec())
+    }
+}
@@ -21,8 +21,10 @@ use frontend::radeco_containers::RadecoFunction;
 use middle::ir::{self, MAddress, MOpcode};
 use middle::phiplacement::PhiPlacer;
 use middle::regfile::SubRegisterFile;
+use middle::ssa::cfg_traits::CFG;
 use middle::ssa::graph_traits::Graph;
-use middle::ssa::ssa_traits::{SSAExtra, SSAMod, ValueInfo};
+use middle::ssa::ssa_traits::{SSA, SSAExtra, SSAMod, ValueInfo};
+use middle::ssa::ssastorage::SSAStorage;
 use petgraph::graph::NodeIndex;
 
 use r2api::structs::{LOpInfo, LRegInfo};
@@ -37,6 +39,27 @@ const FALSE_EDGE: u8 = 0;
 const TRUE_EDGE: u8 = 1;
 const UNCOND_EDGE: u8 = 2;
 
+/// Reasons for which SSA construction for a function can fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstructErr {
+    /// None of the instructions of the function could be lifted from ESIL
+    NoESIL,
+    /// The constructed SSA has no entry node
+    NoEntry,
+    /// The SSA grew beyond the given number of nodes
+    TooManyNodes(usize),
+}
+
+impl fmt::Display for ConstructErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ConstructErr::NoESIL => write!(f, "No ESIL found for the instructions"),
+            ConstructErr::NoEntry => write!(f, "No entry node in the constructed SSA"),
+            ConstructErr::TooManyNodes(max) => write!(f, "SSA exceeded the limit of {} nodes", max),
+        }
+    }
+}
+
 pub struct SSAConstruct<'a, T>
     where T: 'a + Clone + fmt::Debug + SSAExtra + SSAMod<BBInfo = MAddress,
                     ActionRef = <T as Graph>::GraphNodeRef,
@@ -53,6 +76,9 @@ pub struct SSAConstruct<'a, T>
     needs_new_block: bool,
     mem_id: u64,
     assume_cc: bool,
+    // Maximum number of nodes in the SSA, construction stops once it is exceeded
+    max_nodes: Option<usize>,
+    exceeded_max_nodes: bool,
 }
 
 impl<'a, T> SSAConstruct<'a, T>
@@ -70,6 +96,8 @@ impl<'a, T> SSAConstruct<'a, T>
             needs_new_block: true,
             mem_id: 0,
             assume_cc: false,
+            max_nodes: None,
+            exceeded_max_nodes: false,
         };
 
         // Add all the registers to the variable list.
@@ -80,12 +108,46 @@ impl<'a, T> SSAConstruct<'a, T>
     }
 
     // Helper wrapper.
-    pub fn construct(rfn: &mut RadecoFunction, ri: &LRegInfo, assume_cc: bool) {
+    // A function without any instructions (such as an import stub) only gets the entry and
+    // exit blocks and is not considered to be a failure. Neither is a missing or unreachable
+    // exit, which is the case for functions that never return.
+    pub fn construct(rfn: &mut RadecoFunction,
+                     ri: &LRegInfo,
+                     assume_cc: bool)
+                     -> Result<(), ConstructErr> {
+        SSAConstruct::<T>::construct_bounded(rfn, ri, assume_cc, None)
+    }
+
+    /// Like `construct`, but gives up with `ConstructErr::TooManyNodes` once the SSA has more
+    /// than `max_nodes` nodes. The partially constructed SSA is discarded in that case.
+    pub fn construct_bounded(rfn: &mut RadecoFunction,
+                             ri: &LRegInfo,
+                             assume_cc: bool,
+                             max_nodes: Option<usize>)
+                             -> Result<(), ConstructErr> {
         let instructions = rfn.instructions().to_vec();
         let regfile = SubRegisterFile::new(ri);
-        let mut constr = SSAConstruct::new(rfn.ssa_mut(), &regfile);
-        constr.assume_cc = assume_cc;
-        constr.run(instructions.as_slice());
+        let exceeded = {
+            let mut constr = SSAConstruct::new(rfn.ssa_mut(), &regfile);
+            constr.assume_cc = assume_cc;
+            constr.max_nodes = max_nodes;
+            constr.run(instructions.as_slice());
+            constr.exceeded_max_nodes
+        };
+        if let (true, Some(max)) = (exceeded, max_nodes) {
+            *rfn.ssa_mut() = SSAStorage::new();
+            return Err(ConstructErr::TooManyNodes(max));
+        }
+
+        if !instructions.is_empty() &&
+           !instructions.iter().any(|op| op.esil.as_ref().map_or(false, |e| !e.is_empty())) {
+            return Err(ConstructErr::NoESIL);
+        }
+        let ssa = rfn.ssa();
+        if ssa.entry_node().and_then(|n| ssa.registers_in(n)).is_none() {
+            return Err(ConstructErr::NoEntry);
+        }
+        Ok(())
     }
 
     fn set_mem_id(&mut self, id: u64) {
@@ -429,6 +491,11 @@ impl<'a, T> SSAConstruct<'a, T>
                 continue;
             }
 
+            if self.max_nodes.map_or(false, |max| self.phiplacer.nodes_count() > max) {
+                self.exceeded_max_nodes = true;
+                break;
+            }
+
             let offset = op.offset.unwrap_or(0);
 
             // Get ESIL string
@@ -52,6 +52,8 @@
 #![allow(warnings)]
 extern crate regex;
 extern crate petgraph;
+extern crate serde;
+#[macro_use] extern crate serde_derive;
 extern crate serde_json;
 extern crate num;
 #[macro_use] extern crate lazy_static;
@@ -67,6 +69,9 @@ extern crate r2api;
 extern crate esil;
 extern crate capstone_rust;
 extern crate rayon;
+extern crate rustc_demangle;
+extern crate cpp_demangle;
+extern crate goblin;
 
 #[cfg(feature="profile")]
 extern crate cpuprofiler;
@@ -15,7 +15,7 @@ use std::borrow::Cow;
 pub type Address = u64;
 
 /// Used to describe the width of an operand/operation in a SSA Node.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum WidthSpec {
     /// Takes whatever width the other operation takes
     Adaptive,
@@ -48,7 +48,7 @@ impl From<u16> for WidthSpec {
     }
 }
 
-#[derive(Clone, Default, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Default, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 // New address struct
 pub struct MAddress {
     pub address: u64,
@@ -100,7 +100,7 @@ pub enum MArity {
     Zero,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum MOpcode {
     OpAdd,
     OpAnd,
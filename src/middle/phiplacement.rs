@@ -69,6 +69,11 @@ impl<'a, T> PhiPlacer<'a, T>
         }
     }
 
+    /// Number of nodes in the SSA being constructed
+    pub fn nodes_count(&self) -> usize {
+        self.ssa.nodes_count()
+    }
+
     /// Add a new variable that the phiplacer should know of.
     /// This information is required to place phi-s. Note that the 
     /// phis are generated only for variables defined in this list.
@@ -19,7 +19,7 @@ use std::collections::HashMap;
 use std::convert::From;
 
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
 pub struct SubRegister {
     pub base: u64,
     pub shift: u64,
@@ -42,7 +42,7 @@ impl SubRegister {
 ///
 /// It can then translate accesses to partial registers to accesses of whole registers.
 /// Shifts and masks are added automatically.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct SubRegisterFile {
     /// `ValueType`s of whole registers ready to be added to a `PhiPlacer`.
     /// The index within `PhiPlacer` to the first register is needed
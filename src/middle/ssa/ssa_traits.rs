@@ -27,7 +27,7 @@ use std::fmt::{self, Debug};
 use middle::ir;
 use super::cfg_traits::{CFG, CFGMod};
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 /// Defines the high level `type` of value for a node. It is only used to differentiate between
 /// references and non-reference (scalar) types.
 pub enum ValueType {
@@ -41,7 +41,7 @@ pub enum ValueType {
     Invalid,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 /// Information for a `ValueRef`
 pub struct ValueInfo {
     /// `Type` of value
@@ -26,7 +26,7 @@ use utils::logger;
 
 /// Structure that represents data that maybe associated with an node in the
 /// SSA
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AdditionalData {
     comments: Option<String>,
     flag: Option<String>,
@@ -65,7 +65,7 @@ pub type AssociatedData = HashMap<NodeIndex, AdditionalData>;
 /// `RegisterState` is neither.
 /// Value nodes have a `ValueInfo` that can be extracted with
 /// `SSA::get_node_data`
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum NodeData {
     /// Represents on operation.
     Op(MOpcode, ValueInfo),
@@ -134,7 +134,7 @@ impl fmt::Display for NodeData {
 }
 
 /// Edge type for the SSAStorage-internal petgraph.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum EdgeData {
     /// Edge from action to action. Represents control flow. The number is
     /// used to distinguish true branch, false branch, etc.
@@ -161,7 +161,7 @@ pub enum EdgeData {
 }
 
 /// A petgraph based SSA storage.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SSAStorage {
     pub g: StableDiGraph<NodeData, EdgeData>,
     entry_node: NodeIndex,
@@ -10,9 +10,10 @@
 //!
 //! This is only for verification and to catch potential mistakes.
 use std::result;
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
 use std::collections::{HashMap, VecDeque};
 use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
 
 use super::graph_traits::Graph;
 use super::cfg_traits::CFG;
@@ -20,8 +21,9 @@ use super::ssa_traits::{SSA, ValueType};
 use super::ssa_traits::NodeData as TNodeData;
 use super::ssa_traits::NodeType as TNodeType;
 use super::error::SSAErr;
-use super::ssastorage::SSAStorage;
+use super::ssastorage::{EdgeData, SSAStorage};
 
+use analysis::dom::dominators;
 use middle::ir::{MArity, MOpcode};
 
 pub type VResult<T> = result::Result<(), SSAErr<T>>;
@@ -353,3 +355,108 @@ pub fn verify<T>(ssa: &T) -> VResult<T>
     try!(ssa.verify_SCC(&register, &mut timestamp, &mut DFN, &mut LOW, &mut stack));
     Ok(())
 }
+
+/// Structural problem found by `verify_structure`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SsaError {
+    /// The SSA has no entry node
+    NoEntry,
+    /// The SSA has no exit node
+    NoExit,
+    /// (node, operand): `operand` of `node` is not a valid value
+    DanglingOperand(NodeIndex, NodeIndex),
+    /// (phi, predecessors, operands): number of operands of a phi does not match the number
+    /// of predecessors of its block
+    PhiOperands(NodeIndex, usize, usize),
+    /// (phi, predecessor): no operand of a phi is defined on the way to `predecessor`
+    PhiMissingOperand(NodeIndex, NodeIndex),
+    /// (node, blocks): value is defined in more than one block
+    MultipleDefs(NodeIndex, usize),
+    /// Operation or phi that is not contained in any block
+    NoBlock(NodeIndex),
+}
+
+impl fmt::Display for SsaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SsaError::NoEntry => write!(f, "No entry node"),
+            SsaError::NoExit => write!(f, "No exit node"),
+            SsaError::DanglingOperand(ni, op) => {
+                write!(f, "{:?} has operand {:?}, which is not a value", ni, op)
+            }
+            SsaError::PhiOperands(ni, preds, ops) => {
+                write!(f, "Phi {:?} has {} operand(s) for {} predecessor(s)", ni, ops, preds)
+            }
+            SsaError::PhiMissingOperand(ni, pred) => {
+                write!(f, "Phi {:?} has no operand for predecessor {:?}", ni, pred)
+            }
+            SsaError::MultipleDefs(ni, n) => write!(f, "{:?} is defined in {} blocks", ni, n),
+            SsaError::NoBlock(ni) => write!(f, "{:?} is not contained in any block", ni),
+        }
+    }
+}
+
+/// Check the structural invariants of `ssa`, returning every violation found:
+///  * The entry and exit nodes exist.
+///  * Every operand of a value is itself a valid value.
+///  * Every phi has between one operand and one operand per predecessor of its block.
+///    Predecessors providing the same value share an operand, so every reachable
+///    predecessor must instead be dominated by the definition of one of the operands.
+///  * Every operation (other than a constant) and every phi is contained in exactly one
+///    block.
+pub fn verify_structure(ssa: &SSAStorage) -> Vec<SsaError> {
+    let mut errors = Vec::new();
+    if ssa.entry_node().map_or(true, |n| !ssa.g.contains_node(n)) {
+        errors.push(SsaError::NoEntry);
+    }
+    if ssa.exit_node().map_or(true, |n| !ssa.g.contains_node(n)) {
+        errors.push(SsaError::NoExit);
+    }
+
+    let domtree = dominators::dominator_tree(ssa);
+    for node in ssa.values() {
+        for op in ssa.operands_of(node) {
+            if ssa.node_data(op).is_err() {
+                errors.push(SsaError::DanglingOperand(node, op));
+            }
+        }
+
+        let blocks = ssa.g
+            .edges(node)
+            .filter(|e| match *e.weight() {
+                EdgeData::ContainedInBB(_) => true,
+                _ => false,
+            })
+            .count();
+        // Constants are shared by the whole function and are not placed in a block.
+        let placed = match ssa.opcode(node) {
+            Some(MOpcode::OpConst(_)) => false,
+            Some(_) => true,
+            None => ssa.is_phi(node),
+        };
+        if blocks > 1 {
+            errors.push(SsaError::MultipleDefs(node, blocks));
+        } else if blocks == 0 && placed {
+            errors.push(SsaError::NoBlock(node));
+        }
+
+        if ssa.is_phi(node) {
+            let preds = ssa.block_for(node).map_or_else(Vec::new, |b| ssa.preds_of(b));
+            let ops = ssa.operands_of(node);
+            if ops.is_empty() || ops.len() > preds.len() {
+                errors.push(SsaError::PhiOperands(node, preds.len(), ops.len()));
+                continue;
+            }
+            // Values without a block (constants) are available everywhere.
+            for &pred in preds.iter().filter(|&&p| domtree.is_reachable(p)) {
+                let covered = ops.iter().any(|&op| {
+                    ssa.block_for(op).map_or(true, |b| domtree.dominates(b, pred))
+                });
+                if !covered {
+                    errors.push(SsaError::PhiMissingOperand(node, pred));
+                }
+            }
+        }
+    }
+    errors
+}
@@ -0,0 +1,5 @@
+int fixture_add(int a, int b);
+
+int main(int argc, char **argv) {
+  return fixture_add(argc, 1);
+}
@@ -0,0 +1,3 @@
+int fixture_add(int a, int b) {
+  return a + b;
+}